@@ -1,4 +1,5 @@
-use crate::params::ParamStyle;
+use crate::params::{ParamStructure, ParamStyle};
+use proc_macro2::{Delimiter, Group, TokenStream, TokenTree};
 use std::str::FromStr;
 use syn::{
     visit::{self, Visit},
@@ -11,8 +12,77 @@ pub struct RpcMethodAttribute {
     pub name: String,
     pub aliases: Vec<String>,
     pub kind: AttributeKind,
-    pub summary: String,
-    pub params_style: Option<ParamStyle>, // None means do not override the top level default
+    pub summary: Option<String>,
+    pub description: Option<String>,
+    /// Keep the doc comments of the method out of the document.
+    pub no_doc_extraction: bool,
+    pub timeout_ms: Option<u64>,
+    pub sla: Option<String>,
+    pub pagination: Option<Pagination>,
+    pub rate_limit: Option<RateLimit>,
+    pub idempotent: Option<bool>,
+    pub max_bytes: Vec<(syn::Ident, u32)>,
+    pub ranges: Vec<(syn::Ident, NumericRange)>,
+    pub patterns: Vec<(syn::Ident, String)>,
+    pub one_of: Vec<(syn::Ident, Vec<syn::Lit>)>,
+    pub param_examples: Vec<(syn::Ident, String)>,
+    pub param_example_refs: Vec<(syn::Ident, syn::LitStr)>,
+    /// The titles of the param schemas, which docs renderers display.
+    pub param_titles: Vec<(syn::Ident, String)>,
+    pub result_title: Option<String>,
+    pub tags: Vec<String>,
+    pub roles: Vec<String>,
+    pub errors: Vec<ErrorInput>,
+    /// Document the fields of the single struct param as the params.
+    pub expand_params: bool,
+    /// Document all params as the properties of a single object param.
+    pub params_as_object: bool,
+    /// Overrides the trait level default `paramStructure`.
+    pub param_structure: Option<ParamStructure>,
+    /// The method changes no state, so it may be routed to a replica.
+    pub readonly: bool,
+    /// Reject by-name params the method does not declare.
+    pub deny_unknown_params: bool,
+    /// Take the `shared_config` param of the trait as the last param.
+    pub with_config: bool,
+}
+
+/// An error of `errors((code = -32010, message = "..", data = "Type"))`.
+#[derive(Clone, Debug)]
+pub struct ErrorInput {
+    pub code: i64,
+    pub message: String,
+    /// The type of the `data` payload, documented in the components.
+    pub data: Option<syn::Type>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Pagination {
+    pub cursor_param: Option<String>,
+    pub limit_param: Option<String>,
+    pub result_field: Option<String>,
+}
+
+/// `rate_limit(requests = 10, per_ms = 1000)`, how often clients may call.
+#[derive(Clone, Debug)]
+pub struct RateLimit {
+    pub requests: u32,
+    pub per_ms: u64,
+}
+
+/// The bounds of a `range(param = "min..max")` constraint.
+#[derive(Clone, Debug)]
+pub struct NumericRange {
+    pub minimum: Option<Bound>,
+    pub maximum: Option<Bound>,
+    pub maximum_exclusive: bool,
+}
+
+/// A bound of a `range`, an integer literal staying an integer in the schema.
+#[derive(Clone, Copy, Debug)]
+pub enum Bound {
+    Integer(i128),
+    Float(f64),
 }
 
 #[derive(Clone, Debug)]
@@ -21,45 +91,105 @@ pub enum AttributeKind {
         has_metadata: bool,
         returns: Option<String>,
     },
+    PubSub {
+        subscription_name: String,
+        kind: PubSubMethodKind,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PubSubMethodKind {
+    Subscribe,
+    Unsubscribe,
 }
 
-const RPC_ATTR_NAME: &str = "rpc";
+pub const RPC_ATTR_NAME: &str = "rpc";
+const PUB_SUB_ATTR_NAME: &str = "pubsub";
+const SUBSCRIPTION_NAME_KEY: &str = "subscription";
+const SUBSCRIBE_META_WORD: &str = "subscribe";
+const UNSUBSCRIBE_META_WORD: &str = "unsubscribe";
 const RPC_NAME_KEY: &str = "name";
-const ALIASES_KEY: &str = "alias";
+pub const ALIASES_KEY: &str = "alias";
 const METADATA_META_WORD: &str = "meta";
 const RAW_PARAMS_META_WORD: &str = "raw_params"; // to be deprecated and replaced with `params = "raw"`
 const RETURNS_META_WORD: &str = "returns";
+const RESULT_KEY: &str = "result";
+const PARAM_STRUCTURE_KEY: &str = "param_structure";
 const PARAMS_STYLE_KEY: &str = "params";
 const SUMMARY: &str = "summary";
-const MULTIPLE_RPC_ATTRIBUTES_ERR: &str = "Expected only a single rpc attribute per method";
+const DESCRIPTION: &str = "description";
+const TIMEOUT_MS_KEY: &str = "timeout_ms";
+const SLA_KEY: &str = "sla";
+const PAGINATED_KEY: &str = "paginated";
+const CURSOR_PARAM_KEY: &str = "cursor_param";
+const LIMIT_PARAM_KEY: &str = "limit_param";
+const RESULT_FIELD_KEY: &str = "result_field";
+const RATE_LIMIT_KEY: &str = "rate_limit";
+const RATE_LIMIT_REQUESTS_KEY: &str = "requests";
+const RATE_LIMIT_PER_MS_KEY: &str = "per_ms";
+const IDEMPOTENT_META_WORD: &str = "idempotent";
+const SIDE_EFFECTS_META_WORD: &str = "side_effects";
+const NO_DOC_EXTRACTION_META_WORD: &str = "no_doc_extraction";
+const EXPAND_PARAMS_META_WORD: &str = "expand_params";
+const PARAMS_AS_OBJECT_META_WORD: &str = "params_as_object";
+const READONLY_META_WORD: &str = "readonly";
+const DENY_UNKNOWN_PARAMS_META_WORD: &str = "deny_unknown_params";
+const WITH_CONFIG_META_WORD: &str = "with_config";
+const MAX_BYTES_KEY: &str = "max_bytes";
+const RANGE_KEY: &str = "range";
+const PATTERN_KEY: &str = "pattern";
+const ONE_OF_KEY: &str = "one_of";
+const PARAM_EXAMPLE_KEY: &str = "param_example";
+const PARAM_EXAMPLE_REF_KEY: &str = "param_example_ref";
+const PARAM_TITLE_KEY: &str = "param_title";
+const RESULT_TITLE_KEY: &str = "result_title";
+const TAGS_KEY: &str = "tags";
+const ROLES_KEY: &str = "roles";
+const ERRORS_KEY: &str = "errors";
+const ERROR_KEY: &str = "error";
+const ERROR_CODE_KEY: &str = "code";
+const ERROR_MESSAGE_KEY: &str = "message";
+const ERROR_DATA_KEY: &str = "data";
+const MULTIPLE_RPC_ATTRIBUTES_ERR: &str =
+    "Expected only a single pubsub attribute per method, only rpc attributes can be stacked";
 const INVALID_ATTR_PARAM_NAMES_ERR: &str = "Invalid attribute parameter(s):";
 const MISSING_NAME_ERR: &str = "rpc attribute should have a name e.g. `name = \"method_name\"`";
+const MISSING_SUB_NAME_ERR: &str = "pubsub attribute should have a subscription name";
+const BOTH_SUB_AND_UNSUB_ERR: &str =
+    "pubsub attribute annotated with both subscribe and unsubscribe";
+const NEITHER_SUB_OR_UNSUB_ERR: &str =
+    "pubsub attribute not annotated with either subscribe or unsubscribe";
 
 impl RpcMethodAttribute {
-    pub fn parse_attr(method: &syn::TraitItemMethod) -> Result<Option<RpcMethodAttribute>> {
+    /// The attributes of a documented method, several when stacked `#[rpc]`
+    /// attributes serve more than one wire method with it.
+    pub fn parse_attrs(method: &syn::TraitItemMethod) -> Result<Vec<RpcMethodAttribute>> {
         let output = &method.sig.output;
         let attrs = method
             .attrs
             .iter()
-            .filter_map(|attr| Self::parse_meta(attr, &output, method))
+            .filter_map(|attr| Self::parse_meta(attr, output, method))
             .collect::<Result<Vec<_>>>()?;
 
-        if attrs.len() <= 1 {
-            Ok(attrs.first().cloned())
-        } else {
+        let is_pubsub =
+            |attr: &RpcMethodAttribute| matches!(attr.kind, AttributeKind::PubSub { .. });
+        if attrs.len() > 1 && attrs.iter().any(is_pubsub) {
             Err(Error::new_spanned(method, MULTIPLE_RPC_ATTRIBUTES_ERR))
+        } else {
+            Ok(attrs)
         }
     }
 
     fn parse_meta(
         attr: &syn::Attribute,
         output: &syn::ReturnType,
-        method: &syn::TraitItemMethod
+        method: &syn::TraitItemMethod,
     ) -> Option<Result<RpcMethodAttribute>> {
-        match attr.parse_meta().and_then(validate_attribute_meta) {
+        match parse_meta(attr).and_then(validate_attribute_meta) {
             Ok(ref meta) => {
-                let attr_kind = match path_to_str(meta.path()).as_ref().map(String::as_str) {
+                let attr_kind = match path_to_str(meta.path()).as_deref() {
                     Some(RPC_ATTR_NAME) => Some(Self::parse_rpc(meta, output)),
+                    Some(PUB_SUB_ATTR_NAME) => Some(Self::parse_pubsub(meta)),
                     _ => None,
                 };
                 attr_kind.map(|kind| {
@@ -67,24 +197,176 @@ impl RpcMethodAttribute {
                         get_meta_list(meta)
                             .and_then(|ml| get_name_value(RPC_NAME_KEY, ml))
                             .or(Some(method.sig.ident.to_string()))
-                            .map_or_else(||Err(Error::new_spanned(attr, MISSING_NAME_ERR)), |name| {
-                                let meta = get_meta_list(&meta);
-                                let aliases =
-                                    meta.map_or(Vec::new(), |ml| get_aliases(ml));
-                                let summary = meta.map_or(String::new(), |ml| get_name_value(SUMMARY, ml).unwrap_or_default());
-                                let params_style = meta.map_or(Ok(None), |ml| {
-                                    get_params_style(ml).map(|s| Some(s))
-                                })?;
-                            
-                                Ok(RpcMethodAttribute {
-                                    attr: attr.clone(),
-                                    name,
-                                    aliases,
-                                    kind,
-                                    params_style,
-                                    summary
-                                })
-                            })
+                            .map_or_else(
+                                || Err(Error::new_spanned(attr, MISSING_NAME_ERR)),
+                                |name| {
+                                    let meta = get_meta_list(meta);
+                                    let aliases = meta.map_or(Vec::new(), get_aliases);
+                                    let summary = meta.and_then(|ml| get_name_value(SUMMARY, ml));
+                                    let description =
+                                        meta.and_then(|ml| get_name_value(DESCRIPTION, ml));
+                                    let no_doc_extraction = meta.is_some_and(|ml| {
+                                        has_meta_word(NO_DOC_EXTRACTION_META_WORD, ml)
+                                    });
+                                    let params_style = meta.map_or(Ok(None), get_params_style)?;
+                                    let timeout_ms = meta.map_or(Ok(None), |ml| {
+                                        get_name_value_int(TIMEOUT_MS_KEY, ml)
+                                    })?;
+                                    let sla = meta.and_then(|ml| get_name_value(SLA_KEY, ml));
+                                    let pagination = meta.map_or(Ok(None), get_pagination)?;
+                                    let rate_limit = meta.map_or(Ok(None), get_rate_limit)?;
+                                    let idempotent = meta.map_or(Ok(None), get_idempotent)?;
+                                    let max_bytes = meta.map_or(Ok(Vec::new()), |ml| {
+                                        get_param_values(MAX_BYTES_KEY, ml, |lit| match lit {
+                                            syn::Lit::Int(int) => int.base10_parse(),
+                                            lit => {
+                                                Err(Error::new_spanned(lit, "Expected an integer"))
+                                            }
+                                        })
+                                    })?;
+                                    let ranges = meta.map_or(Ok(Vec::new()), |ml| {
+                                        get_param_values(RANGE_KEY, ml, parse_range)
+                                    })?;
+                                    let patterns = meta.map_or(Ok(Vec::new()), |ml| {
+                                        get_param_values(PATTERN_KEY, ml, parse_pattern)
+                                    })?;
+                                    let one_of = meta.map_or(Ok(Vec::new()), get_one_of)?;
+                                    let tags =
+                                        meta.map_or(Vec::new(), |ml| get_str_list(TAGS_KEY, ml));
+                                    let roles =
+                                        meta.map_or(Vec::new(), |ml| get_str_list(ROLES_KEY, ml));
+                                    let errors = meta.map_or(Ok(Vec::new()), get_errors)?;
+                                    let param_structure =
+                                        meta.map_or(Ok(None), get_param_structure)?;
+                                    // the `params` key of jsonrpc-derive implies the structure
+                                    let param_structure = match (
+                                        param_structure,
+                                        params_style.and_then(ParamStyle::param_structure),
+                                    ) {
+                                        (Some(structure), Some(implied))
+                                            if structure != implied =>
+                                        {
+                                            let msg = format!(
+                                                "`{}` contradicts `{}`",
+                                                PARAM_STRUCTURE_KEY, PARAMS_STYLE_KEY
+                                            );
+                                            return Err(Error::new_spanned(attr, msg));
+                                        }
+                                        (structure, implied) => structure.or(implied),
+                                    };
+                                    let readonly = meta
+                                        .is_some_and(|ml| has_meta_word(READONLY_META_WORD, ml));
+                                    if readonly && idempotent == Some(false) {
+                                        let msg = format!(
+                                            "A `{}` method has no `{}`",
+                                            READONLY_META_WORD, SIDE_EFFECTS_META_WORD
+                                        );
+                                        return Err(Error::new_spanned(attr, msg));
+                                    }
+                                    let deny_unknown_params = meta.is_some_and(|ml| {
+                                        has_meta_word(DENY_UNKNOWN_PARAMS_META_WORD, ml)
+                                    });
+                                    let with_config = meta
+                                        .is_some_and(|ml| has_meta_word(WITH_CONFIG_META_WORD, ml));
+                                    let expand_params = meta.is_some_and(|ml| {
+                                        has_meta_word(EXPAND_PARAMS_META_WORD, ml)
+                                    });
+                                    let params_as_object = meta.is_some_and(|ml| {
+                                        has_meta_word(PARAMS_AS_OBJECT_META_WORD, ml)
+                                    });
+                                    if expand_params && params_as_object {
+                                        let msg = format!(
+                                            "Expected only one of `{}` and `{}`",
+                                            EXPAND_PARAMS_META_WORD, PARAMS_AS_OBJECT_META_WORD
+                                        );
+                                        return Err(Error::new_spanned(attr, msg));
+                                    }
+                                    let reshaped = if expand_params {
+                                        Some((EXPAND_PARAMS_META_WORD, ParamStructure::ByName))
+                                    } else if params_as_object {
+                                        Some((
+                                            PARAMS_AS_OBJECT_META_WORD,
+                                            ParamStructure::ByPosition,
+                                        ))
+                                    } else {
+                                        None
+                                    };
+                                    if let (Some((word, reshaped)), Some(structure)) =
+                                        (reshaped, param_structure)
+                                    {
+                                        if structure != reshaped {
+                                            let msg = format!(
+                                                "`{}` documents the params {}",
+                                                word,
+                                                reshaped.describe()
+                                            );
+                                            return Err(Error::new_spanned(attr, msg));
+                                        }
+                                    }
+                                    let param_examples = meta.map_or(Ok(Vec::new()), |ml| {
+                                        get_param_values(PARAM_EXAMPLE_KEY, ml, |lit| match lit {
+                                            syn::Lit::Str(s) => Ok(s.value()),
+                                            lit => Err(Error::new_spanned(
+                                                lit,
+                                                "Expected a string literal",
+                                            )),
+                                        })
+                                    })?;
+                                    let param_example_refs = meta.map_or(Ok(Vec::new()), |ml| {
+                                        get_param_values(PARAM_EXAMPLE_REF_KEY, ml, |lit| match lit
+                                        {
+                                            syn::Lit::Str(s) => Ok(s.clone()),
+                                            lit => Err(Error::new_spanned(
+                                                lit,
+                                                "Expected a string literal",
+                                            )),
+                                        })
+                                    })?;
+                                    let param_titles = meta.map_or(Ok(Vec::new()), |ml| {
+                                        get_param_values(PARAM_TITLE_KEY, ml, |lit| match lit {
+                                            syn::Lit::Str(s) => Ok(s.value()),
+                                            lit => Err(Error::new_spanned(
+                                                lit,
+                                                "Expected a string literal",
+                                            )),
+                                        })
+                                    })?;
+                                    let result_title =
+                                        meta.and_then(|ml| get_name_value(RESULT_TITLE_KEY, ml));
+
+                                    Ok(RpcMethodAttribute {
+                                        attr: attr.clone(),
+                                        name,
+                                        aliases,
+                                        kind,
+                                        summary,
+                                        description,
+                                        no_doc_extraction,
+                                        timeout_ms,
+                                        sla,
+                                        pagination,
+                                        rate_limit,
+                                        idempotent,
+                                        max_bytes,
+                                        ranges,
+                                        patterns,
+                                        one_of,
+                                        param_examples,
+                                        param_example_refs,
+                                        param_titles,
+                                        result_title,
+                                        tags,
+                                        roles,
+                                        errors,
+                                        expand_params,
+                                        params_as_object,
+                                        param_structure,
+                                        readonly,
+                                        deny_unknown_params,
+                                        with_config,
+                                    })
+                                },
+                            )
                     })
                 })
             }
@@ -94,20 +376,29 @@ impl RpcMethodAttribute {
 
     fn parse_rpc(meta: &syn::Meta, output: &syn::ReturnType) -> Result<AttributeKind> {
         let has_metadata =
-            get_meta_list(meta).map_or(false, |ml| has_meta_word(METADATA_META_WORD, ml));
-        let returns = get_meta_list(meta).map_or(None, |ml| get_name_value(RETURNS_META_WORD, ml));
+            get_meta_list(meta).is_some_and(|ml| has_meta_word(METADATA_META_WORD, ml));
+        let returns = get_meta_list(meta).and_then(|ml| get_name_value(RETURNS_META_WORD, ml));
+        let result = get_meta_list(meta).and_then(|ml| get_name_value(RESULT_KEY, ml));
+        if returns.is_some() && result.is_some() {
+            let msg = format!(
+                "Expected only one of `{}` and `{}`",
+                RETURNS_META_WORD, RESULT_KEY
+            );
+            return Err(syn::Error::new_spanned(meta, msg));
+        }
+        // `result` names the documented shape of an erased return type
+        let returns = returns.or(result);
         let is_notification = match output {
             syn::ReturnType::Default => true,
-            syn::ReturnType::Type(_, ret) => match **ret {
-                syn::Type::Tuple(ref tup) if tup.elems.empty_or_trailing() => true,
-                _ => false,
-            },
+            syn::ReturnType::Type(_, ret) => {
+                matches!(**ret, syn::Type::Tuple(ref tup) if tup.elems.empty_or_trailing())
+            }
         };
 
         if is_notification && returns.is_some() {
             return Err(syn::Error::new_spanned(
                 output,
-                &"Notifications must return ()",
+                "Notifications must return ()",
             ));
         }
 
@@ -116,6 +407,86 @@ impl RpcMethodAttribute {
             returns,
         })
     }
+
+    fn parse_pubsub(meta: &syn::Meta) -> Result<AttributeKind> {
+        let name_and_list = get_meta_list(meta)
+            .and_then(|ml| get_name_value(SUBSCRIPTION_NAME_KEY, ml).map(|name| (name, ml)));
+
+        name_and_list.map_or(
+            Err(Error::new_spanned(meta, MISSING_SUB_NAME_ERR)),
+            |(sub_name, ml)| {
+                let is_subscribe = has_meta_word(SUBSCRIBE_META_WORD, ml);
+                let is_unsubscribe = has_meta_word(UNSUBSCRIBE_META_WORD, ml);
+                let kind = match (is_subscribe, is_unsubscribe) {
+                    (true, false) => Ok(PubSubMethodKind::Subscribe),
+                    (false, true) => Ok(PubSubMethodKind::Unsubscribe),
+                    (true, true) => Err(Error::new_spanned(meta, BOTH_SUB_AND_UNSUB_ERR)),
+                    (false, false) => Err(Error::new_spanned(meta, NEITHER_SUB_OR_UNSUB_ERR)),
+                };
+                kind.map(|kind| AttributeKind::PubSub {
+                    subscription_name: sub_name,
+                    kind,
+                })
+            },
+        )
+    }
+}
+
+/// `syn::Attribute::parse_meta`, also accepting `key = [a, b]` which a meta
+/// cannot hold, by reading it as `key(a, b)`.
+fn parse_meta(attr: &syn::Attribute) -> Result<syn::Meta> {
+    let path = &attr.path;
+    let tokens = arrays_to_lists(attr.tokens.clone());
+    syn::parse2(quote::quote!(#path #tokens))
+}
+
+fn arrays_to_lists(tokens: TokenStream) -> TokenStream {
+    let mut output = Vec::new();
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Punct(punct) if punct.as_char() == '=' => match tokens.peek() {
+                Some(TokenTree::Group(array)) if array.delimiter() == Delimiter::Bracket => {
+                    let mut list =
+                        Group::new(Delimiter::Parenthesis, arrays_to_lists(array.stream()));
+                    list.set_span(array.span());
+                    output.push(TokenTree::Group(list));
+                    tokens.next();
+                }
+                _ => output.push(TokenTree::Punct(punct)),
+            },
+            TokenTree::Group(group) => {
+                let mut stream = arrays_to_lists(group.stream());
+                // the errors are bare tuples, which are no meta items
+                if matches!(output.last(), Some(TokenTree::Ident(ident)) if ident == ERRORS_KEY) {
+                    stream = name_tuples(stream, ERROR_KEY);
+                }
+                let mut rewritten = Group::new(group.delimiter(), stream);
+                rewritten.set_span(group.span());
+                output.push(TokenTree::Group(rewritten));
+            }
+            token => output.push(token),
+        }
+    }
+    output.into_iter().collect()
+}
+
+/// Rewrites the bare `(..)` items of a list to `name(..)`.
+fn name_tuples(tokens: TokenStream, name: &str) -> TokenStream {
+    let mut output = Vec::new();
+    for token in tokens {
+        if let TokenTree::Group(group) = &token {
+            let named = matches!(output.last(), Some(TokenTree::Ident(_)));
+            if group.delimiter() == Delimiter::Parenthesis && !named {
+                output.push(TokenTree::Ident(proc_macro2::Ident::new(
+                    name,
+                    group.span(),
+                )));
+            }
+        }
+        output.push(token);
+    }
+    output.into_iter().collect()
 }
 
 fn validate_attribute_meta(meta: syn::Meta) -> Result<syn::Meta> {
@@ -141,19 +512,81 @@ fn validate_attribute_meta(meta: syn::Meta) -> Result<syn::Meta> {
     visit::visit_meta(&mut visitor, &meta);
 
     let ident = path_to_str(meta.path());
-    match ident.as_ref().map(String::as_str) {
+    match ident.as_deref() {
         Some(RPC_ATTR_NAME) => {
             validate_idents(
                 &meta,
                 &visitor.meta_words,
-                &[METADATA_META_WORD, RAW_PARAMS_META_WORD],
+                &[
+                    METADATA_META_WORD,
+                    RAW_PARAMS_META_WORD,
+                    IDEMPOTENT_META_WORD,
+                    SIDE_EFFECTS_META_WORD,
+                    NO_DOC_EXTRACTION_META_WORD,
+                    EXPAND_PARAMS_META_WORD,
+                    PARAMS_AS_OBJECT_META_WORD,
+                    READONLY_META_WORD,
+                    DENY_UNKNOWN_PARAMS_META_WORD,
+                    WITH_CONFIG_META_WORD,
+                ],
+            )?;
+            validate_idents(
+                &meta,
+                &visitor.name_value_names,
+                &[
+                    RPC_NAME_KEY,
+                    RETURNS_META_WORD,
+                    RESULT_KEY,
+                    PARAM_STRUCTURE_KEY,
+                    PARAMS_STYLE_KEY,
+                    SUMMARY,
+                    DESCRIPTION,
+                    TIMEOUT_MS_KEY,
+                    SLA_KEY,
+                    RESULT_TITLE_KEY,
+                ],
+            )?;
+            validate_idents(
+                &meta,
+                &visitor.meta_list_names,
+                &[
+                    ALIASES_KEY,
+                    PAGINATED_KEY,
+                    RATE_LIMIT_KEY,
+                    MAX_BYTES_KEY,
+                    RANGE_KEY,
+                    PATTERN_KEY,
+                    ONE_OF_KEY,
+                    PARAM_EXAMPLE_KEY,
+                    PARAM_EXAMPLE_REF_KEY,
+                    PARAM_TITLE_KEY,
+                    TAGS_KEY,
+                    ROLES_KEY,
+                    ERRORS_KEY,
+                ],
+            )
+        }
+        Some(PUB_SUB_ATTR_NAME) => {
+            validate_idents(
+                &meta,
+                &visitor.meta_words,
+                &[
+                    SUBSCRIBE_META_WORD,
+                    UNSUBSCRIBE_META_WORD,
+                    RAW_PARAMS_META_WORD,
+                    NO_DOC_EXTRACTION_META_WORD,
+                ],
             )?;
             validate_idents(
                 &meta,
                 &visitor.name_value_names,
-                &[RPC_NAME_KEY, RETURNS_META_WORD, PARAMS_STYLE_KEY, SUMMARY],
+                &[SUBSCRIPTION_NAME_KEY, RPC_NAME_KEY, SUMMARY, DESCRIPTION],
             )?;
-            validate_idents(&meta, &visitor.meta_list_names, &[ALIASES_KEY])
+            validate_idents(
+                &meta,
+                &visitor.meta_list_names,
+                &[ALIASES_KEY, TAGS_KEY, ROLES_KEY, ERRORS_KEY],
+            )
         }
         _ => Ok(meta), // ignore other attributes - compiler will catch unknown ones
     }
@@ -205,10 +638,32 @@ fn get_name_value(key: &str, ml: &syn::MetaList) -> Option<String> {
     })
 }
 
+fn get_name_value_int<N>(key: &str, ml: &syn::MetaList) -> Result<Option<N>>
+where
+    N: FromStr,
+    N::Err: std::fmt::Display,
+{
+    ml.nested
+        .iter()
+        .find_map(|nested| match nested {
+            syn::NestedMeta::Meta(syn::Meta::NameValue(mnv)) if path_eq_str(&mnv.path, key) => {
+                Some(match mnv.lit {
+                    syn::Lit::Int(ref lit) => lit.base10_parse(),
+                    ref lit => Err(Error::new_spanned(
+                        lit,
+                        format!("Expected an integer for `{}`", key),
+                    )),
+                })
+            }
+            _ => None,
+        })
+        .transpose()
+}
+
 fn has_meta_word(word: &str, ml: &syn::MetaList) -> bool {
     ml.nested.iter().any(|nested| {
         if let syn::NestedMeta::Meta(syn::Meta::Path(p)) = nested {
-            path_eq_str(&p, word)
+            path_eq_str(p, word)
         } else {
             false
         }
@@ -216,41 +671,324 @@ fn has_meta_word(word: &str, ml: &syn::MetaList) -> bool {
 }
 
 fn get_aliases(ml: &syn::MetaList) -> Vec<String> {
-    ml.nested
-        .iter()
-        .find_map(|nested| {
-            if let syn::NestedMeta::Meta(syn::Meta::List(list)) = nested {
-                if path_eq_str(&list.path, ALIASES_KEY) {
-                    Some(list)
+    get_str_list(ALIASES_KEY, ml)
+}
+
+fn get_str_list(key: &str, ml: &syn::MetaList) -> Vec<String> {
+    get_meta_list_named(key, ml).map_or(Vec::new(), |list| {
+        list.nested
+            .iter()
+            .filter_map(|nm| {
+                if let syn::NestedMeta::Lit(syn::Lit::Str(value)) = nm {
+                    Some(value.value())
                 } else {
                     None
                 }
-            } else {
-                None
+            })
+            .collect()
+    })
+}
+
+fn get_meta_list_named<'a>(key: &str, ml: &'a syn::MetaList) -> Option<&'a syn::MetaList> {
+    ml.nested.iter().find_map(|nested| {
+        if let syn::NestedMeta::Meta(syn::Meta::List(list)) = nested {
+            if path_eq_str(&list.path, key) {
+                return Some(list);
+            }
+        }
+        None
+    })
+}
+
+fn get_pagination(ml: &syn::MetaList) -> Result<Option<Pagination>> {
+    let list = match get_meta_list_named(PAGINATED_KEY, ml) {
+        Some(list) => list,
+        None => return Ok(None),
+    };
+    validate_idents(
+        &syn::Meta::List(list.clone()),
+        &list
+            .nested
+            .iter()
+            .filter_map(|nested| match nested {
+                syn::NestedMeta::Meta(meta) => path_to_str(meta.path()),
+                _ => None,
+            })
+            .collect::<Vec<_>>(),
+        &[CURSOR_PARAM_KEY, LIMIT_PARAM_KEY, RESULT_FIELD_KEY],
+    )?;
+    Ok(Some(Pagination {
+        cursor_param: get_name_value(CURSOR_PARAM_KEY, list),
+        limit_param: get_name_value(LIMIT_PARAM_KEY, list),
+        result_field: get_name_value(RESULT_FIELD_KEY, list),
+    }))
+}
+
+fn get_rate_limit(ml: &syn::MetaList) -> Result<Option<RateLimit>> {
+    let list = match get_meta_list_named(RATE_LIMIT_KEY, ml) {
+        Some(list) => list,
+        None => return Ok(None),
+    };
+    validate_idents(
+        &syn::Meta::List(list.clone()),
+        &list
+            .nested
+            .iter()
+            .filter_map(|nested| match nested {
+                syn::NestedMeta::Meta(meta) => path_to_str(meta.path()),
+                _ => None,
+            })
+            .collect::<Vec<_>>(),
+        &[RATE_LIMIT_REQUESTS_KEY, RATE_LIMIT_PER_MS_KEY],
+    )?;
+    let requests = get_name_value_int(RATE_LIMIT_REQUESTS_KEY, list)?;
+    let per_ms = get_name_value_int(RATE_LIMIT_PER_MS_KEY, list)?;
+    // no token would ever be refilled
+    let zero = list.nested.iter().find(|nested| match nested {
+        syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue {
+            lit: syn::Lit::Int(int),
+            ..
+        })) => int.base10_digits() == "0",
+        _ => false,
+    });
+    if let Some(zero) = zero {
+        return Err(Error::new_spanned(
+            zero,
+            "rate_limit needs a `requests` and a `per_ms` above 0",
+        ));
+    }
+    match (requests, per_ms) {
+        (Some(requests), Some(per_ms)) => Ok(Some(RateLimit { requests, per_ms })),
+        _ => Err(Error::new_spanned(
+            list,
+            "rate_limit should have both `requests` and `per_ms` e.g. `rate_limit(requests = 10, per_ms = 1000)`",
+        )),
+    }
+}
+
+/// The `param = value` items of the list `key`, one per constrained param.
+fn get_param_values<T>(
+    key: &str,
+    ml: &syn::MetaList,
+    parse: impl Fn(&syn::Lit) -> Result<T>,
+) -> Result<Vec<(syn::Ident, T)>> {
+    let list = match get_meta_list_named(key, ml) {
+        Some(list) => list,
+        None => return Ok(Vec::new()),
+    };
+    list.nested
+        .iter()
+        .map(|nested| match nested {
+            syn::NestedMeta::Meta(syn::Meta::NameValue(mnv)) if mnv.path.get_ident().is_some() => {
+                let param = mnv.path.get_ident().cloned().unwrap();
+                Ok((param, parse(&mnv.lit)?))
+            }
+            other => Err(Error::new_spanned(
+                other,
+                format!("Expected `{}(param = value)`", key),
+            )),
+        })
+        .collect()
+}
+
+fn parse_range(lit: &syn::Lit) -> Result<NumericRange> {
+    let invalid = || Error::new_spanned(lit, "Expected a range e.g. `\"1..=1000\"`");
+    let range = match lit {
+        syn::Lit::Str(s) => s.parse::<syn::ExprRange>().map_err(|_| invalid())?,
+        _ => return Err(invalid()),
+    };
+    let bound = |expr: &Option<Box<syn::Expr>>| {
+        expr.as_deref()
+            .map(|expr| range_bound(expr).ok_or_else(invalid))
+            .transpose()
+    };
+    let minimum = bound(&range.from)?;
+    let maximum = bound(&range.to)?;
+    if minimum.is_none() && maximum.is_none() {
+        return Err(invalid());
+    }
+    let representable = |bound: &Bound| match *bound {
+        Bound::Integer(bound) => (i128::from(i64::MIN)..=i128::from(u64::MAX)).contains(&bound),
+        Bound::Float(bound) => bound.is_finite(),
+    };
+    if !minimum.iter().chain(&maximum).all(representable) {
+        return Err(Error::new_spanned(
+            lit,
+            "The bounds of a range must fit in an i64 or u64",
+        ));
+    }
+    Ok(NumericRange {
+        minimum,
+        maximum,
+        maximum_exclusive: matches!(range.limits, syn::RangeLimits::HalfOpen(_)),
+    })
+}
+
+fn get_one_of(ml: &syn::MetaList) -> Result<Vec<(syn::Ident, Vec<syn::Lit>)>> {
+    let list = match get_meta_list_named(ONE_OF_KEY, ml) {
+        Some(list) => list,
+        None => return Ok(Vec::new()),
+    };
+    let expected = || format!("Expected `{}(param = [\"value\", ..])`", ONE_OF_KEY);
+    list.nested
+        .iter()
+        .map(|nested| match nested {
+            syn::NestedMeta::Meta(syn::Meta::List(values)) if values.path.get_ident().is_some() => {
+                let param = values.path.get_ident().cloned().unwrap();
+                let values = values
+                    .nested
+                    .iter()
+                    .map(|value| match value {
+                        syn::NestedMeta::Lit(
+                            lit @ (syn::Lit::Str(_)
+                            | syn::Lit::Int(_)
+                            | syn::Lit::Float(_)
+                            | syn::Lit::Bool(_)),
+                        ) => Ok(lit.clone()),
+                        other => Err(Error::new_spanned(other, "Expected a literal")),
+                    })
+                    .collect::<Result<_>>()?;
+                Ok((param, values))
             }
+            other => Err(Error::new_spanned(other, expected())),
         })
-        .map_or(Vec::new(), |list| {
-            list.nested
-                .iter()
-                .filter_map(|nm| {
-                    if let syn::NestedMeta::Lit(syn::Lit::Str(alias)) = nm {
-                        Some(alias.value())
-                    } else {
-                        None
+        .collect()
+}
+
+fn parse_pattern(lit: &syn::Lit) -> Result<String> {
+    match lit {
+        syn::Lit::Str(s) => {
+            let pattern = s.value();
+            regex_syntax::Parser::new()
+                .parse(&pattern)
+                .map_err(|e| Error::new_spanned(lit, format!("Invalid pattern: {}", e)))?;
+            Ok(pattern)
+        }
+        _ => Err(Error::new_spanned(lit, "Expected a string literal")),
+    }
+}
+
+fn range_bound(expr: &syn::Expr) -> Option<Bound> {
+    match expr {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(int),
+            ..
+        }) => int.base10_parse().ok().map(Bound::Integer),
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Float(float),
+            ..
+        }) => float.base10_parse().ok().map(Bound::Float),
+        syn::Expr::Unary(syn::ExprUnary {
+            op: syn::UnOp::Neg(_),
+            expr,
+            ..
+        }) => match range_bound(expr)? {
+            Bound::Integer(bound) => Some(Bound::Integer(-bound)),
+            Bound::Float(bound) => Some(Bound::Float(-bound)),
+        },
+        _ => None,
+    }
+}
+
+fn get_errors(ml: &syn::MetaList) -> Result<Vec<ErrorInput>> {
+    let errors = match get_meta_list_named(ERRORS_KEY, ml) {
+        Some(errors) => errors,
+        None => return Ok(Vec::new()),
+    };
+    errors
+        .nested
+        .iter()
+        .map(|nested| {
+            let error = match nested {
+                syn::NestedMeta::Meta(syn::Meta::List(error))
+                    if path_eq_str(&error.path, ERROR_KEY) =>
+                {
+                    error
+                }
+                other => {
+                    return Err(Error::new_spanned(
+                        other,
+                        "Expected `(code = .., message = \"..\")`",
+                    ))
+                }
+            };
+            let (mut code, mut message, mut data) = (None, None, None);
+            for nested in &error.nested {
+                match nested {
+                    syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) => {
+                        match (path_to_str(&nv.path).as_deref(), &nv.lit) {
+                            (Some(ERROR_CODE_KEY), syn::Lit::Int(int)) => {
+                                code = Some(int.base10_parse()?)
+                            }
+                            (Some(ERROR_MESSAGE_KEY), syn::Lit::Str(s)) => {
+                                message = Some(s.value())
+                            }
+                            (Some(ERROR_DATA_KEY), syn::Lit::Str(s)) => data = Some(s.parse()?),
+                            _ => {
+                                return Err(Error::new_spanned(
+                                    nv,
+                                    "Expected an integer `code`, a `message` or a `data` type",
+                                ))
+                            }
+                        }
                     }
-                })
-                .collect()
+                    other => return Err(Error::new_spanned(other, "Expected `key = value`")),
+                }
+            }
+            let code = code.ok_or_else(|| {
+                Error::new_spanned(error, "error should have a code e.g. `code = -32010`")
+            })?;
+            let message = message.ok_or_else(|| {
+                Error::new_spanned(
+                    error,
+                    "error should have a message e.g. `message = \"Tx failed\"`",
+                )
+            })?;
+            Ok(ErrorInput {
+                code,
+                message,
+                data,
+            })
         })
+        .collect()
+}
+
+fn get_idempotent(ml: &syn::MetaList) -> Result<Option<bool>> {
+    match (
+        has_meta_word(IDEMPOTENT_META_WORD, ml),
+        has_meta_word(SIDE_EFFECTS_META_WORD, ml),
+    ) {
+        (true, true) => Err(Error::new_spanned(
+            ml,
+            format!(
+                "Expected only one of '{}, {}'",
+                IDEMPOTENT_META_WORD, SIDE_EFFECTS_META_WORD
+            ),
+        )),
+        (true, false) => Ok(Some(true)),
+        (false, true) => Ok(Some(false)),
+        (false, false) => Ok(None),
+    }
+}
+
+fn get_param_structure(ml: &syn::MetaList) -> Result<Option<ParamStructure>> {
+    get_name_value(PARAM_STRUCTURE_KEY, ml).map_or(Ok(None), |s| {
+        s.parse()
+            .map(Some)
+            .map_err(|e: String| Error::new_spanned(ml, e))
+    })
 }
 
-fn get_params_style(ml: &syn::MetaList) -> Result<ParamStyle> {
-    get_name_value(PARAMS_STYLE_KEY, ml).map_or(Ok(ParamStyle::default()), |s| {
-        ParamStyle::from_str(&s).map_err(|e| Error::new_spanned(ml, e))
+fn get_params_style(ml: &syn::MetaList) -> Result<Option<ParamStyle>> {
+    get_name_value(PARAMS_STYLE_KEY, ml).map_or(Ok(None), |s| {
+        ParamStyle::from_str(&s)
+            .map(Some)
+            .map_err(|e| Error::new_spanned(ml, e))
     })
 }
 
 pub fn path_eq_str(path: &syn::Path, s: &str) -> bool {
-    path.get_ident().map_or(false, |i| i == s)
+    path.get_ident().is_some_and(|i| i == s)
 }
 
 fn path_to_str(path: &syn::Path) -> Option<String> {
@@ -0,0 +1,204 @@
+//! Warnings about the changes of a trait that break the clients of a baseline
+//! document, e.g. the one the dump binary wrote for the last release.
+//!
+//! Proc macros cannot emit warnings on stable, so every breaking change is
+//! reported by using a `#[deprecated]` constant whose note describes it,
+//! spanned to the method so editors show it there.
+use open_rpc_schema::__private::serde_json::{self, Value};
+use proc_macro2::Span;
+use quote::{quote, quote_spanned};
+use syn::{Error, Result};
+
+/// What the clients of a method depend on, as the macro knows it.
+pub struct Signature<'a> {
+    pub name: &'a str,
+    /// The documented params, `None` when the params are reshaped into names
+    /// only the generated code knows.
+    pub params: Option<Vec<Param>>,
+    /// The result type as the result of the document names it.
+    pub returns: String,
+    pub result_shape: Shape,
+    pub span: Span,
+}
+
+pub struct Param {
+    pub name: String,
+    pub required: bool,
+    pub shape: Shape,
+}
+
+/// What the macro knows of the schema of a type without running schemars.
+pub enum Shape {
+    /// The `type`s of the schema, sorted.
+    Types(Vec<&'static str>),
+    /// The name of the schema in the components, which a `$ref` points to.
+    Component(String),
+    Unknown,
+}
+
+impl Shape {
+    /// How the schema `old` of the baseline changed into this shape, `None`
+    /// when it didn't or the shape cannot tell.
+    fn changed_from(&self, old: &Value) -> Option<(String, String)> {
+        let old_types = match &old["type"] {
+            Value::String(ty) => Some(vec![ty.as_str()]),
+            Value::Array(types) => Some(types.iter().filter_map(Value::as_str).collect()),
+            _ => None,
+        };
+        let old_component = old["$ref"]
+            .as_str()
+            .map(|reference| reference.rsplit('/').next().unwrap_or(reference));
+        let was = match (old_types.clone(), old_component) {
+            (Some(types), _) => types.join(" | "),
+            (None, Some(component)) => component.to_string(),
+            (None, None) => return None,
+        };
+        let now = match self {
+            Shape::Types(types) => {
+                let mut old_types = old_types.unwrap_or_default();
+                old_types.sort_unstable();
+                if old_types == *types {
+                    return None;
+                }
+                types.join(" | ")
+            }
+            // an inlined schema may have any type
+            Shape::Component(component) => match old_component {
+                Some(old) if old != component => component.clone(),
+                _ => return None,
+            },
+            Shape::Unknown => return None,
+        };
+        Some((was, now))
+    }
+}
+
+/// Compares the methods with the baseline document at `path`, relative to
+/// the manifest of the crate, and expands to a warning per breaking change
+/// and the `BREAKING_CHANGES` table listing them.
+pub fn compute_breaking_changes(
+    path: &syn::LitStr,
+    signatures: &[Signature],
+    trait_span: Span,
+) -> Result<proc_macro2::TokenStream> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let full_path = std::path::Path::new(&manifest_dir).join(path.value());
+    let baseline = std::fs::read_to_string(&full_path)
+        .map_err(|err| Error::new_spanned(path, format!("Cannot read the baseline: {}", err)))?;
+    let baseline: Value = serde_json::from_str(&baseline)
+        .map_err(|err| Error::new_spanned(path, format!("Invalid baseline document: {}", err)))?;
+
+    let changes = breaking_changes(&baseline, signatures, trait_span);
+    let warnings = changes.iter().enumerate().map(|(index, (span, change))| {
+        let ident = quote::format_ident!("breaking_change_{}", index, span = *span);
+        let note = format!("{} since the baseline `{}`", change, path.value());
+        quote_spanned! {*span=>
+            #[deprecated(note = #note)]
+            #[allow(non_upper_case_globals)]
+            const #ident: () = ();
+            const _: () = #ident;
+        }
+    });
+    let changes = changes.iter().map(|(_, change)| change);
+    // recompile when the baseline changes
+    let full_path = full_path.to_string_lossy();
+    Ok(quote! {
+        const _: &[u8] = ::core::include_bytes!(#full_path);
+
+        #(#warnings)*
+
+        /// The changes of the methods breaking the clients of the baseline
+        /// document.
+        pub const BREAKING_CHANGES: &[&str] = &[#(#changes),*];
+    })
+}
+
+fn breaking_changes(
+    baseline: &Value,
+    signatures: &[Signature],
+    trait_span: Span,
+) -> Vec<(Span, String)> {
+    let mut changes = Vec::new();
+    for method in baseline["methods"].as_array().into_iter().flatten() {
+        let name = method["name"].as_str().unwrap_or_default();
+        let signature = match signatures.iter().find(|signature| signature.name == name) {
+            Some(signature) => signature,
+            None => {
+                changes.push((trait_span, format!("method `{}` was removed", name)));
+                continue;
+            }
+        };
+        let mut change = |change: String| changes.push((signature.span, change));
+
+        let was = compact(method["result"]["name"].as_str().unwrap_or_default());
+        if !was.is_empty() && was != compact(&signature.returns) {
+            change(format!(
+                "the result of `{}` changed from `{}` to `{}`",
+                name,
+                was,
+                compact(&signature.returns)
+            ));
+        }
+        if let Some((was, now)) = signature
+            .result_shape
+            .changed_from(&method["result"]["schema"])
+        {
+            change(format!(
+                "the result schema of `{}` changed from `{}` to `{}`",
+                name, was, now
+            ));
+        }
+
+        let params = match &signature.params {
+            Some(params) => params,
+            None => continue,
+        };
+        let old_params: Vec<_> = method["params"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|param| param["name"].as_str())
+            .collect();
+        for (position, old) in old_params.iter().enumerate() {
+            match params.iter().position(|param| param.name == *old) {
+                None => change(format!("param `{}` of `{}` was removed", old, name)),
+                Some(moved) if moved != position => change(format!(
+                    "param `{}` of `{}` moved from position {} to {}",
+                    old, name, position, moved
+                )),
+                Some(_) => {}
+            }
+        }
+        for param in params {
+            let old = method["params"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .find(|old| old["name"] == param.name.as_str());
+            let was_required = old.is_some_and(|old| old["required"] == true);
+            match old {
+                None if param.required => change(format!(
+                    "required param `{}` was added to `{}`",
+                    param.name, name
+                )),
+                Some(_) if param.required && !was_required => change(format!(
+                    "param `{}` of `{}` is now required",
+                    param.name, name
+                )),
+                _ => {}
+            }
+            if let Some((was, now)) = old.and_then(|old| param.shape.changed_from(&old["schema"])) {
+                change(format!(
+                    "the schema of param `{}` of `{}` changed from `{}` to `{}`",
+                    param.name, name, was, now
+                ));
+            }
+        }
+    }
+    changes
+}
+
+/// `stringify!` and `quote` space tokens differently.
+fn compact(ty: &str) -> String {
+    ty.split_whitespace().collect()
+}
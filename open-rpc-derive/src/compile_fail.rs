@@ -0,0 +1,112 @@
+//! Attributes the macros reject at expansion time.
+//!
+//! A pagination param the method does not take:
+//!
+//! ```compile_fail
+//! use open_rpc_derive::{document_rpc, rpc};
+//!
+//! #[document_rpc]
+//! pub trait Rpc {
+//!     #[rpc(paginated(cursor_param = "after", limit_param = "limit"))]
+//!     fn list(&self, before: Option<String>, limit: u32) -> Result<Vec<String>, String>;
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! A `one_of` value of another type than the param:
+//!
+//! ```compile_fail
+//! use open_rpc_derive::{document_rpc, rpc};
+//!
+//! #[document_rpc]
+//! pub trait Rpc {
+//!     #[rpc(one_of(slot = ["latest"]))]
+//!     fn block(&self, slot: Option<u64>) -> Result<String, String>;
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! Params documented as one object but passed by name:
+//!
+//! ```compile_fail
+//! use open_rpc_derive::{document_rpc, rpc};
+//!
+//! #[document_rpc]
+//! pub trait Rpc {
+//!     #[rpc(params_as_object, param_structure = "by-name")]
+//!     fn stake(&self, amount: u64) -> Result<bool, String>;
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! A `param_structure` contradicting the `params` key:
+//!
+//! ```compile_fail
+//! use open_rpc_derive::{document_rpc, rpc};
+//!
+//! #[document_rpc]
+//! pub trait Rpc {
+//!     #[rpc(params = "named", param_structure = "by-position")]
+//!     fn stake(&self, amount: u64) -> Result<bool, String>;
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! A read-only method with side effects:
+//!
+//! ```compile_fail
+//! use open_rpc_derive::{document_rpc, rpc};
+//!
+//! #[document_rpc]
+//! pub trait Rpc {
+//!     #[rpc(readonly, side_effects)]
+//!     fn balance(&self, address: String) -> Result<u64, String>;
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! Subscriptions naming their notification envelopes alike:
+//!
+//! ```compile_fail
+//! use open_rpc_derive::{document_rpc, pubsub};
+//!
+//! pub struct Subscriber<T>(T);
+//!
+//! #[document_rpc]
+//! pub trait Rpc {
+//!     type Metadata;
+//!
+//!     #[pubsub(subscription = "slot", subscribe, name = "slotSubscribe")]
+//!     fn slot(&self, meta: Self::Metadata, subscriber: Subscriber<u64>);
+//!     #[pubsub(subscription = "slotNotification", subscribe, name = "slotNotificationSubscribe")]
+//!     fn slot_notification(&self, meta: Self::Metadata, subscriber: Subscriber<u64>);
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! A stacked method whose generated items would be named after a keyword:
+//!
+//! ```compile_fail
+//! use open_rpc_derive::{document_rpc, rpc};
+//!
+//! #[document_rpc]
+//! pub trait Rpc {
+//!     #[rpc(name = "getBlock")]
+//!     #[rpc(name = "type")]
+//!     fn get_block(&self, slot: u64) -> Result<String, String>;
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! A rate limit never refilling its tokens:
+//!
+//! ```compile_fail
+//! use open_rpc_derive::{document_rpc, rpc};
+//!
+//! #[document_rpc]
+//! pub trait Rpc {
+//!     #[rpc(rate_limit(requests = 0, per_ms = 1000))]
+//!     fn slot(&self) -> Result<u64, String>;
+//! }
+//! # fn main() {}
+//! ```
@@ -0,0 +1,311 @@
+//! Summary and description taken from the doc comments of a method.
+//!
+//! Following rustdoc, the first paragraph is the summary and whatever follows
+//! the first blank line is the description. The markdown is kept, minus the
+//! constructs only rustdoc understands: hidden lines of Rust code blocks and
+//! intra-doc links, which would render as broken bracket text elsewhere.
+//! Given the base url of the published rustdoc, intra-doc links become links
+//! into it instead of plain text.
+
+const RUST_FENCE_ATTRIBUTES: &[&str] = &[
+    "rust",
+    "ignore",
+    "no_run",
+    "should_panic",
+    "compile_fail",
+    "test_harness",
+    "edition2015",
+    "edition2018",
+    "edition2021",
+    "edition2024",
+];
+
+/// The text of the `#[doc]` attributes, one line per attribute.
+pub fn doc_text(doc_comments: &[syn::Attribute]) -> Option<String> {
+    let lines: Vec<String> = doc_comments
+        .iter()
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(syn::Meta::NameValue(syn::MetaNameValue {
+                lit: syn::Lit::Str(doc),
+                ..
+            })) => Some(doc.value()),
+            _ => None,
+        })
+        .flat_map(|doc| {
+            // unlike `lines()` this keeps the blank `///` separating paragraphs
+            doc.split('\n')
+                .map(|line| {
+                    line.strip_prefix(' ')
+                        .unwrap_or(line)
+                        .trim_end()
+                        .to_string()
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    let text = lines.join("\n");
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// Splits doc text into the summary paragraph and the description after it.
+pub fn split_summary(text: &str) -> (Option<String>, Option<String>) {
+    let (summary, description) = match text.split_once("\n\n") {
+        Some((summary, description)) => (summary, Some(description.trim())),
+        None => (text, None),
+    };
+    let summary = summary.split_whitespace().collect::<Vec<_>>().join(" ");
+    (
+        (!summary.is_empty()).then_some(summary),
+        description
+            .filter(|description| !description.is_empty())
+            .map(str::to_string),
+    )
+}
+
+/// Rewrites rustdoc specific markdown into plain markdown, linking items into
+/// the rustdoc at `docs_base_url` when given.
+pub fn sanitize(text: &str, docs_base_url: Option<&str>) -> String {
+    let defined = url_definitions(text);
+    let mut lines = Vec::new();
+    // `Some(is_rust)` inside a code block
+    let mut fence = None;
+    for line in text.split('\n') {
+        let trimmed = line.trim_start();
+        if let Some(info) = trimmed.strip_prefix("```") {
+            fence = match fence {
+                Some(_) => None,
+                None => {
+                    let is_rust = is_rust_fence(info);
+                    if is_rust {
+                        lines.push(format!("{}```rust", &line[..line.len() - trimmed.len()]));
+                        fence = Some(true);
+                        continue;
+                    }
+                    Some(false)
+                }
+            };
+            lines.push(line.to_string());
+        } else if fence == Some(true) {
+            if trimmed == "#" || trimmed.starts_with("# ") {
+                continue;
+            }
+            match trimmed.strip_prefix("##") {
+                Some(rest) => {
+                    lines.push(format!("{}#{}", &line[..line.len() - trimmed.len()], rest))
+                }
+                None => lines.push(line.to_string()),
+            }
+        } else if fence.is_none() {
+            match (link_definition(trimmed), docs_base_url) {
+                (Some((label, target)), Some(base)) => {
+                    lines.push(format!("[{}]: {}", label, item_url(base, target)))
+                }
+                (Some(_), None) => {}
+                (None, _) => lines.push(rewrite_links(line, &defined, docs_base_url)),
+            }
+        } else {
+            lines.push(line.to_string());
+        }
+    }
+    lines.join("\n")
+}
+
+/// rustdoc treats a block as Rust unless told otherwise.
+fn is_rust_fence(info: &str) -> bool {
+    info.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|attribute| !attribute.is_empty())
+        .all(|attribute| RUST_FENCE_ATTRIBUTES.contains(&attribute))
+}
+
+/// The labels of the reference definitions pointing at urls rather than
+/// items, lowercased as markdown matches them case-insensitively.
+fn url_definitions(text: &str) -> Vec<String> {
+    let mut in_fence = false;
+    text.split('\n')
+        .map(str::trim_start)
+        .filter(|line| {
+            if line.starts_with("```") {
+                in_fence = !in_fence;
+            }
+            !in_fence
+        })
+        .filter(|line| link_definition(line).is_none())
+        .filter_map(|line| line.strip_prefix('[')?.split_once("]:"))
+        .map(|(label, _)| label.to_lowercase())
+        .collect()
+}
+
+/// The label and target of a `[Foo]: crate::Foo` reference definition
+/// pointing at an item.
+fn link_definition(line: &str) -> Option<(&str, &str)> {
+    let (label, target) = line.strip_prefix('[')?.split_once("]:")?;
+    let target = target.trim();
+    is_item_path(target).then_some((label, target))
+}
+
+/// Replaces the intra-doc links of a line with their text, or with links into
+/// the rustdoc at `docs_base_url`. Reference links to one of the `defined`
+/// url labels are markdown already and kept.
+fn rewrite_links(line: &str, defined: &[String], docs_base_url: Option<&str>) -> String {
+    let link = |text: &str, target: &str| match docs_base_url {
+        Some(base) => format!("[{}]({})", text, item_url(base, target)),
+        None => text.to_string(),
+    };
+    let chars: Vec<char> = line.chars().collect();
+    let find = |from: usize, c: char| (from..chars.len()).find(|&i| chars[i] == c);
+    let collect = |from: usize, to: usize| chars[from..to].iter().collect::<String>();
+    let mut output = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            // inline code is left alone
+            '`' => {
+                if let Some(end) = find(i + 1, '`') {
+                    output.push_str(&collect(i, end + 1));
+                    i = end + 1;
+                    continue;
+                }
+            }
+            '[' => {
+                if let Some(end) = find(i + 1, ']') {
+                    let text = collect(i + 1, end);
+                    let is_defined = |label: &str| defined.contains(&label.to_lowercase());
+                    let target_end = match chars.get(end + 1) {
+                        Some('(') => find(end + 2, ')').map(|close| (close, end + 2)),
+                        Some('[') => find(end + 2, ']').map(|close| (close, end + 2)),
+                        _ => None,
+                    };
+                    match target_end {
+                        Some((close, start)) => {
+                            let target = collect(start, close);
+                            let is_reference = chars[end + 1] == '[';
+                            if is_item_path(&target) && !(is_reference && is_defined(&target)) {
+                                output.push_str(&link(&text, &target));
+                                i = close + 1;
+                                continue;
+                            }
+                        }
+                        None => {
+                            if is_item_path(text.trim_matches('`')) && !is_defined(&text) {
+                                output.push_str(&link(&text, text.trim_matches('`')));
+                                i = end + 1;
+                                continue;
+                            }
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+        output.push(chars[i]);
+        i += 1;
+    }
+    output
+}
+
+/// Whether a link target names a Rust item rather than a URL or anchor, e.g.
+/// `Foo`, `crate::Foo::bar()`, `struct@Foo` or `vec!`.
+fn is_item_path(target: &str) -> bool {
+    let path = target.split_once('@').map_or(target, |(_, path)| path);
+    let path = path.trim_end_matches("()").trim_end_matches('!');
+    path.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        && path.split("::").all(|segment| {
+            !segment.is_empty() && segment.chars().all(|c| c.is_alphanumeric() || c == '_')
+        })
+}
+
+/// The rustdoc page of an item. The kind of the item, which names the page,
+/// is only known from a disambiguator like `struct@` or a `()`/`!` suffix,
+/// otherwise the link is a rustdoc search for it.
+fn item_url(base: &str, target: &str) -> String {
+    let (kind, path) = match target.split_once('@') {
+        Some((kind, path)) => (Some(kind), path),
+        None if target.ends_with("()") => (Some("fn"), target.trim_end_matches("()")),
+        None if target.ends_with('!') => (Some("macro"), target.trim_end_matches('!')),
+        None => (None, target),
+    };
+    let path = path.trim_end_matches("()").trim_end_matches('!');
+    let path = ["crate::", "self::"]
+        .iter()
+        .find_map(|prefix| path.strip_prefix(prefix))
+        .unwrap_or(path);
+    let base = base.trim_end_matches('/');
+    match kind {
+        Some(kind) => {
+            let mut segments: Vec<_> = path.split("::").collect();
+            let name = segments.pop().unwrap_or_default();
+            segments.push("");
+            format!("{}/{}{}.{}.html", base, segments.join("/"), kind, name)
+        }
+        None => format!("{}/?search={}", base, path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rustdoc_constructs_are_rewritten() {
+        let docs = [
+            "Returns the [`Account`] at [an address](crate::Address), see [`get`][Rpc::get].",
+            "",
+            "[Rpc::get]: crate::Rpc::get",
+            "Keeps [links](https://example.com), `a[0]` and [1].",
+            "```",
+            "# use rpc::Account;",
+            "let account = Account::default();",
+            "## not hidden",
+            "```",
+            "```json",
+            "# {}",
+            "```",
+        ];
+        let expected = [
+            "Returns the `Account` at an address, see `get`.",
+            "",
+            "Keeps [links](https://example.com), `a[0]` and [1].",
+            "```rust",
+            "let account = Account::default();",
+            "# not hidden",
+            "```",
+            "```json",
+            "# {}",
+            "```",
+        ];
+        assert_eq!(sanitize(&docs.join("\n"), None), expected.join("\n"));
+    }
+
+    #[test]
+    fn intra_doc_links_point_into_rustdoc() {
+        let base = Some("https://docs.rs/accounts/latest/accounts/");
+        assert_eq!(
+            sanitize("Returns the [`Account`].", base),
+            "Returns the [`Account`](https://docs.rs/accounts/latest/accounts/?search=Account)."
+        );
+        assert_eq!(
+            sanitize("See [get](struct@crate::rpc::Client).", base),
+            "See [get](https://docs.rs/accounts/latest/accounts/rpc/struct.Client.html)."
+        );
+        assert_eq!(
+            sanitize("[Client]: crate::Client", base),
+            "[Client]: https://docs.rs/accounts/latest/accounts/?search=Client"
+        );
+    }
+
+    #[test]
+    fn links_to_url_definitions_are_kept() {
+        let docs = [
+            "See [spec] and [the spec][Spec].",
+            "",
+            "[spec]: https://www.jsonrpc.org/specification",
+        ]
+        .join("\n");
+        assert_eq!(sanitize(&docs, None), docs);
+        assert_eq!(
+            sanitize(&docs, Some("https://docs.rs/accounts/latest/accounts/")),
+            docs
+        );
+    }
+}
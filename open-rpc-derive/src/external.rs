@@ -0,0 +1,120 @@
+use crate::options::DocumentOptions;
+use crate::rpc_trait::{compute_method_registrations, rpc_wrapper_mod_name};
+use crate::to_gen_schema::generate_schema_method;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_quote,
+    punctuated::Punctuated,
+    Result, Token,
+};
+
+/// Input of `document_external_rpc!`:
+///
+/// ```ignore
+/// document_external_rpc!(path::to::ForeignTrait {
+///     #[rpc(name = "getBalance")]
+///     get_balance(addr: String) -> u64,
+/// });
+/// ```
+pub struct ExternalRpc {
+    path: syn::Path,
+    methods: Punctuated<ExternalMethod, Token![,]>,
+}
+
+struct ExternalMethod {
+    attrs: Vec<syn::Attribute>,
+    ident: syn::Ident,
+    args: Punctuated<syn::FnArg, Token![,]>,
+    output: syn::Type,
+}
+
+impl Parse for ExternalRpc {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let path = input.parse()?;
+        let content;
+        syn::braced!(content in input);
+        let methods = content.parse_terminated(ExternalMethod::parse)?;
+        Ok(ExternalRpc { path, methods })
+    }
+}
+
+impl Parse for ExternalMethod {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let attrs = input.call(syn::Attribute::parse_outer)?;
+        let ident = input.parse()?;
+        let content;
+        syn::parenthesized!(content in input);
+        let args = content.parse_terminated(syn::FnArg::parse)?;
+        let output = if input.peek(Token![->]) {
+            input.parse::<Token![->]>()?;
+            input.parse()?
+        } else {
+            parse_quote!(())
+        };
+        Ok(ExternalMethod {
+            attrs,
+            ident,
+            args,
+            output,
+        })
+    }
+}
+
+impl ExternalMethod {
+    /// The trait method this entry stands for, as if it had been annotated in
+    /// the foreign trait itself.
+    fn to_trait_item(&self) -> syn::TraitItemMethod {
+        let ExternalMethod {
+            attrs,
+            ident,
+            args,
+            output,
+        } = self;
+        let attrs = if attrs.is_empty() {
+            vec![parse_quote!(#[rpc])]
+        } else {
+            attrs.clone()
+        };
+        parse_quote! {
+            #(#attrs)*
+            fn #ident(&self, #args) -> Result<#output>;
+        }
+    }
+}
+
+pub fn document_external_rpc(input: ExternalRpc) -> Result<proc_macro2::TokenStream> {
+    let ExternalRpc { path, methods } = input;
+    let trait_ident = match path.segments.last() {
+        Some(segment) => segment.ident.clone(),
+        None => return Err(syn::Error::new_spanned(path, "Expected a trait path")),
+    };
+    let trait_items = methods.iter().map(ExternalMethod::to_trait_item);
+    let item_trait: syn::ItemTrait = parse_quote! {
+        trait #trait_ident {
+            #(#trait_items)*
+        }
+    };
+    let method_registrations = compute_method_registrations(&item_trait)?;
+    let mod_name_ident = rpc_wrapper_mod_name(&trait_ident);
+    let generate_schema_method = generate_schema_method(
+        &method_registrations,
+        &DocumentOptions::default(),
+        &item_trait,
+    )?;
+    let method_idents = methods.iter().map(|m| &m.ident);
+
+    Ok(quote!(
+        pub mod #mod_name_ident {
+            use super::*;
+            #generate_schema_method
+
+            // fails to compile when the foreign trait drifts away from the
+            // documented methods
+            #[allow(dead_code)]
+            fn assert_methods_exist<T: #path>() {
+                #(let _ = <T as #path>::#method_idents;)*
+            }
+        }
+    ))
+}
@@ -4,13 +4,23 @@ use proc_macro::TokenStream;
 use syn::parse_macro_input;
 
 mod attr;
+mod baseline;
+#[cfg(doctest)]
+mod compile_fail;
+mod docs;
+mod external;
+#[cfg(doctest)]
+mod no_std;
+mod options;
 mod params;
+mod rpc_error;
 mod rpc_trait;
 mod to_gen_schema;
 #[proc_macro_attribute]
-pub fn document_rpc(_args: TokenStream, input: TokenStream) -> TokenStream {
+pub fn document_rpc(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as syn::AttributeArgs);
     let input_tokens = parse_macro_input!(input as syn::Item);
-    let output: TokenStream = match rpc_trait::rpc_trait(input_tokens) {
+    let output: TokenStream = match rpc_trait::rpc_trait(args, input_tokens) {
         Ok(output) => output.into(),
         Err(err) => err.to_compile_error().into(),
     };
@@ -21,3 +31,50 @@ pub fn document_rpc(_args: TokenStream, input: TokenStream) -> TokenStream {
 pub fn rpc(_args: TokenStream, input: TokenStream) -> TokenStream {
     input
 }
+
+#[proc_macro_attribute]
+pub fn pubsub(_args: TokenStream, input: TokenStream) -> TokenStream {
+    input
+}
+
+/// Documents a trait this crate does not own. Expands to the same
+/// `openrpc_schema_<Trait>` module `#[document_rpc]` generates, without the
+/// `schema` method on the trait.
+#[proc_macro]
+pub fn document_external_rpc(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as external::ExternalRpc);
+    match external::document_external_rpc(input) {
+        Ok(output) => output.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// The JSON of the document of a trait, `openrpc_json!(path::to::Rpc)`.
+///
+/// The schemas only exist once the program runs, so this is a
+/// `&'static str` built on first use rather than a string literal: it is not
+/// usable in `const` contexts.
+#[proc_macro]
+pub fn openrpc_json(input: TokenStream) -> TokenStream {
+    let mut path = parse_macro_input!(input as syn::Path);
+    match path.segments.last_mut() {
+        Some(last) => last.ident = rpc_trait::rpc_wrapper_mod_name(&last.ident),
+        None => {
+            return syn::Error::new_spanned(&path, "Expected the path of a trait")
+                .to_compile_error()
+                .into()
+        }
+    }
+    quote::quote!(#path::json()).into()
+}
+
+/// Lists the variants of an error enum as the errors of the methods returning
+/// it, see `open_rpc_schema::errors`.
+#[proc_macro_derive(OpenRpcError, attributes(rpc_error, error))]
+pub fn derive_open_rpc_error(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as syn::DeriveInput);
+    match rpc_error::derive_open_rpc_error(input) {
+        Ok(output) => output.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
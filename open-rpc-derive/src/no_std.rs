@@ -0,0 +1,24 @@
+//! A `no_std` crate building its document with `alloc`.
+//!
+//! ```
+//! #![no_std]
+//! extern crate alloc;
+//!
+//! use alloc::string::String;
+//! use open_rpc_derive::{document_rpc, rpc};
+//!
+//! #[derive(Debug)]
+//! pub struct Error;
+//!
+//! #[document_rpc]
+//! pub trait Rpc {
+//!     /// The current slot.
+//!     #[rpc(name = "getSlot")]
+//!     fn get_slot(&self, commitment: Option<String>) -> Result<u64, Error>;
+//! }
+//!
+//! fn main() {
+//!     let document = gen_schema();
+//!     assert_eq!(document.methods[0].name, "getSlot");
+//! }
+//! ```
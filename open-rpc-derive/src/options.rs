@@ -0,0 +1,623 @@
+use crate::params::ParamStructure;
+use syn::{Error, Result};
+
+const COMPACT: &str = "compact";
+const FEATURE: &str = "feature";
+const BATCH: &str = "batch";
+const BATCH_SUPPORTED: &str = "supported";
+const BATCH_MAX_SIZE: &str = "max_size";
+const AUTH: &str = "auth";
+const AUTH_SCHEME: &str = "scheme";
+const AUTH_DESCRIPTION: &str = "description";
+const EXAMPLES: &str = "examples";
+const SYNTHESIZE_EXAMPLES: &str = "synthesize_examples";
+const SYNTHESIZE_SEED: &str = "seed";
+const DOCS_BASE_URL: &str = "docs_base_url";
+const EXTERNAL_DOCS: &str = "external_docs";
+const EXTERNAL_DOCS_URL: &str = "url";
+const EXTERNAL_DOCS_DESCRIPTION: &str = "description";
+const METADATA: &str = "metadata";
+const TAG_GROUPS: &str = "tag_groups";
+const STANDARD_ERRORS: &str = "standard_errors";
+const BACKEND: &str = "backend";
+const SCHEMA_GENERATOR: &str = "schema_generator";
+const PARAM_STRUCTURE: &str = "param_structure";
+const TRAIT_TAG: &str = "trait_tag";
+const DISPLAY_STRIP_PREFIX: &str = "display_strip_prefix";
+const CODE_SAMPLES: &str = "code_samples";
+const DISCOVER_METHOD: &str = "discover_method";
+const REQUEST_BUILDERS: &str = "request_builders";
+const GOLDEN_TESTS: &str = "golden_tests";
+const SLIM: &str = "slim";
+const BASELINE: &str = "baseline";
+const COMPONENT_NAMES: &str = "component_names";
+const DEDUPE_SCHEMAS: &str = "dedupe_schemas";
+const SHARED_CONFIG: &str = "shared_config";
+const COMPONENT_NAMES_MODULE_PATH: &str = "module_path";
+const COMPONENT_NAMES_GENERICS: &str = "generics";
+const COMPONENT_NAMES_RENAME: &str = "rename";
+const SCHEMARS: &str = "schemars";
+const SCHEMARS_INLINE_SUBSCHEMAS: &str = "inline_subschemas";
+const SCHEMARS_DEFINITIONS_PATH: &str = "definitions_path";
+const SCHEMARS_OPTION_NULLABLE: &str = "option_nullable";
+const SCHEMARS_OPTION_ADD_NULL_TYPE: &str = "option_add_null_type";
+const SCHEMARS_BOOL_SCHEMAS: &str = "bool_schemas";
+const METADATA_HEADER: &str = "header";
+const METADATA_NAME: &str = "name";
+const METADATA_DESCRIPTION: &str = "description";
+const INVALID_OPTION_ERR: &str = "Invalid document_rpc argument:";
+
+/// Trait level options given to `#[document_rpc(...)]`.
+#[derive(Debug, Default)]
+pub struct DocumentOptions {
+    /// Emit a static metadata table and build the document at runtime with
+    /// the shared builder instead of expanding every method inline.
+    pub compact: bool,
+    /// Only generate the document when this crate feature is enabled,
+    /// otherwise `gen_schema()` returns an empty document.
+    pub feature: Option<String>,
+    /// Batch request support, emitted as the `x-batch` extension.
+    pub batch: Option<BatchOptions>,
+    /// How clients authenticate, emitted as the `x-auth` extension.
+    pub auth: Option<AuthOptions>,
+    /// Named examples shared by the methods through `param_example_ref`.
+    pub examples: Vec<(String, String)>,
+    /// Synthesize an example pairing for the methods without one, from the
+    /// given seed or the default one.
+    pub synthesize_examples: Option<Option<u64>>,
+    /// The published rustdoc intra-doc links in doc comments point into.
+    pub docs_base_url: Option<String>,
+    /// The document level `externalDocs`.
+    pub external_docs: Option<ExternalDocsOptions>,
+    /// The request headers the `Metadata` of the trait is extracted from,
+    /// emitted as the `x-metadata` extension.
+    pub metadata: Vec<MetadataInput>,
+    /// Named sets of tags, each generating a `gen_schema_<group>()` with only
+    /// the methods carrying one of them.
+    pub tag_groups: Vec<(syn::Ident, Vec<String>)>,
+    /// Document the errors every JSON-RPC server may return on every method.
+    pub standard_errors: bool,
+    /// The `SchemaBackend` `gen_backend_document()` converts the document with.
+    pub backend: Option<syn::Path>,
+    /// The `GenerateSchema` implementation building the schemas instead of
+    /// `schemars`.
+    pub schema_generator: Option<syn::Path>,
+    /// The `paramStructure` of the methods not declaring their own.
+    pub param_structure: Option<ParamStructure>,
+    /// A tag applied to every method of the trait, so merged documents keep
+    /// the trait each method comes from.
+    pub trait_tag: Option<TraitTag>,
+    /// Emit the method names without this prefix as `x-display-name`.
+    pub display_strip_prefix: Option<String>,
+    /// Emit Rust, curl and TypeScript usage of every method as
+    /// `x-code-samples`.
+    pub code_samples: bool,
+    /// The method the document is served under instead of `rpc.discover`,
+    /// emitted as the `x-discover-method` extension.
+    pub discover_method: Option<String>,
+    /// Generate a `requests` module building the request bodies of the
+    /// methods, which needs the param types to implement `Serialize`.
+    pub request_builders: bool,
+    /// The handler answering the example requests of the generated
+    /// `golden_<method>` tests, a `fn(&Value) -> Value`.
+    pub golden_tests: Option<syn::Path>,
+    /// Embed the document without examples and method descriptions, while
+    /// `gen_schema()` keeps building the full one for the dump binary.
+    pub slim: bool,
+    /// The settings `schemars` builds the schemas with.
+    pub schemars: Option<SchemarsOptions>,
+    /// The document, relative to the manifest of the crate, whose clients
+    /// the changes of the trait are checked against.
+    pub baseline: Option<syn::LitStr>,
+    /// How the component schemas are named after the Rust types.
+    pub component_names: ComponentNames,
+    /// Merge the component schemas equal to one another, listing the names
+    /// of the merged ones under `x-aliases`.
+    pub dedupe_schemas: bool,
+    /// The type of the optional `config` param appended to the methods marked
+    /// `with_config`.
+    pub shared_config: Option<syn::Path>,
+}
+
+#[derive(Debug)]
+pub enum TraitTag {
+    /// `trait_tag`, the tag is the name of the trait.
+    TraitName,
+    /// `trait_tag = "..."`.
+    Named(String),
+}
+
+/// `component_names(module_path, generics, rename(Block = "LedgerBlock"))`.
+#[derive(Debug, Default)]
+pub struct ComponentNames {
+    /// Prefix the names with the module path the trait spells the type with,
+    /// `v1::Block` as `v1_Block`.
+    pub module_path: bool,
+    /// Name the generic args too, `Page<Block>` as `Page_for_Block` like
+    /// `schemars` does.
+    pub generics: bool,
+    /// The names given to the components instead of the derived ones.
+    pub renames: Vec<(String, String)>,
+}
+
+/// `schemars(inline_subschemas = false, definitions_path = "..")`, the
+/// fields of `open_rpc_schema::generator::SchemarsSettings`.
+#[derive(Debug)]
+pub struct SchemarsOptions {
+    pub inline_subschemas: bool,
+    pub definitions_path: String,
+    pub option_nullable: bool,
+    pub option_add_null_type: bool,
+    pub bool_schemas: bool,
+}
+
+#[derive(Debug)]
+pub struct BatchOptions {
+    pub supported: bool,
+    pub max_size: Option<u64>,
+}
+
+#[derive(Debug)]
+pub struct AuthOptions {
+    pub scheme: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct ExternalDocsOptions {
+    pub url: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct MetadataInput {
+    pub header: String,
+    pub description: Option<String>,
+}
+
+impl DocumentOptions {
+    pub fn try_from(args: syn::AttributeArgs) -> Result<Self> {
+        let mut options = DocumentOptions::default();
+        for arg in args {
+            let meta = match arg {
+                syn::NestedMeta::Meta(meta) => meta,
+                syn::NestedMeta::Lit(lit) => return Err(invalid_option(lit)),
+            };
+            let name = meta.path().get_ident().map(|i| i.to_string());
+            match (name.as_deref(), &meta) {
+                (Some(COMPACT), syn::Meta::Path(_)) => options.compact = true,
+                (Some(TRAIT_TAG), syn::Meta::Path(_)) => {
+                    options.trait_tag = Some(TraitTag::TraitName)
+                }
+                (Some(TRAIT_TAG), syn::Meta::NameValue(nv)) => {
+                    options.trait_tag = Some(TraitTag::Named(lit_str(nv)?))
+                }
+                (Some(STANDARD_ERRORS), syn::Meta::Path(_)) => options.standard_errors = true,
+                (Some(CODE_SAMPLES), syn::Meta::Path(_)) => options.code_samples = true,
+                (Some(REQUEST_BUILDERS), syn::Meta::Path(_)) => options.request_builders = true,
+                (Some(SLIM), syn::Meta::Path(_)) => options.slim = true,
+                (Some(DEDUPE_SCHEMAS), syn::Meta::Path(_)) => options.dedupe_schemas = true,
+                (Some(SYNTHESIZE_EXAMPLES), syn::Meta::Path(_)) => {
+                    options.synthesize_examples = Some(None)
+                }
+                (Some(SYNTHESIZE_EXAMPLES), syn::Meta::List(ml)) => {
+                    let mut seed = None;
+                    for nv in name_values(ml)? {
+                        match path_to_string(&nv.path).as_deref() {
+                            Some(SYNTHESIZE_SEED) => seed = Some(lit_int(nv)?),
+                            _ => {
+                                return Err(Error::new_spanned(
+                                    nv,
+                                    format!("Expected '{}'", SYNTHESIZE_SEED),
+                                ))
+                            }
+                        }
+                    }
+                    options.synthesize_examples = Some(seed)
+                }
+                (Some(FEATURE), syn::Meta::NameValue(nv)) => options.feature = Some(lit_str(nv)?),
+                (Some(PARAM_STRUCTURE), syn::Meta::NameValue(nv)) => {
+                    let structure = lit_str(nv)?
+                        .parse()
+                        .map_err(|e| Error::new_spanned(nv, e))?;
+                    options.param_structure = Some(structure)
+                }
+                (Some(BACKEND), syn::Meta::NameValue(nv)) => options.backend = Some(lit_path(nv)?),
+                (Some(SCHEMA_GENERATOR), syn::Meta::NameValue(nv)) => {
+                    if options.schemars.is_some() {
+                        return Err(generator_conflict(nv));
+                    }
+                    options.schema_generator = Some(lit_path(nv)?)
+                }
+                (Some(DISPLAY_STRIP_PREFIX), syn::Meta::NameValue(nv)) => {
+                    options.display_strip_prefix = Some(lit_str(nv)?)
+                }
+                (Some(DISCOVER_METHOD), syn::Meta::NameValue(nv)) => {
+                    options.discover_method = Some(lit_str(nv)?)
+                }
+                (Some(SHARED_CONFIG), syn::Meta::NameValue(nv)) => {
+                    options.shared_config = Some(lit_path(nv)?)
+                }
+                (Some(BASELINE), syn::Meta::NameValue(nv)) => {
+                    options.baseline = Some(syn::LitStr::new(&lit_str(nv)?, nv.lit.span()))
+                }
+                (Some(GOLDEN_TESTS), syn::Meta::NameValue(nv)) => {
+                    options.golden_tests = Some(lit_path(nv)?)
+                }
+                (Some(DOCS_BASE_URL), syn::Meta::NameValue(nv)) => {
+                    options.docs_base_url = Some(lit_str(nv)?)
+                }
+                (Some(BATCH), syn::Meta::Path(_)) => {
+                    options.batch = Some(BatchOptions {
+                        supported: true,
+                        max_size: None,
+                    })
+                }
+                (Some(BATCH), syn::Meta::List(ml)) => {
+                    options.batch = Some(BatchOptions::try_from(ml)?)
+                }
+                (Some(AUTH), syn::Meta::List(ml)) => {
+                    options.auth = Some(AuthOptions::try_from(ml)?)
+                }
+                (Some(COMPONENT_NAMES), syn::Meta::List(ml)) => {
+                    options.component_names = ComponentNames::try_from(ml)?
+                }
+                (Some(SCHEMARS), syn::Meta::List(ml)) => {
+                    if options.schema_generator.is_some() {
+                        return Err(generator_conflict(ml));
+                    }
+                    options.schemars = Some(SchemarsOptions::try_from(ml)?)
+                }
+                (Some(METADATA), syn::Meta::List(ml)) => {
+                    for nested in &ml.nested {
+                        match nested {
+                            syn::NestedMeta::Meta(syn::Meta::List(input))
+                                if input.path.is_ident(METADATA_HEADER) =>
+                            {
+                                options.metadata.push(MetadataInput::try_from(input)?)
+                            }
+                            other => {
+                                return Err(Error::new_spanned(
+                                    other,
+                                    format!("Expected '{}(..)'", METADATA_HEADER),
+                                ))
+                            }
+                        }
+                    }
+                }
+                (Some(TAG_GROUPS), syn::Meta::List(ml)) => {
+                    for nested in &ml.nested {
+                        let group = match nested {
+                            syn::NestedMeta::Meta(syn::Meta::List(group)) => group,
+                            other => {
+                                return Err(Error::new_spanned(
+                                    other,
+                                    "Expected `group(\"tag\", ..)`",
+                                ))
+                            }
+                        };
+                        let name =
+                            group.path.get_ident().cloned().ok_or_else(|| {
+                                Error::new_spanned(&group.path, "Expected a name")
+                            })?;
+                        let tags = group
+                            .nested
+                            .iter()
+                            .map(|tag| match tag {
+                                syn::NestedMeta::Lit(syn::Lit::Str(tag)) => Ok(tag.value()),
+                                other => {
+                                    Err(Error::new_spanned(other, "Expected a string literal"))
+                                }
+                            })
+                            .collect::<Result<_>>()?;
+                        options.tag_groups.push((name, tags));
+                    }
+                }
+                (Some(EXTERNAL_DOCS), syn::Meta::List(ml)) => {
+                    options.external_docs = Some(ExternalDocsOptions::try_from(ml)?)
+                }
+                (Some(EXAMPLES), syn::Meta::List(ml)) => {
+                    for nv in name_values(ml)? {
+                        let name = path_to_string(&nv.path)
+                            .ok_or_else(|| Error::new_spanned(&nv.path, "Expected a name"))?;
+                        options.examples.push((name, lit_str(nv)?));
+                    }
+                }
+                _ => return Err(invalid_option(meta)),
+            }
+        }
+        Ok(options)
+    }
+}
+
+impl DocumentOptions {
+    /// The tag of `trait_tag` for the trait `ident`.
+    pub fn trait_tag(&self, ident: &syn::Ident) -> Option<String> {
+        match &self.trait_tag {
+            Some(TraitTag::TraitName) => Some(ident.to_string()),
+            Some(TraitTag::Named(tag)) => Some(tag.clone()),
+            None => None,
+        }
+    }
+}
+
+impl BatchOptions {
+    fn try_from(ml: &syn::MetaList) -> Result<Self> {
+        let mut batch = BatchOptions {
+            supported: true,
+            max_size: None,
+        };
+        for nv in name_values(ml)? {
+            match path_to_string(&nv.path).as_deref() {
+                Some(BATCH_SUPPORTED) => batch.supported = lit_bool(nv)?,
+                Some(BATCH_MAX_SIZE) => batch.max_size = Some(lit_int(nv)?),
+                _ => {
+                    return Err(Error::new_spanned(
+                        nv,
+                        format!("Expected '{}, {}'", BATCH_SUPPORTED, BATCH_MAX_SIZE),
+                    ))
+                }
+            }
+        }
+        Ok(batch)
+    }
+}
+
+impl ComponentNames {
+    fn try_from(ml: &syn::MetaList) -> Result<Self> {
+        let mut names = ComponentNames::default();
+        for nested in &ml.nested {
+            match nested {
+                syn::NestedMeta::Meta(syn::Meta::Path(path))
+                    if path.is_ident(COMPONENT_NAMES_MODULE_PATH) =>
+                {
+                    names.module_path = true
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(path))
+                    if path.is_ident(COMPONENT_NAMES_GENERICS) =>
+                {
+                    names.generics = true
+                }
+                syn::NestedMeta::Meta(syn::Meta::List(renames))
+                    if renames.path.is_ident(COMPONENT_NAMES_RENAME) =>
+                {
+                    for nv in name_values(renames)? {
+                        let from = path_to_string(&nv.path)
+                            .ok_or_else(|| Error::new_spanned(&nv.path, "Expected a name"))?;
+                        names.renames.push((from, lit_str(nv)?));
+                    }
+                }
+                other => {
+                    return Err(Error::new_spanned(
+                        other,
+                        format!(
+                            "Expected '{}, {}, {}(..)'",
+                            COMPONENT_NAMES_MODULE_PATH,
+                            COMPONENT_NAMES_GENERICS,
+                            COMPONENT_NAMES_RENAME
+                        ),
+                    ))
+                }
+            }
+        }
+        Ok(names)
+    }
+}
+
+impl SchemarsOptions {
+    fn try_from(ml: &syn::MetaList) -> Result<Self> {
+        let mut schemars = SchemarsOptions {
+            inline_subschemas: true,
+            definitions_path: "#/definitions/".to_string(),
+            option_nullable: false,
+            option_add_null_type: true,
+            bool_schemas: false,
+        };
+        for nv in name_values(ml)? {
+            match path_to_string(&nv.path).as_deref() {
+                Some(SCHEMARS_INLINE_SUBSCHEMAS) => schemars.inline_subschemas = lit_bool(nv)?,
+                Some(SCHEMARS_DEFINITIONS_PATH) => schemars.definitions_path = lit_str(nv)?,
+                Some(SCHEMARS_OPTION_NULLABLE) => schemars.option_nullable = lit_bool(nv)?,
+                Some(SCHEMARS_OPTION_ADD_NULL_TYPE) => {
+                    schemars.option_add_null_type = lit_bool(nv)?
+                }
+                Some(SCHEMARS_BOOL_SCHEMAS) => schemars.bool_schemas = lit_bool(nv)?,
+                _ => {
+                    let expected = [
+                        SCHEMARS_INLINE_SUBSCHEMAS,
+                        SCHEMARS_DEFINITIONS_PATH,
+                        SCHEMARS_OPTION_NULLABLE,
+                        SCHEMARS_OPTION_ADD_NULL_TYPE,
+                        SCHEMARS_BOOL_SCHEMAS,
+                    ];
+                    return Err(Error::new_spanned(
+                        nv,
+                        format!("Expected '{}'", expected.join(", ")),
+                    ));
+                }
+            }
+        }
+        Ok(schemars)
+    }
+
+    /// Whether the `$ref`s point into the components of the document, which
+    /// then has to hold the definitions.
+    pub fn refers_to_components(&self) -> bool {
+        self.definitions_path.starts_with("#/components/schemas/")
+    }
+}
+
+impl AuthOptions {
+    fn try_from(ml: &syn::MetaList) -> Result<Self> {
+        let mut scheme = None;
+        let mut description = None;
+        for nv in name_values(ml)? {
+            match path_to_string(&nv.path).as_deref() {
+                Some(AUTH_SCHEME) => scheme = Some(lit_str(nv)?),
+                Some(AUTH_DESCRIPTION) => description = Some(lit_str(nv)?),
+                _ => {
+                    return Err(Error::new_spanned(
+                        nv,
+                        format!("Expected '{}, {}'", AUTH_SCHEME, AUTH_DESCRIPTION),
+                    ))
+                }
+            }
+        }
+        let scheme = scheme.ok_or_else(|| {
+            Error::new_spanned(ml, "auth should have a scheme e.g. `scheme = \"bearer\"`")
+        })?;
+        Ok(AuthOptions {
+            scheme,
+            description,
+        })
+    }
+}
+
+impl MetadataInput {
+    fn try_from(ml: &syn::MetaList) -> Result<Self> {
+        let mut header = None;
+        let mut description = None;
+        for nv in name_values(ml)? {
+            match path_to_string(&nv.path).as_deref() {
+                Some(METADATA_NAME) => header = Some(lit_str(nv)?),
+                Some(METADATA_DESCRIPTION) => description = Some(lit_str(nv)?),
+                _ => {
+                    return Err(Error::new_spanned(
+                        nv,
+                        format!("Expected '{}, {}'", METADATA_NAME, METADATA_DESCRIPTION),
+                    ))
+                }
+            }
+        }
+        let header = header.ok_or_else(|| {
+            Error::new_spanned(
+                ml,
+                "header should have a name e.g. `name = \"Authorization\"`",
+            )
+        })?;
+        Ok(MetadataInput {
+            header,
+            description,
+        })
+    }
+}
+
+impl ExternalDocsOptions {
+    fn try_from(ml: &syn::MetaList) -> Result<Self> {
+        let mut url = None;
+        let mut description = None;
+        for nv in name_values(ml)? {
+            match path_to_string(&nv.path).as_deref() {
+                Some(EXTERNAL_DOCS_URL) => url = Some(lit_str(nv)?),
+                Some(EXTERNAL_DOCS_DESCRIPTION) => description = Some(lit_str(nv)?),
+                _ => {
+                    return Err(Error::new_spanned(
+                        nv,
+                        format!(
+                            "Expected '{}, {}'",
+                            EXTERNAL_DOCS_URL, EXTERNAL_DOCS_DESCRIPTION
+                        ),
+                    ))
+                }
+            }
+        }
+        let url = url.ok_or_else(|| {
+            Error::new_spanned(
+                ml,
+                "external_docs should have a url e.g. `url = \"https://...\"`",
+            )
+        })?;
+        Ok(ExternalDocsOptions { url, description })
+    }
+}
+
+/// The `key = value` items of a meta list, failing on anything else.
+fn name_values(ml: &syn::MetaList) -> Result<Vec<&syn::MetaNameValue>> {
+    ml.nested
+        .iter()
+        .map(|nested| match nested {
+            syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) => Ok(nv),
+            other => Err(Error::new_spanned(other, "Expected `key = value`")),
+        })
+        .collect()
+}
+
+fn path_to_string(path: &syn::Path) -> Option<String> {
+    path.get_ident().map(|i| i.to_string())
+}
+
+fn lit_str(nv: &syn::MetaNameValue) -> Result<String> {
+    match &nv.lit {
+        syn::Lit::Str(lit) => Ok(lit.value()),
+        lit => Err(Error::new_spanned(lit, "Expected a string literal")),
+    }
+}
+
+fn lit_path(nv: &syn::MetaNameValue) -> Result<syn::Path> {
+    match &nv.lit {
+        syn::Lit::Str(lit) => lit.parse(),
+        lit => Err(Error::new_spanned(lit, "Expected a string literal")),
+    }
+}
+
+fn lit_bool(nv: &syn::MetaNameValue) -> Result<bool> {
+    match &nv.lit {
+        syn::Lit::Bool(lit) => Ok(lit.value),
+        lit => Err(Error::new_spanned(lit, "Expected a boolean literal")),
+    }
+}
+
+fn lit_int<N>(nv: &syn::MetaNameValue) -> Result<N>
+where
+    N: std::str::FromStr,
+    N::Err: std::fmt::Display,
+{
+    match &nv.lit {
+        syn::Lit::Int(lit) => lit.base10_parse(),
+        lit => Err(Error::new_spanned(lit, "Expected an integer literal")),
+    }
+}
+
+fn generator_conflict<T: quote::ToTokens>(tokens: T) -> Error {
+    let msg = format!(
+        "Expected only one of `{}` and `{}`",
+        SCHEMA_GENERATOR, SCHEMARS
+    );
+    Error::new_spanned(tokens, msg)
+}
+
+fn invalid_option<T: quote::ToTokens>(tokens: T) -> Error {
+    let expected = [
+        COMPACT,
+        FEATURE,
+        BATCH,
+        AUTH,
+        EXAMPLES,
+        SYNTHESIZE_EXAMPLES,
+        DOCS_BASE_URL,
+        EXTERNAL_DOCS,
+        METADATA,
+        TAG_GROUPS,
+        STANDARD_ERRORS,
+        BACKEND,
+        SCHEMA_GENERATOR,
+        PARAM_STRUCTURE,
+        TRAIT_TAG,
+        DISPLAY_STRIP_PREFIX,
+        CODE_SAMPLES,
+        DISCOVER_METHOD,
+        REQUEST_BUILDERS,
+        GOLDEN_TESTS,
+        SLIM,
+        SCHEMARS,
+        BASELINE,
+        COMPONENT_NAMES,
+        DEDUPE_SCHEMAS,
+        SHARED_CONFIG,
+    ]
+    .join(", ");
+    Error::new_spanned(
+        tokens,
+        format!("{} Expected '{}'", INVALID_OPTION_ERR, expected),
+    )
+}
@@ -3,20 +3,19 @@ use std::str::FromStr;
 const POSITIONAL: &str = "positional";
 const NAMED: &str = "named";
 const RAW: &str = "raw";
+const BY_POSITION: &str = "by-position";
+const BY_NAME: &str = "by-name";
+const EITHER: &str = "either";
 
-#[derive(Clone, Debug, PartialEq)]
+/// The `params` key of jsonrpc-derive, how the server reads the params.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub enum ParamStyle {
+    #[default]
     Positional,
     Named,
     Raw,
 }
 
-impl Default for ParamStyle {
-    fn default() -> Self {
-        Self::Positional
-    }
-}
-
 impl FromStr for ParamStyle {
     type Err = String;
 
@@ -32,3 +31,61 @@ impl FromStr for ParamStyle {
         }
     }
 }
+
+impl ParamStyle {
+    /// The structure the server accepts, `None` when it takes the raw params.
+    pub fn param_structure(self) -> Option<ParamStructure> {
+        match self {
+            Self::Positional => Some(ParamStructure::ByPosition),
+            Self::Named => Some(ParamStructure::ByName),
+            Self::Raw => None,
+        }
+    }
+}
+
+/// The documented `paramStructure` of a method.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ParamStructure {
+    ByPosition,
+    ByName,
+    Either,
+}
+
+impl FromStr for ParamStructure {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            BY_POSITION => Ok(Self::ByPosition),
+            BY_NAME => Ok(Self::ByName),
+            EITHER => Ok(Self::Either),
+            _ => Err(format!(
+                "Invalid value for param_structure. Must be one of [{}, {}, {}]",
+                BY_POSITION, BY_NAME, EITHER
+            )),
+        }
+    }
+}
+
+impl ParamStructure {
+    /// How the params are passed, for error messages.
+    pub fn describe(self) -> &'static str {
+        match self {
+            Self::ByPosition => "by position",
+            Self::ByName => "by name",
+            Self::Either => "by position or by name",
+        }
+    }
+
+    /// Statement setting the param structure of `method_object`.
+    pub fn to_tokens(self) -> proc_macro2::TokenStream {
+        let variant = match self {
+            Self::ByPosition => quote::quote!(ByPosition),
+            Self::ByName => quote::quote!(ByName),
+            Self::Either => quote::quote!(Either),
+        };
+        quote::quote! {
+            method_object.param_structure = Some(MethodObjectParamStructure::#variant);
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use quote::quote;
+use syn::{Error, Result};
+
+const RPC_ERROR_ATTR: &str = "rpc_error";
+const THISERROR_ATTR: &str = "error";
+const CODE_KEY: &str = "code";
+const MESSAGE_KEY: &str = "message";
+
+/// Implements `OpenRpcErrors` for an error enum, one error object per
+/// variant. The code comes from `#[rpc_error(code = ..)]`, the message from
+/// `#[rpc_error(message = "..")]` or else the `#[error("..")]` of thiserror.
+pub fn derive_open_rpc_error(input: syn::DeriveInput) -> Result<proc_macro2::TokenStream> {
+    let data = match &input.data {
+        syn::Data::Enum(data) => data,
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "OpenRpcError is derived for enums",
+            ))
+        }
+    };
+    let mut errors = vec![];
+    for variant in &data.variants {
+        let (code, message) = variant_error(variant)?;
+        errors.push(quote! {
+            ::open_rpc_schema::document::ErrorObject {
+                code: #code,
+                message: ::open_rpc_schema::__private::ToString::to_string(#message),
+                data: ::open_rpc_schema::__private::None,
+            }
+        });
+    }
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::open_rpc_schema::errors::OpenRpcErrors for #ident #ty_generics #where_clause {
+            fn errors() -> ::open_rpc_schema::__private::Vec<::open_rpc_schema::document::ErrorObject> {
+                ::open_rpc_schema::__private::vec![#(#errors),*]
+            }
+        }
+    })
+}
+
+fn variant_error(variant: &syn::Variant) -> Result<(i64, String)> {
+    let mut code = None;
+    let mut message = None;
+    let mut display = None;
+    for attr in &variant.attrs {
+        if attr.path.is_ident(RPC_ERROR_ATTR) {
+            let ml = match attr.parse_meta()? {
+                syn::Meta::List(ml) => ml,
+                meta => return Err(Error::new_spanned(meta, "Expected `rpc_error(code = ..)`")),
+            };
+            for nested in &ml.nested {
+                match nested {
+                    syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) => {
+                        match (
+                            nv.path.get_ident().map(|i| i.to_string()).as_deref(),
+                            &nv.lit,
+                        ) {
+                            (Some(CODE_KEY), syn::Lit::Int(int)) => {
+                                code = Some(int.base10_parse()?)
+                            }
+                            (Some(MESSAGE_KEY), syn::Lit::Str(s)) => message = Some(s.value()),
+                            _ => {
+                                return Err(Error::new_spanned(
+                                    nv,
+                                    "Expected an integer `code` or a `message`",
+                                ))
+                            }
+                        }
+                    }
+                    other => return Err(Error::new_spanned(other, "Expected `key = value`")),
+                }
+            }
+        } else if attr.path.is_ident(THISERROR_ATTR) {
+            // `#[error("..")]`, `#[error(transparent)]` has no message of its own
+            if let Ok(syn::Meta::List(ml)) = attr.parse_meta() {
+                if let Some(syn::NestedMeta::Lit(syn::Lit::Str(s))) = ml.nested.first() {
+                    display = Some(s.value());
+                }
+            }
+        }
+    }
+    let code = code.ok_or_else(|| {
+        Error::new_spanned(
+            &variant.ident,
+            "variant should have an error code e.g. `#[rpc_error(code = -32010)]`",
+        )
+    })?;
+    let message = message
+        .or(display)
+        .unwrap_or_else(|| variant.ident.to_string());
+    Ok((code, message))
+}
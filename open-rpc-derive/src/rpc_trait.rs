@@ -1,55 +1,40 @@
-use crate::attr::{AttributeKind, RpcMethodAttribute};
+use crate::attr::{
+    AttributeKind, PubSubMethodKind, RpcMethodAttribute, ALIASES_KEY, RPC_ATTR_NAME,
+};
+use crate::options::DocumentOptions;
 use crate::to_gen_schema::generate_schema_method;
 use crate::to_gen_schema::{MethodRegistration, RpcMethod};
-use proc_macro::Ident;
-use proc_macro2::Span;
+use proc_macro2::{Delimiter, Group, Span, TokenStream, TokenTree};
 use quote::quote;
-use syn::{ItemTrait, ItemImpl};
-use syn::{
-    fold::{self, Fold},
-    parse_quote, Result,
-};
-
-const METADATA_TYPE: &str = "Metadata";
+use std::collections::HashMap;
+use syn::{parse_quote, Result};
+use syn::{ItemImpl, ItemTrait};
 
 const OPENRPC_SCHEMA_MODE_PREFIX: &str = "openrpc_schema_";
+const MISSING_SUBSCRIBE_METHOD_ERR: &str =
+    "Can't find subscribe method, expected a method annotated with `subscribe` \
+     e.g. `#[pubsub(subscription = \"hello\", subscribe, name = \"hello_subscribe\")]`";
 
-struct RpcTrait {
-    methods: Vec<RpcMethod>,
-    has_metadata: bool,
-}
-
-impl<'a> Fold for RpcTrait {
-    fn fold_trait_item_method(&mut self, method: syn::TraitItemMethod) -> syn::TraitItemMethod {
-        let mut foldable_method = method.clone();
-        // strip rpc attributes
-        foldable_method.attrs.retain(|a| {
-            let rpc_method = self.methods.iter().find(|m| m.trait_item == method);
-            rpc_method.map_or(true, |rpc| rpc.attr.attr != *a)
-        });
-        fold::fold_trait_item_method(self, foldable_method)
-    }
-
-    fn fold_trait_item_type(&mut self, ty: syn::TraitItemType) -> syn::TraitItemType {
-        if ty.ident == METADATA_TYPE {
-            self.has_metadata = true;
-            let mut ty = ty.clone();
-            ty.bounds.push(parse_quote!(_jsonrpc_core::Metadata));
-            return ty;
-        }
-        ty
-    }
-}
-
-fn compute_method_registrations(item_trait: &syn::ItemTrait) -> Result<Vec<MethodRegistration>> {
+pub fn compute_method_registrations(
+    item_trait: &syn::ItemTrait,
+) -> Result<Vec<MethodRegistration>> {
     let methods_result: Result<Vec<_>> = item_trait
         .items
         .iter()
         .filter_map(|trait_item| {
             if let syn::TraitItem::Method(method) = trait_item {
-                match RpcMethodAttribute::parse_attr(method) {
-                    Ok(Some(attr)) => Some(Ok(RpcMethod::new(attr, method.clone()))),
-                    Ok(None) => None, // non rpc annotated trait method
+                // non rpc annotated trait methods have no attributes
+                match RpcMethodAttribute::parse_attrs(method) {
+                    Ok(attrs) => Some(
+                        attrs
+                            .into_iter()
+                            .enumerate()
+                            .map(|(i, attr)| match i {
+                                0 => Ok(RpcMethod::new(attr, method.clone())),
+                                _ => RpcMethod::stacked(attr, method.clone()),
+                            })
+                            .collect::<Result<Vec<_>>>(),
+                    ),
                     Err(err) => Some(Err(syn::Error::new_spanned(method, err))),
                 }
             } else {
@@ -57,80 +42,362 @@ fn compute_method_registrations(item_trait: &syn::ItemTrait) -> Result<Vec<Metho
             }
         })
         .collect();
-    let methods = methods_result?;
+    let methods: Vec<_> = methods_result?.into_iter().flatten().collect();
+    // the generated items of stacked methods are named after their wire name
+    for (index, method) in methods.iter().enumerate() {
+        if let Some(other) = methods[..index]
+            .iter()
+            .find(|other| other.ident == method.ident)
+        {
+            let msg = format!(
+                "`{}` and `{}` would both name their generated items `{}`",
+                other.name(),
+                method.name(),
+                method.ident
+            );
+            return Err(syn::Error::new_spanned(&method.attr().attr, msg));
+        }
+    }
 
-    let mut method_registrations: Vec<MethodRegistration> = Vec::new();
+    // the registrations keep the order of the trait, a subscription sitting
+    // where its first method is declared
+    enum Registration<'a> {
+        Standard(&'a RpcMethod),
+        PubSub(String),
+    }
+    let mut registrations = Vec::new();
+    let mut pubsub_method_pairs: HashMap<String, (Vec<RpcMethod>, Option<RpcMethod>)> =
+        HashMap::new();
 
     for method in methods.iter() {
         match &method.attr().kind {
-            AttributeKind::Rpc { has_metadata, .. } => {
+            AttributeKind::Rpc { .. } => registrations.push(Registration::Standard(method)),
+            AttributeKind::PubSub {
+                subscription_name,
+                kind,
+            } => {
+                if !pubsub_method_pairs.contains_key(subscription_name) {
+                    registrations.push(Registration::PubSub(subscription_name.clone()));
+                }
+                let (ref mut sub, ref mut unsub) = pubsub_method_pairs
+                    .entry(subscription_name.clone())
+                    .or_insert((vec![], None));
+                match kind {
+                    PubSubMethodKind::Subscribe => sub.push(method.clone()),
+                    PubSubMethodKind::Unsubscribe => {
+                        if unsub.is_none() {
+                            *unsub = Some(method.clone())
+                        } else {
+                            return Err(syn::Error::new_spanned(
+                                &method.trait_item,
+                                format!(
+                                    "Subscription '{}' unsubscribe method is already defined",
+                                    subscription_name
+                                ),
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let mut method_registrations: Vec<MethodRegistration> = Vec::new();
+    for registration in registrations {
+        match registration {
+            Registration::Standard(method) => {
                 method_registrations.push(MethodRegistration::Standard {
                     method: method.clone(),
-                    has_metadata: *has_metadata,
                 })
             }
+            Registration::PubSub(name) => match pubsub_method_pairs.remove(&name) {
+                // without an unsubscribe method the conventional one is documented
+                Some((subscribers, unsubscribe)) if !subscribers.is_empty() => method_registrations
+                    .push(MethodRegistration::PubSub {
+                        name,
+                        subscribes: subscribers,
+                        unsubscribe,
+                    }),
+                Some((_, unsubscribe)) => {
+                    return Err(syn::Error::new_spanned(
+                        unsubscribe.map(|m| m.trait_item),
+                        format!("subscription '{}'. {}", name, MISSING_SUBSCRIBE_METHOD_ERR),
+                    ))
+                }
+                None => unreachable!("every registered subscription has methods"),
+            },
         }
     }
     Ok(method_registrations)
 }
 
-fn rpc_wrapper_mod_name(ident: &syn::Ident) -> syn::Ident {
+pub fn rpc_wrapper_mod_name(ident: &syn::Ident) -> syn::Ident {
     let name = ident.clone();
-    let mod_name = format!("{}{}", OPENRPC_SCHEMA_MODE_PREFIX, name.to_string());
+    let mod_name = format!("{}{}", OPENRPC_SCHEMA_MODE_PREFIX, name);
     syn::Ident::new(&mod_name, proc_macro2::Span::call_site())
 }
 
+/// The param the methods marked `with_config` take the shared config as.
+const SHARED_CONFIG_PARAM: &str = "config";
 
-fn handle_trait(mut rpc_trait: ItemTrait) -> Result<proc_macro2::TokenStream> {
+/// Appends the `config` param to the methods marked `with_config`, so the
+/// trait, its implementations and the document all take it.
+fn append_shared_config(rpc_trait: &mut ItemTrait, options: &DocumentOptions) -> Result<()> {
+    for item in &mut rpc_trait.items {
+        let method = match item {
+            syn::TraitItem::Method(method) => method,
+            _ => continue,
+        };
+        // an invalid attribute is reported with the registrations
+        let with_config = RpcMethodAttribute::parse_attrs(method)
+            .is_ok_and(|attrs| attrs.iter().any(|attr| attr.with_config));
+        if !with_config {
+            continue;
+        }
+        let config = options.shared_config.as_ref().ok_or_else(|| {
+            syn::Error::new_spanned(
+                &method.sig.ident,
+                "`with_config` needs `#[document_rpc(shared_config = \"Type\")]` on the trait",
+            )
+        })?;
+        let param = syn::Ident::new(SHARED_CONFIG_PARAM, proc_macro2::Span::call_site());
+        let taken = method.sig.inputs.iter().any(|arg| match arg {
+            syn::FnArg::Typed(arg) => {
+                matches!(&*arg.pat, syn::Pat::Ident(pat) if pat.ident == param)
+            }
+            syn::FnArg::Receiver(_) => false,
+        });
+        if taken {
+            return Err(syn::Error::new_spanned(
+                &method.sig.ident,
+                format!(
+                    "`with_config` appends a `{}` param, which the method already takes",
+                    param
+                ),
+            ));
+        }
+        method
+            .sig
+            .inputs
+            .push(parse_quote!(#param: ::core::option::Option<#config>));
+    }
+    Ok(())
+}
+
+/// Leaves only the first `#[rpc]` attribute on a trait method, the stacked
+/// ones becoming its aliases, so jsonrpc-derive registers every documented
+/// name with the same function.
+fn merge_stacked_rpc_attrs(rpc_trait: &mut ItemTrait) -> Result<()> {
+    for item in &mut rpc_trait.items {
+        let method = match item {
+            syn::TraitItem::Method(method) => method,
+            _ => continue,
+        };
+        let attrs = RpcMethodAttribute::parse_attrs(method)?;
+        if attrs.len() < 2 {
+            continue;
+        }
+        let aliases: Vec<_> = attrs[1..]
+            .iter()
+            .flat_map(|attr| std::iter::once(&attr.name).chain(&attr.aliases))
+            .collect();
+        let is_rpc = |attr: &syn::Attribute| attr.path.is_ident(RPC_ATTR_NAME);
+        let mut positions = method
+            .attrs
+            .iter()
+            .enumerate()
+            .filter(|(_, attr)| is_rpc(attr));
+        let first = match positions.next() {
+            Some((first, _)) => first,
+            None => continue,
+        };
+        let stacked: Vec<_> = positions.map(|(position, _)| position).collect();
+        for position in stacked.into_iter().rev() {
+            method.attrs.remove(position);
+        }
+        let attr = &mut method.attrs[first];
+        attr.tokens = with_aliases(attr.tokens.clone(), &aliases);
+    }
+    Ok(())
+}
+
+/// Adds `aliases` to the `alias(..)` list of the arguments of an attribute,
+/// appending the list when it has none.
+fn with_aliases(tokens: TokenStream, aliases: &[&String]) -> TokenStream {
+    let alias = syn::Ident::new(ALIASES_KEY, Span::call_site());
+    let args = match tokens.into_iter().next() {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => group,
+        _ => return quote!((#alias(#(#aliases),*))),
+    };
+    let mut output: Vec<TokenTree> = args.stream().into_iter().collect();
+    let existing = output.windows(2).position(|pair| {
+        matches!(pair, [TokenTree::Ident(ident), TokenTree::Group(_)] if ident == ALIASES_KEY)
+    });
+    match existing.map(|index| (index + 1, &output[index + 1])) {
+        Some((index, TokenTree::Group(list))) => {
+            let mut extended = Group::new(
+                Delimiter::Parenthesis,
+                comma_separated(list.stream(), quote!(#(#aliases),*)),
+            );
+            extended.set_span(list.span());
+            output[index] = TokenTree::Group(extended);
+        }
+        _ => {
+            let args = comma_separated(output.into_iter().collect(), quote!(#alias(#(#aliases),*)));
+            output = args.into_iter().collect();
+        }
+    }
+    let mut group = Group::new(Delimiter::Parenthesis, output.into_iter().collect());
+    group.set_span(args.span());
+    quote!(#group)
+}
+
+/// The items of `list` followed by `items`.
+fn comma_separated(mut list: TokenStream, items: TokenStream) -> TokenStream {
+    let trailing_comma = matches!(
+        list.clone().into_iter().last(),
+        Some(TokenTree::Punct(punct)) if punct.as_char() == ','
+    );
+    if !list.is_empty() && !trailing_comma {
+        list.extend(quote!(,));
+    }
+    list.extend(items);
+    list
+}
+
+fn handle_trait(
+    mut rpc_trait: ItemTrait,
+    options: DocumentOptions,
+) -> Result<proc_macro2::TokenStream> {
+    append_shared_config(&mut rpc_trait, &options)?;
     let method_registrations = compute_method_registrations(&rpc_trait)?;
+    merge_stacked_rpc_attrs(&mut rpc_trait)?;
     let mod_name_ident = rpc_wrapper_mod_name(&rpc_trait.ident);
-    let generate_schema_method = generate_schema_method(&method_registrations)?;
-      
+    let schema_ident = syn::Ident::new(
+        &format!("{}Schema", rpc_trait.ident),
+        proc_macro2::Span::call_site(),
+    );
+    let generate_schema_method =
+        generate_schema_method(&method_registrations, &options, &rpc_trait)?;
+
     rpc_trait.items.push(parse_quote!(
         #[doc(hidden)]
         fn schema(&self) -> OpenrpcDocument;
     ));
+    // resolved by the impl side through whatever path names the trait, so the
+    // impl keeps working when the trait is re-exported
+    rpc_trait.items.push(parse_quote!(
+        #[doc(hidden)]
+        fn openrpc_schema_anchor() -> ::open_rpc_schema::document::OpenrpcDocument
+        where
+            Self: ::core::marker::Sized,
+        {
+            #mod_name_ident::gen_schema()
+        }
+    ));
+
+    // object safe, so a `Box<dyn Trait>` reaches the document of its trait
+    if cfg!(feature = "std") {
+        rpc_trait.items.push(parse_quote!(
+            /// The OpenRPC document of the trait.
+            fn openrpc_document(&self) -> &'static ::open_rpc_schema::document::OpenrpcDocument {
+                #mod_name_ident::cached_schema()
+            }
+        ));
+    }
+    let cached_schema = cfg!(feature = "std").then(|| quote!(cached_schema,));
 
     let openrpc_quote = quote!(
 
-        use open_rpc_schema::document::OpenrpcDocument;
+        use ::open_rpc_schema::document::OpenrpcDocument;
 
         pub mod #mod_name_ident {
             use super::*;
-            use open_rpc_schema::document::*;
             #generate_schema_method
         }
-        
-        pub use self::#mod_name_ident::gen_schema;
-        
+
+        pub use self::#mod_name_ident::{#cached_schema gen_schema, gen_schema_for_tags};
+        pub use self::#mod_name_ident::Schema as #schema_ident;
+
         #rpc_trait
     );
     Ok(quote!(#openrpc_quote))
 }
 
-
-fn handle_impl(mut rpc_impl: ItemImpl)-> Result<proc_macro2::TokenStream>   {
-    
-    let r#trait = &rpc_impl.clone().trait_.unwrap().1;    
-    let mod_name_ident = rpc_wrapper_mod_name(r#trait.get_ident().unwrap());
+fn handle_impl(mut rpc_impl: ItemImpl) -> Result<proc_macro2::TokenStream> {
+    let trait_path = match &rpc_impl.trait_ {
+        Some((_, path, _)) => path.clone(),
+        None => {
+            return Err(syn::Error::new_spanned(
+                rpc_impl.self_ty,
+                "The #[document_rpc] custom attribute only works with trait impls",
+            ))
+        }
+    };
     let st = parse_quote!(
-        fn schema(&self) -> OpenrpcDocument {
-           super::#mod_name_ident::gen_schema()
+        fn schema(&self) -> ::open_rpc_schema::document::OpenrpcDocument {
+            <Self as #trait_path>::openrpc_schema_anchor()
         }
     );
     rpc_impl.items.push(st);
     Ok(quote!(#rpc_impl))
 }
 
-pub fn rpc_trait(input: syn::Item) -> Result<proc_macro2::TokenStream> {
+pub fn rpc_trait(args: syn::AttributeArgs, input: syn::Item) -> Result<proc_macro2::TokenStream> {
     match input.clone() {
-        syn::Item::Trait(item_trait) => handle_trait(item_trait),
+        syn::Item::Trait(item_trait) => handle_trait(item_trait, DocumentOptions::try_from(args)?),
         syn::Item::Impl(item_impl) => handle_impl(item_impl),
-        item => {
-            return Err(syn::Error::new_spanned(
-                item,
-                "The #[document_rpc] custom attribute only works with trait and impl declarations",
-            ));
-        }
+        item => Err(syn::Error::new_spanned(
+            item,
+            "The #[document_rpc] custom attribute only works with trait and impl declarations",
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stacked_rpc_attrs_become_aliases() {
+        let mut rpc_trait: ItemTrait = parse_quote! {
+            pub trait Rpc {
+                /// Returns the block at the slot.
+                #[rpc(name = "getBlock", alias("block"))]
+                #[rpc(name = "getConfirmedBlock", tags("deprecated"))]
+                #[rpc(name = "confirmedBlock")]
+                fn get_block(&self, slot: u64) -> Result<String, String>;
+                #[rpc(name = "getSlot")]
+                #[rpc(name = "slot")]
+                fn get_slot(&self) -> Result<u64, String>;
+            }
+        };
+        merge_stacked_rpc_attrs(&mut rpc_trait).unwrap();
+        let attrs: Vec<_> = rpc_trait
+            .items
+            .iter()
+            .map(|item| match item {
+                syn::TraitItem::Method(method) => method
+                    .attrs
+                    .iter()
+                    .map(|attr| quote!(#attr).to_string())
+                    .collect::<Vec<_>>(),
+                _ => unreachable!(),
+            })
+            .collect();
+        let expected = |attr: proc_macro2::TokenStream| attr.to_string();
+        assert_eq!(
+            attrs[0],
+            [
+                expected(quote!(#[doc = r" Returns the block at the slot."])),
+                expected(quote!(
+                    #[rpc(name = "getBlock", alias("block", "getConfirmedBlock", "confirmedBlock"))]
+                )),
+            ]
+        );
+        assert_eq!(
+            attrs[1],
+            [expected(quote!(#[rpc(name = "getSlot", alias("slot"))]))]
+        );
     }
 }
@@ -1,86 +1,1618 @@
-use crate::attr::AttributeKind;
 use crate::attr::RpcMethodAttribute;
+use crate::attr::{AttributeKind, Bound, PubSubMethodKind};
+use crate::baseline;
+use crate::docs;
+use crate::options::{DocumentOptions, SchemarsOptions};
+use crate::params::ParamStructure;
 use quote::quote;
+use std::collections::HashMap;
 use syn::{punctuated::Punctuated, Result};
 
-pub fn generate_schema_method(methods: &[MethodRegistration]) -> Result<syn::ImplItem> {
-    let mut schema_methods = vec![];
+const RPC_DISCOVER: &str = "rpc.discover";
+
+pub fn generate_schema_method(
+    methods: &[MethodRegistration],
+    options: &DocumentOptions,
+    item_trait: &syn::ItemTrait,
+) -> Result<proc_macro2::TokenStream> {
+    let trait_ident = &item_trait.ident;
+    let mut schemas = vec![];
     for method in methods {
         match method {
-            MethodRegistration::Standard { method, .. } => {
-            
-                let rpc_name = method.name();
-                let args = compute_args(&method.trait_item);
-                let arg_names = compute_arg_identifiers(&args)?;
-                let returns = match &method.attr.kind {
-                    AttributeKind::Rpc { returns, .. } => {
-                        compute_returns(&method.trait_item, returns)?
-                    }
+            MethodRegistration::Standard { method } => {
+                schemas.push(MethodSchema::new(method, options)?);
+            }
+            MethodRegistration::PubSub {
+                name,
+                subscribes,
+                unsubscribe,
+            } => {
+                let unsubscribe = match unsubscribe {
+                    Some(unsubscribe) => MethodSchema::new(unsubscribe, options)?,
+                    None => MethodSchema::conventional_unsubscribe(name, options),
                 };
-                let args_types = compute_arg_type(&args)?;
-                let arg_schemas = quote! {  {
-                    let mut arg_schemas = vec![];
-                    #(arg_schemas.push(
-                        ContentDescriptorOrReference::new_content_descriptor::<#args_types>(
-                            stringify!(#arg_names).to_string(),
-                            None,
-                        )
-                    ));*;
-                    arg_schemas
+                for subscribe in subscribes {
+                    let mut subscribe = MethodSchema::new(subscribe, options)?;
+                    subscribe.extras.extend(unsubscribe.link_from_subscribe());
+                    subscribe
+                        .slim_extras
+                        .extend(unsubscribe.link_from_subscribe());
+                    schemas.push(subscribe);
                 }
-                };
-                let summary = method.attr.summary.clone();
-                let schema_method = quote! {{
-                    let mut method_object = MethodObject::new(#rpc_name.to_string(), None);
-                    let returns = ContentDescriptorOrReference::new_content_descriptor::<#returns>(
-                        stringify!(#returns).to_string(),
-                        None,
+                schemas.push(unsubscribe);
+            }
+        }
+    }
+
+    // with a gating feature every generated item is compiled out unless the
+    // feature is enabled, and replaced with an empty stub otherwise
+    let (cfg, stub) = match &options.feature {
+        Some(feature) => {
+            let slim_stub = options.slim.then(|| {
+                quote! {
+                    #[cfg(not(feature = #feature))]
+                    pub fn gen_slim_schema() -> OpenrpcDocument {
+                        OpenrpcDocument::default()
+                    }
+                }
+            });
+            (
+                quote!(#[cfg(feature = #feature)]),
+                quote! {
+                    #[cfg(not(feature = #feature))]
+                    pub fn gen_schema() -> OpenrpcDocument {
+                        OpenrpcDocument::default()
+                    }
+
+                    #[cfg(not(feature = #feature))]
+                    pub const CONST_METHODS: &[::open_rpc_schema::table::ConstMethod] = &[];
+
+                    #[cfg(not(feature = #feature))]
+                    pub static STATIC_METHODS: &[::open_rpc_schema::table::StaticMethod] = &[];
+
+                    #slim_stub
+                },
+            )
+        }
+        None => (quote!(), quote!()),
+    };
+
+    let components: Vec<_> = schemas
+        .iter()
+        .filter(|schema| !schema.components.is_empty())
+        .map(|schema| {
+            let MethodSchema {
+                cfg, components, ..
+            } = schema;
+            quote!(#(#cfg)* { #components })
+        })
+        .collect();
+    let trait_tag = options.trait_tag(trait_ident).map(|tag| {
+        quote! {
+            for method_object in &mut document.methods {
+                method_object.add_tag(#tag);
+            }
+        }
+    });
+    // the doc comment of the trait describes the whole api
+    let description = docs::doc_text(&get_doc_comments(&item_trait.attrs)).map(|text| {
+        let text = docs::sanitize(&text, options.docs_base_url.as_deref());
+        quote!(document.info.description = Some(#text.to_string());)
+    });
+    let document_fn = |doc: proc_macro2::TokenStream, ident: &str, table: &str, slim: bool| {
+        let ident = quote::format_ident!("{}", ident);
+        let document_extras = compute_document_extras(options, slim);
+        let description = description.as_ref().filter(|_| !slim);
+        let document_extras = quote!(#description #(#components)* #trait_tag #document_extras);
+        if options.compact {
+            let table = quote::format_ident!("{}", table);
+            let entries = schemas
+                .iter()
+                .map(|schema| schema.to_method_entry(options, slim));
+            quote! {
+                #cfg
+                static #table: &[::open_rpc_schema::table::MethodEntry] = &[#(#entries,)*];
+
+                #doc
+                #cfg
+                pub fn #ident() -> OpenrpcDocument {
+                    #[allow(unused_mut)]
+                    let mut document = ::open_rpc_schema::table::build_document(#table);
+                    #document_extras
+                    document
+                }
+            }
+        } else {
+            let schema_methods = schemas
+                .iter()
+                .map(|schema| schema.to_method_object(options, slim));
+            quote! {
+                #doc
+                #cfg
+                pub fn #ident() -> OpenrpcDocument {
+                    let mut document = OpenrpcDocument::default();
+                    for method_object in [#(#schema_methods,)*] {
+                        document.add_object_method(method_object);
+                    }
+                    #document_extras
+                    document
+                }
+            }
+        }
+    };
+    let generate_schema_method = document_fn(quote!(), "gen_schema", "METHODS", false);
+    // the full document stays reachable through `gen_schema()`, which only
+    // ends up in the binaries calling it, e.g. the dump binary
+    let slim_schema_method = options.slim.then(|| {
+        let doc = "The document without examples and method descriptions, the one \
+                   `cached_schema()` serves.";
+        document_fn(
+            quote!(#[doc = #doc]),
+            "gen_slim_schema",
+            "SLIM_METHODS",
+            true,
+        )
+    });
+    let schemars_schema_fn = schemars_schema_fn(options);
+    let embedded = if options.slim {
+        quote!(gen_slim_schema)
+    } else {
+        quote!(gen_schema)
+    };
+
+    let const_methods = schemas.iter().filter_map(MethodSchema::to_const_method);
+    let registrations = methods
+        .iter()
+        .flat_map(|method| match method {
+            MethodRegistration::Standard { method } => vec![method],
+            MethodRegistration::PubSub {
+                subscribes,
+                unsubscribe,
+                ..
+            } => subscribes.iter().chain(unsubscribe).collect(),
+        })
+        .map(compute_registration)
+        .collect::<Result<Vec<_>>>()?;
+    let static_methods = schemas
+        .iter()
+        .map(|schema| schema.to_static_method(options));
+
+    let requests = options.request_builders.then(|| {
+        let requests = schemas.iter().map(|schema| &schema.request);
+        quote! {
+            /// Request bodies for the methods of the trait, for tests.
+            pub mod requests {
+                #[allow(unused_imports)]
+                use super::*;
+
+                #(#requests)*
+            }
+        }
+    });
+
+    let golden_tests = schemas.iter().map(|schema| &schema.golden);
+
+    let mut notifications = Vec::new();
+    // the subscription each envelope is named after
+    let mut envelopes: HashMap<String, &str> = HashMap::new();
+    for method in methods {
+        let (name, subscribe, (envelope, item)) = match method {
+            MethodRegistration::PubSub {
+                name, subscribes, ..
+            } => {
+                let notification = subscribes.iter().find_map(|subscribe| {
+                    notification_of(subscribe).map(|notification| (subscribe, notification))
+                });
+                match notification {
+                    Some((subscribe, notification)) => (name, subscribe, notification),
+                    None => continue,
+                }
+            }
+            MethodRegistration::Standard { .. } => continue,
+        };
+        let envelope = syn::parse_str::<syn::Ident>(&envelope).map_err(|_| {
+            let msg = format!(
+                "The notification envelope `{}` of subscription `{}` is not a valid identifier",
+                envelope, name
+            );
+            syn::Error::new_spanned(&subscribe.trait_item.sig.ident, msg)
+        })?;
+        if let Some(other) = envelopes.insert(envelope.to_string(), name) {
+            let msg = format!(
+                "Subscriptions `{}` and `{}` both name their notification envelope `{}`",
+                other, name, envelope
+            );
+            return Err(syn::Error::new_spanned(
+                &subscribe.trait_item.sig.ident,
+                msg,
+            ));
+        }
+        let doc = format!("A notification frame of the `{}` subscription.", name);
+        notifications.push(quote! {
+            #[doc = #doc]
+            pub type #envelope = ::open_rpc_schema::notification::Notification<#item>;
+        });
+    }
+
+    let group_names = options
+        .tag_groups
+        .iter()
+        .map(|(group, _)| group.to_string());
+    let group_tags = options.tag_groups.iter().map(|(_, tags)| tags);
+    let tag_groups = options.tag_groups.iter().map(|(group, tags)| {
+        let ident = quote::format_ident!("gen_schema_{}", group);
+        let doc = format!(
+            "The document with only the methods of the `{}` tag group.",
+            group
+        );
+        quote! {
+            #[doc = #doc]
+            pub fn #ident() -> OpenrpcDocument {
+                gen_schema_for_tags(&[#(#tags),*])
+            }
+        }
+    });
+
+    let backend = options.backend.as_ref().map(|backend| {
+        quote! {
+            /// The document converted by the backend of the trait.
+            pub fn gen_backend_document() -> ::core::result::Result<
+                <#backend as ::open_rpc_schema::backend::SchemaBackend>::Document,
+                <#backend as ::open_rpc_schema::backend::SchemaBackend>::Error,
+            > {
+                <#backend as ::open_rpc_schema::backend::SchemaBackend>::convert(gen_schema())
+            }
+        }
+    });
+
+    let breaking_changes = match &options.baseline {
+        Some(path) => {
+            let signatures: Vec<_> = schemas
+                .iter()
+                .map(|schema| schema.signature(options))
+                .collect();
+            Some(baseline::compute_breaking_changes(
+                path,
+                &signatures,
+                trait_ident.span(),
+            )?)
+        }
+        None => None,
+    };
+
+    let discover_method = options.discover_method.as_deref().unwrap_or(RPC_DISCOVER);
+
+    // the caches need `std::sync::OnceLock`, without `std` only the document
+    // is built, on every call
+    let (discover_for, caches) = if cfg!(feature = "std") {
+        (
+            quote! {
+            /// The document served to a client of the tag group `group`, e.g. the
+            /// tier or role of the caller answering `rpc.discover`. `None` for a
+            /// group the trait does not declare.
+            pub fn discover_for(group: &str) -> ::core::option::Option<OpenrpcDocument> {
+                for (name, tags) in TAG_GROUPS {
+                    if *name == group {
+                        return Some(cached_schema().filter_tags(tags));
+                    }
+                }
+                None
+            }
+                },
+            quote! {
+            /// The document built once and shared for the lifetime of the program.
+            pub fn cached_schema() -> &'static OpenrpcDocument {
+                static DOCUMENT: ::open_rpc_schema::__private::OnceLock<OpenrpcDocument> =
+                    ::open_rpc_schema::__private::OnceLock::new();
+                DOCUMENT.get_or_init(#embedded)
+            }
+
+            /// The JSON Pointers to the methods and params of the cached document.
+            pub fn pointer_index() -> &'static ::open_rpc_schema::pointer::PointerIndex {
+                static INDEX: ::open_rpc_schema::__private::OnceLock<::open_rpc_schema::pointer::PointerIndex> =
+                    ::open_rpc_schema::__private::OnceLock::new();
+                INDEX.get_or_init(|| cached_schema().pointer_index())
+            }
+
+            /// Validates request params against the cached document.
+            pub fn validator() -> &'static ::open_rpc_schema::validate::Validator {
+                static VALIDATOR: ::open_rpc_schema::__private::OnceLock<::open_rpc_schema::validate::Validator> =
+                    ::open_rpc_schema::__private::OnceLock::new();
+                VALIDATOR.get_or_init(|| ::open_rpc_schema::validate::Validator::new(cached_schema()))
+            }
+
+            /// The retry, rate limit and timeout policy of every method, for the
+            /// clients calling it.
+            pub fn call_policies() -> &'static ::open_rpc_schema::__private::HashMap<::open_rpc_schema::__private::String, ::open_rpc_schema::policy::CallPolicy> {
+                static POLICIES: ::open_rpc_schema::__private::OnceLock<::open_rpc_schema::__private::HashMap<::open_rpc_schema::__private::String, ::open_rpc_schema::policy::CallPolicy>> =
+                    ::open_rpc_schema::__private::OnceLock::new();
+                POLICIES.get_or_init(|| cached_schema().call_policies())
+            }
+
+            /// The JSON of the cached document, what `openrpc_json!` expands to.
+            pub fn json() -> &'static str {
+                static JSON: ::open_rpc_schema::__private::OnceLock<::open_rpc_schema::__private::String> =
+                    ::open_rpc_schema::__private::OnceLock::new();
+                JSON.get_or_init(|| ::open_rpc_schema::__private::serde_json::to_string(cached_schema())
+                    .expect("the document serializes to JSON"))
+            }
+                },
+        )
+    } else {
+        (quote!(), quote!())
+    };
+
+    // the generated code only names items through absolute paths or these
+    // imports, so it does not depend on the std prelude of the caller
+    Ok(quote! {
+        use ::open_rpc_schema::document::*;
+        #[allow(unused_imports)]
+        use ::open_rpc_schema::__private::{vec, Default, None, Some, ToString};
+
+        #schemars_schema_fn
+
+        #generate_schema_method
+
+        #slim_schema_method
+
+        /// The methods whose params and result are all primitives.
+        #cfg
+        pub const CONST_METHODS: &[::open_rpc_schema::table::ConstMethod] = &[#(#const_methods,)*];
+
+        /// Every method with its params, for introspection without the
+        /// document.
+        #cfg
+        pub static STATIC_METHODS: &[::open_rpc_schema::table::StaticMethod] = &[#(#static_methods,)*];
+
+        /// The methods of the trait as the attributes registered them, for
+        /// generators building on this crate.
+        pub static REGISTRATIONS: &[::open_rpc_schema::registration::MethodRegistration] =
+            &[#(#registrations,)*];
+
+        #stub
+
+        #backend
+
+        #(#notifications)*
+
+        #requests
+
+        #(#golden_tests)*
+
+        #breaking_changes
+
+        /// The document with only the methods tagged with one of `tags`.
+        pub fn gen_schema_for_tags(tags: &[&str]) -> OpenrpcDocument {
+            gen_schema().filter_tags(tags)
+        }
+
+        #(#tag_groups)*
+
+        /// The tags each tag group may see.
+        pub const TAG_GROUPS: &[(&str, &[&str])] = &[#((#group_names, &[#(#group_tags),*])),*];
+
+        #discover_for
+
+        /// The method serving the document, to register the discover handler
+        /// under.
+        pub const DISCOVER_METHOD: &str = #discover_method;
+
+        /// Names this document in generic code, e.g. `open_rpc_schema::dump::run`.
+        pub struct Schema;
+
+        impl ::open_rpc_schema::document::SchemaProvider for Schema {
+            fn gen_schema() -> OpenrpcDocument {
+                gen_schema()
+            }
+        }
+
+        #caches
+    })
+}
+
+/// Statements applying the trait level options to `document`, leaving out
+/// the examples for the `slim` document.
+fn compute_document_extras(options: &DocumentOptions, slim: bool) -> proc_macro2::TokenStream {
+    let mut extras = quote!();
+    if let Some(batch) = &options.batch {
+        let supported = batch.supported;
+        let mut entries = vec![("supported", quote!(#supported))];
+        if let Some(max_size) = batch.max_size {
+            entries.push(("maxSize", quote!(#max_size)));
+        }
+        extras.extend(insert_extension(
+            quote!(document),
+            "x-batch",
+            json_object(entries),
+        ));
+    }
+    if !options.metadata.is_empty() {
+        let inputs = options.metadata.iter().map(|input| {
+            let mut entries = vec![
+                ("in", quote!("header")),
+                ("name", {
+                    let header = &input.header;
+                    quote!(#header)
+                }),
+            ];
+            if let Some(description) = &input.description {
+                entries.push(("description", quote!(#description)));
+            }
+            json_object(entries)
+        });
+        extras.extend(insert_extension(
+            quote!(document),
+            "x-metadata",
+            quote!([#(#inputs),*]),
+        ));
+    }
+    if let Some(external_docs) = &options.external_docs {
+        let url = &external_docs.url;
+        let description = option_str(&external_docs.description, quote!(.to_string()));
+        extras.extend(quote! {
+            document.external_docs = Some(ExternalDocumentationObject {
+                description: #description,
+                url: #url.to_string(),
+            });
+        });
+    }
+    for (name, example) in options.examples.iter().filter(|_| !slim) {
+        extras.extend(quote! {
+            document.add_example(#name, ::open_rpc_schema::document::example_value(#example, None));
+        });
+    }
+    if let Some(prefix) = &options.display_strip_prefix {
+        // the wire names stay, docs generators title the pages with these
+        extras.extend(quote! {
+            for method_object in &mut document.methods {
+                if let Some(display_name) = method_object.name.strip_prefix(#prefix) {
+                    method_object.extensions.insert(
+                        "x-display-name".to_string(),
+                        ::open_rpc_schema::__private::serde_json::json!(display_name),
                     );
-                    method_object.result = returns;
-                    method_object.params = #arg_schemas;
-                    method_object.summary = Some(#summary.to_string());
-                    method_object
-                }};
-                schema_methods.push(schema_method);
+                }
             }
+        });
+    }
+    if options.standard_errors {
+        extras.extend(quote!(document.add_standard_errors();));
+    }
+    if let Some(method) = &options.discover_method {
+        extras.extend(insert_extension(
+            quote!(document),
+            "x-discover-method",
+            quote!(#method),
+        ));
+    }
+    match options.synthesize_examples.filter(|_| !slim) {
+        Some(Some(seed)) => extras.extend(quote!(document.synthesize_examples_with_seed(#seed);)),
+        Some(None) => extras.extend(quote!(document.synthesize_examples();)),
+        None => {}
+    }
+    if let Some(auth) = &options.auth {
+        let scheme = &auth.scheme;
+        let mut entries = vec![("scheme", quote!(#scheme))];
+        if let Some(description) = &auth.description {
+            entries.push(("description", quote!(#description)));
         }
+        extras.extend(insert_extension(
+            quote!(document),
+            "x-auth",
+            json_object(entries),
+        ));
     }
+    // last, the synthesized examples look the definitions up in the schemas
+    if options
+        .schemars
+        .as_ref()
+        .is_some_and(SchemarsOptions::refers_to_components)
+    {
+        extras.extend(quote!(document.hoist_definitions();));
+    }
+    if !options.component_names.renames.is_empty() {
+        let (from, to): (Vec<_>, Vec<_>) = options.component_names.renames.iter().cloned().unzip();
+        extras.extend(quote!(document.rename_schemas(&[#((#from, #to)),*]);));
+    }
+    if options.dedupe_schemas {
+        extras.extend(quote!(document.dedupe_schemas();));
+    }
+    extras
+}
 
-    let generate_schema_method = syn::parse_quote! {
-    pub fn gen_schema() -> OpenrpcDocument {
-        let mut document = OpenrpcDocument::default();
-        let args_tuple = [#(#schema_methods,)*];
-        for a in args_tuple.to_vec(){
-            document.add_object_method(a);
+/// Statements applying the method attributes to `method_object`, shared by
+/// the inline and the compact expansion. The `slim` document gets no
+/// examples.
+fn compute_method_extras(
+    method: &RpcMethod,
+    arg_names: &[syn::Ident],
+    arg_types: &[syn::Type],
+    options: &DocumentOptions,
+    slim: bool,
+) -> Result<proc_macro2::TokenStream> {
+    let attr = &method.attr;
+    let mut extras = quote!();
+    // the trait level default, before what may reshape the params
+    if let (None, Some(structure)) = (attr.param_structure, options.param_structure) {
+        extras.extend(structure.to_tokens());
+    }
+    if !arg_types.is_empty() {
+        let by_name =
+            attr.param_structure.or(options.param_structure) == Some(ParamStructure::ByName);
+        let required = required_params(arg_types, by_name);
+        extras.extend(quote!(method_object.mark_required(&[#(#required),*]);));
+    }
+    for tag in &attr.tags {
+        extras.extend(quote!(method_object.add_tag(#tag);));
+    }
+    for error in &attr.errors {
+        let (code, message) = (error.code, &error.message);
+        let data = match &error.data {
+            Some(ty) => {
+                let reference = format!("#/components/schemas/{}", component_name(ty, options)?);
+                quote!(Some(
+                    ::open_rpc_schema::__private::serde_json::json!({ "$ref": #reference })
+                ))
+            }
+            None => quote!(None),
+        };
+        extras.extend(quote!(method_object.add_error(#code, #message, #data);));
+    }
+    // the errors the error type of the method documents itself, if any
+    if let AttributeKind::Rpc { .. } = attr.kind {
+        if let Some(error) = try_infer_error(&method.trait_item.sig.output) {
+            if !is_self_type(Some(error.clone())) {
+                extras.extend(quote! {
+                    {
+                        use ::open_rpc_schema::errors::{HarvestErrors as _, NoErrors as _};
+                        method_object.add_errors(
+                            (&::open_rpc_schema::errors::ErrorsOf::<#error>::new()).harvest(),
+                        );
+                    }
+                });
+            }
+        }
+    }
+    if let Some((envelope, _)) = notification_of(method) {
+        let reference = format!("#/components/schemas/{}", envelope);
+        extras.extend(insert_extension(
+            quote!(method_object),
+            "x-notification",
+            quote!({ "$ref": #reference }),
+        ));
+    }
+    if !attr.roles.is_empty() {
+        let roles = &attr.roles;
+        extras.extend(insert_extension(
+            quote!(method_object),
+            "x-roles",
+            quote!([#(#roles),*]),
+        ));
+    }
+    let has_metadata = matches!(
+        attr.kind,
+        AttributeKind::Rpc {
+            has_metadata: true,
+            ..
+        }
+    );
+    if has_metadata && !options.metadata.is_empty() {
+        // the headers the `Metadata` param of the method is extracted from
+        let headers = options.metadata.iter().map(|input| &input.header);
+        extras.extend(insert_extension(
+            quote!(method_object),
+            "x-metadata",
+            quote!([#(#headers),*]),
+        ));
+    }
+    if let Some(timeout_ms) = attr.timeout_ms {
+        extras.extend(insert_extension(
+            quote!(method_object),
+            "x-timeout",
+            quote!(#timeout_ms),
+        ));
+    }
+    if let Some(sla) = &attr.sla {
+        extras.extend(insert_extension(
+            quote!(method_object),
+            "x-sla",
+            quote!(#sla),
+        ));
+    }
+    if attr.readonly {
+        extras.extend(insert_extension(
+            quote!(method_object),
+            "x-readonly",
+            quote!(true),
+        ));
+    }
+    if let Some(rate_limit) = &attr.rate_limit {
+        let (requests, per_ms) = (rate_limit.requests, rate_limit.per_ms);
+        extras.extend(insert_extension(
+            quote!(method_object),
+            "x-rate-limit",
+            json_object(vec![
+                ("requests", quote!(#requests)),
+                ("perMs", quote!(#per_ms)),
+            ]),
+        ));
+    }
+    if let Some(idempotent) = attr.idempotent {
+        extras.extend(insert_extension(
+            quote!(method_object),
+            "x-idempotent",
+            quote!(#idempotent),
+        ));
+    }
+    for (param, max_bytes) in &attr.max_bytes {
+        let param = param_name(method, arg_names, param)?;
+        extras.extend(quote! {
+            if let Some(schema) = method_object.param_schema_mut(#param) {
+                schema.string().max_length = Some(#max_bytes);
+                schema.extensions.insert(
+                    "x-max-bytes".to_string(),
+                    ::open_rpc_schema::__private::serde_json::json!(#max_bytes),
+                );
+            }
+        });
+    }
+    for (param, range) in &attr.ranges {
+        let param = param_name(method, arg_names, param)?;
+        let maximum = if range.maximum_exclusive {
+            "exclusiveMaximum"
+        } else {
+            "maximum"
+        };
+        let bounds = [("minimum", range.minimum), (maximum, range.maximum)]
+            .into_iter()
+            .filter_map(|(keyword, bound)| {
+                let number = quote!(::open_rpc_schema::__private::serde_json::Number);
+                let bound = match bound? {
+                    Bound::Integer(bound) => match u64::try_from(bound) {
+                        Ok(bound) => quote!(Some(#number::from(#bound))),
+                        Err(_) => {
+                            let bound = bound as i64;
+                            quote!(Some(#number::from(#bound)))
+                        }
+                    },
+                    Bound::Float(bound) => quote!(#number::from_f64(#bound)),
+                };
+                Some(quote! {
+                    if let Some(bound) = #bound {
+                        ::open_rpc_schema::document::set_numeric_bound(schema, #keyword, bound);
+                    }
+                })
+            });
+        extras.extend(quote! {
+            if let Some(schema) = method_object.param_schema_mut(#param) {
+                #(#bounds)*
+            }
+        });
+    }
+    for (param, pattern) in &attr.patterns {
+        let param = param_name(method, arg_names, param)?;
+        extras.extend(quote! {
+            if let Some(schema) = method_object.param_schema_mut(#param) {
+                schema.string().pattern = Some(#pattern.to_string());
+            }
+        });
+    }
+    for (param, values) in &attr.one_of {
+        let ty = arg_names
+            .iter()
+            .zip(arg_types)
+            .find(|(arg, _)| *arg == param);
+        let param = param_name(method, arg_names, param)?;
+        let inner = ty.and_then(|(_, ty)| option_inner(ty));
+        if let Some(ty) = inner.or(ty.map(|(_, ty)| ty)) {
+            for value in values {
+                check_literal(value, ty)?;
+            }
+        }
+        // an absent optional param is sent as null, which the enum must allow
+        let null = inner.map(|_| quote!(::open_rpc_schema::__private::serde_json::Value::Null));
+        extras.extend(quote! {
+            if let Some(schema) = method_object.param_schema_mut(#param) {
+                schema.enum_values = Some(vec![#(
+                    ::open_rpc_schema::__private::serde_json::json!(#values),
+                )* #null]);
+            }
+        });
+    }
+    for (param, example) in attr.param_examples.iter().filter(|_| !slim) {
+        let param = param_name(method, arg_names, param)?;
+        extras.extend(quote! {
+            if let Some(schema) = method_object.param_schema_mut(#param) {
+                let example = ::open_rpc_schema::document::example_value(#example, Some(&*schema));
+                schema.metadata().examples.push(example);
+            }
+        });
+    }
+    for (param, example_ref) in attr.param_example_refs.iter().filter(|_| !slim) {
+        let param = param_name(method, arg_names, param)?;
+        // schema `examples` cannot hold references, so the shared value is
+        // inlined while the document keeps the named original in components
+        let example = options
+            .examples
+            .iter()
+            .find(|(name, _)| *name == example_ref.value())
+            .map(|(_, example)| example)
+            .ok_or_else(|| {
+                let msg = format!("No shared example named `{}`", example_ref.value());
+                syn::Error::new_spanned(example_ref, msg)
+            })?;
+        extras.extend(quote! {
+            if let Some(schema) = method_object.param_schema_mut(#param) {
+                let example = ::open_rpc_schema::document::example_value(#example, Some(&*schema));
+                schema.metadata().examples.push(example);
+            }
+        });
+    }
+    for (param, title) in &attr.param_titles {
+        let param = param_name(method, arg_names, param)?;
+        extras.extend(quote! {
+            if let Some(schema) = method_object.param_schema_mut(#param) {
+                schema.metadata().title = Some(#title.to_string());
+            }
+        });
+    }
+    if let Some(title) = &attr.result_title {
+        extras.extend(quote! {
+            if let Some(schema) = method_object.result_schema_mut() {
+                schema.metadata().title = Some(#title.to_string());
+            }
+        });
+    }
+    if let Some(pagination) = &attr.pagination {
+        let mut entries = vec![];
+        for (key, param) in [
+            ("cursorParam", &pagination.cursor_param),
+            ("limitParam", &pagination.limit_param),
+        ] {
+            if let Some(param) = param {
+                if !arg_names.iter().any(|arg| arg == param) {
+                    let msg = format!("`{}` is not a param of `{}`", param, method.name());
+                    return Err(syn::Error::new_spanned(&attr.attr, msg));
+                }
+                entries.push((key, quote!(#param)));
+            }
+        }
+        // the fields of the result are only known once its schema is built,
+        // so the result field is checked by a generated test instead
+        if let Some(field) = &pagination.result_field {
+            entries.push(("resultField", quote!(#field)));
+        }
+        extras.extend(insert_extension(
+            quote!(method_object),
+            "x-pagination",
+            json_object(entries),
+        ));
+    }
+    if attr.expand_params {
+        if arg_names.len() != 1 {
+            let msg = "expand_params expects a method taking a single struct param";
+            return Err(syn::Error::new_spanned(&attr.attr, msg));
         }
-        document
-    }};
+        // a param without properties of its own, e.g. an enum, stays as is
+        extras.extend(quote!(method_object.expand_params();));
+    }
+    if attr.params_as_object {
+        let optional = arg_names
+            .iter()
+            .zip(arg_types)
+            .filter(|(_, ty)| is_option(ty))
+            .map(|(name, _)| name.to_string());
+        extras.extend(quote!(method_object.params_as_object(&[#(#optional),*]);));
+    }
+    if let Some(structure) = attr.param_structure {
+        extras.extend(structure.to_tokens());
+    }
+    if attr.deny_unknown_params {
+        extras.extend(quote!(method_object.deny_unknown_params();));
+    }
+    // last, so the samples encode the params as finally documented
+    if options.code_samples && !slim {
+        let rust_fn = method.trait_item.sig.ident.to_string();
+        let rust_args = arg_names.iter().map(|arg| arg.to_string());
+        extras.extend(quote!(method_object.add_code_samples(#rust_fn, &[#(#rust_args),*]);));
+    }
+    Ok(extras)
+}
 
-    Ok(generate_schema_method)
+/// A function named after the trait method returning the body of a request
+/// to it, the params encoded the way the method documents them.
+fn compute_request_builder(
+    method: &RpcMethod,
+    arg_names: &[syn::Ident],
+    arg_types: &[syn::Type],
+    options: &DocumentOptions,
+) -> proc_macro2::TokenStream {
+    let attr = &method.attr;
+    let name = method.name();
+    let by_name = attr.param_structure.or(options.param_structure) == Some(ParamStructure::ByName);
+    let keys = arg_names.iter().map(|arg| arg.to_string());
+    let params = match (arg_names, arg_types) {
+        ([arg], [ty]) if attr.expand_params => {
+            // the fields of the struct are the params, unless the document
+            // left the param as is for having no properties of its own
+            let key = arg.to_string();
+            let schema = schema_fn(ty, options);
+            let unexpanded = if by_name {
+                quote!(::open_rpc_schema::__private::serde_json::json!({ #key: #arg }))
+            } else {
+                quote!(::open_rpc_schema::__private::serde_json::json!([#arg]))
+            };
+            quote! {{
+                let mut method_object = ::open_rpc_schema::document::MethodObject::new(
+                    ::open_rpc_schema::__private::String::new(),
+                    ::core::option::Option::None,
+                );
+                method_object.params = ::open_rpc_schema::__private::vec![
+                    ::open_rpc_schema::document::ContentDescriptorOrReference::from_schema(
+                        ::open_rpc_schema::__private::String::from(#key),
+                        ::core::option::Option::None,
+                        #schema(),
+                    ),
+                ];
+                if method_object.expand_params() {
+                    ::open_rpc_schema::__private::serde_json::json!(#arg)
+                } else {
+                    #unexpanded
+                }
+            }}
+        }
+        _ if attr.params_as_object => quote! {
+            ::open_rpc_schema::__private::serde_json::json!([{ #(#keys: #arg_names),* }])
+        },
+        _ if by_name => quote! {
+            ::open_rpc_schema::__private::serde_json::json!({ #(#keys: #arg_names),* })
+        },
+        _ => quote!(::open_rpc_schema::__private::serde_json::json!([#(#arg_names),*])),
+    };
+    let ident = &method.ident;
+    let cfg = method
+        .trait_item
+        .attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("cfg"));
+    let doc = format!("The body of a `{}` request.", name);
+    quote! {
+        #(#cfg)*
+        #[doc = #doc]
+        pub fn #ident(#(#arg_names: #arg_types),*) -> ::open_rpc_schema::__private::serde_json::Value {
+            let params: ::open_rpc_schema::__private::serde_json::Value = #params;
+            ::open_rpc_schema::__private::serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": #name,
+                "params": params,
+            })
+        }
+    }
 }
 
-fn get_doc_comments(attrs: &[syn::Attribute]) -> Vec<syn::Attribute> {
-    let mut doc_comments = vec![];
-    for attr in attrs {
-        match attr {
-            syn::Attribute {
-                path: syn::Path { segments, .. },
-                ..
-            } => match &segments[0] {
-                syn::PathSegment { ident, .. } => {
-                    if ident.to_string() == "doc" {
-                        doc_comments.push(attr.to_owned());
+/// Expands to the `MethodRegistration` row of the method.
+fn compute_registration(method: &RpcMethod) -> Result<proc_macro2::TokenStream> {
+    let args = compute_args(method);
+    let arg_names = compute_arg_identifiers(&args)?;
+    let arg_types = compute_arg_type(&args)?;
+    let (kind, subscription, has_metadata) = match &method.attr.kind {
+        AttributeKind::Rpc { has_metadata, .. } => (quote!(Method), None, *has_metadata),
+        AttributeKind::PubSub {
+            subscription_name,
+            kind,
+        } => {
+            let kind = match kind {
+                PubSubMethodKind::Subscribe => quote!(Subscribe),
+                PubSubMethodKind::Unsubscribe => quote!(Unsubscribe),
+            };
+            let has_metadata = takes_metadata(&method.trait_item);
+            (kind, Some(subscription_name), has_metadata)
+        }
+    };
+    let name = method.name();
+    let rust_name = method.trait_item.sig.ident.to_string();
+    let subscription = option_str(&subscription.cloned(), quote!());
+    let aliases = &method.attr.aliases;
+    let returns = match &method.trait_item.sig.output {
+        syn::ReturnType::Default => quote!("()"),
+        syn::ReturnType::Type(_, ty) => quote!(::core::stringify!(#ty)),
+    };
+    let cfg = method
+        .trait_item
+        .attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("cfg"));
+    Ok(quote! {
+        #(#cfg)*
+        ::open_rpc_schema::registration::MethodRegistration {
+            name: #name,
+            rust_name: #rust_name,
+            kind: ::open_rpc_schema::registration::RegistrationKind::#kind,
+            subscription: #subscription,
+            aliases: &[#(#aliases),*],
+            has_metadata: #has_metadata,
+            params: &[#(::open_rpc_schema::registration::RegisteredParam {
+                name: ::core::stringify!(#arg_names),
+                rust_type: ::core::stringify!(#arg_types),
+            },)*],
+            returns: #returns,
+        }
+    })
+}
+
+/// The `#[test]` sending the example requests of the method to `handler`.
+fn compute_golden_test(method: &RpcMethod, handler: &syn::Path) -> proc_macro2::TokenStream {
+    let name = method.name();
+    let ident = quote::format_ident!("golden_{}", method.ident);
+    let cfg = method
+        .trait_item
+        .attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("cfg"));
+    quote! {
+        #(#cfg)*
+        #[cfg(test)]
+        #[test]
+        fn #ident() {
+            let checked = ::open_rpc_schema::golden::check_examples(&gen_schema(), #name, #handler);
+            if let ::core::result::Result::Err(failure) = checked {
+                ::core::panic!("{}", failure);
+            }
+        }
+    }
+}
+
+/// The `#[test]` checking that the result of a paginated method declares its
+/// `result_field`.
+fn compute_result_field_test(method: &RpcMethod) -> Option<proc_macro2::TokenStream> {
+    let field = method.attr.pagination.as_ref()?.result_field.as_ref()?;
+    let name = method.name();
+    let ident = quote::format_ident!("result_field_{}", method.ident);
+    let cfg = method
+        .trait_item
+        .attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("cfg"));
+    Some(quote! {
+        #(#cfg)*
+        #[cfg(test)]
+        #[test]
+        fn #ident() {
+            ::core::assert!(
+                ::open_rpc_schema::validate::Validator::new(&gen_schema()).result_declares(#name, #field),
+                "result_field `{}` is not a property of the result of `{}`",
+                #field,
+                #name,
+            );
+        }
+    })
+}
+
+/// Statements registering the schemas the method refers to on `document`.
+fn compute_method_components(
+    method: &RpcMethod,
+    options: &DocumentOptions,
+) -> Result<proc_macro2::TokenStream> {
+    let mut components = quote!();
+    for ty in method
+        .attr
+        .errors
+        .iter()
+        .filter_map(|error| error.data.as_ref())
+    {
+        let name = component_name(ty, options)?;
+        let schema = schema_fn(ty, options);
+        components.extend(quote!(document.add_schema(#name, #schema());));
+    }
+    if let (
+        Some((envelope, item)),
+        AttributeKind::PubSub {
+            subscription_name, ..
+        },
+    ) = (notification_of(method), &method.attr.kind)
+    {
+        let name = envelope;
+        let schema = schema_fn(&item, options);
+        components.extend(quote! {
+            document.add_schema(
+                #name,
+                ::open_rpc_schema::notification::envelope_schema(#subscription_name, #schema()),
+            );
+        });
+    }
+    Ok(components)
+}
+
+/// The `fn() -> JSONSchema` generating the schema of `ty`.
+fn schema_fn(ty: &syn::Type, options: &DocumentOptions) -> proc_macro2::TokenStream {
+    match &options.schema_generator {
+        Some(generator) => quote! {
+            <#generator as ::open_rpc_schema::generator::GenerateSchema<#ty>>::generate
+        },
+        None if options.schemars.is_some() => quote!(schemars_schema::<#ty>),
+        None => quote!(::open_rpc_schema::document::schema_for::<#ty>),
+    }
+}
+
+/// The generic schema function of the module, building the schemas with the
+/// `schemars` settings of the trait.
+fn schemars_schema_fn(options: &DocumentOptions) -> Option<proc_macro2::TokenStream> {
+    let SchemarsOptions {
+        inline_subschemas,
+        definitions_path,
+        option_nullable,
+        option_add_null_type,
+        bool_schemas,
+    } = options.schemars.as_ref()?;
+    Some(quote! {
+        fn schemars_schema<T>() -> JSONSchema
+        where
+            T: ?::core::marker::Sized + ::open_rpc_schema::schemars::JsonSchema,
+        {
+            ::open_rpc_schema::generator::SchemarsSettings {
+                inline_subschemas: #inline_subschemas,
+                definitions_path: #definitions_path,
+                option_nullable: #option_nullable,
+                option_add_null_type: #option_add_null_type,
+                bool_schemas: #bool_schemas,
+            }
+            .schema_for::<T>()
+        }
+    })
+}
+
+/// The name of the schema of `ty` in the components, its last path segment
+/// unless `component_names` asks for more. The renames are applied to the
+/// whole document at runtime, with the definitions `schemars` names.
+fn component_name(ty: &syn::Type, options: &DocumentOptions) -> Result<String> {
+    let naming = &options.component_names;
+    let path = match ty {
+        syn::Type::Path(path) => &path.path,
+        _ => return Err(syn::Error::new_spanned(ty, "Expected a type path")),
+    };
+    let last = path
+        .segments
+        .last()
+        .ok_or_else(|| syn::Error::new_spanned(ty, "Expected a type path"))?;
+    let mut name = last.ident.to_string();
+    if naming.generics {
+        let args = type_args(&last.arguments);
+        if !args.is_empty() {
+            let args = args.into_iter().map(schemars_name).collect::<Vec<_>>();
+            name = format!("{}_for_{}", name, args.join("_and_"));
+        }
+    }
+    if naming.module_path {
+        let modules = path.segments.iter().rev().skip(1).rev();
+        let modules = modules
+            .map(|segment| segment.ident.to_string())
+            .filter(|module| !matches!(module.as_str(), "crate" | "self" | "super"));
+        name = modules.chain([name]).collect::<Vec<_>>().join("_");
+    }
+    Ok(name)
+}
+
+/// The name `schemars` gives the schema of `ty`, for naming the generic args
+/// the way it names the definitions of generic types.
+fn schemars_name(ty: &syn::Type) -> String {
+    let names = |tys: Vec<&syn::Type>| tys.into_iter().map(schemars_name).collect::<Vec<_>>();
+    let segment = match ty {
+        syn::Type::Path(path) => match path.path.segments.last() {
+            Some(segment) => segment,
+            None => return String::new(),
+        },
+        syn::Type::Reference(reference) => return schemars_name(&reference.elem),
+        syn::Type::Paren(paren) => return schemars_name(&paren.elem),
+        syn::Type::Slice(slice) => return format!("Array_of_{}", schemars_name(&slice.elem)),
+        syn::Type::Tuple(tuple) if tuple.elems.is_empty() => return "Null".to_string(),
+        syn::Type::Tuple(tuple) => {
+            return format!(
+                "Tuple_of_{}",
+                names(tuple.elems.iter().collect()).join("_and_")
+            )
+        }
+        _ => return quote!(#ty).to_string(),
+    };
+    let ident = segment.ident.to_string();
+    let args = names(type_args(&segment.arguments));
+    let arg = |index: usize| args.get(index).cloned().unwrap_or_default();
+    match ident.as_str() {
+        "bool" => "Boolean".to_string(),
+        "str" | "String" | "Path" | "PathBuf" => "String".to_string(),
+        "char" => "Character".to_string(),
+        "f32" => "float".to_string(),
+        "f64" => "double".to_string(),
+        "i8" | "i16" | "i32" | "i64" | "i128" => format!("int{}", &ident[1..]),
+        "u8" | "u16" | "u32" | "u64" | "u128" => format!("uint{}", &ident[1..]),
+        "isize" => "int".to_string(),
+        "usize" => "uint".to_string(),
+        "Option" => format!("Nullable_{}", arg(0)),
+        "Vec" | "VecDeque" | "LinkedList" => format!("Array_of_{}", arg(0)),
+        "HashSet" | "BTreeSet" => format!("Set_of_{}", arg(0)),
+        "HashMap" | "BTreeMap" => format!("Map_of_{}", arg(1)),
+        "Box" | "Rc" | "Arc" | "Cow" | "Cell" | "RefCell" | "Mutex" | "RwLock" => {
+            args.last().cloned().unwrap_or_default()
+        }
+        "Result" => format!("Result_of_{}_or_{}", arg(0), arg(1)),
+        _ if args.is_empty() => ident,
+        _ => format!("{}_for_{}", ident, args.join("_and_")),
+    }
+}
+
+/// The type args of a path segment, without its lifetimes and consts.
+fn type_args(arguments: &syn::PathArguments) -> Vec<&syn::Type> {
+    match arguments {
+        syn::PathArguments::AngleBracketed(args) => args
+            .args
+            .iter()
+            .filter_map(|arg| match arg {
+                syn::GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// The name of the constrained `param`, failing when the method has no such
+/// param.
+fn param_name(method: &RpcMethod, arg_names: &[syn::Ident], param: &syn::Ident) -> Result<String> {
+    if arg_names.iter().any(|arg| arg == param) {
+        Ok(param.to_string())
+    } else {
+        let msg = format!("`{}` is not a param of `{}`", param, method.name());
+        Err(syn::Error::new_spanned(param, msg))
+    }
+}
+
+/// Statement inserting the `x-` extension `key` into `target.extensions`.
+fn insert_extension(
+    target: proc_macro2::TokenStream,
+    key: &str,
+    value: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    quote! {
+        #target.extensions.insert(
+            #key.to_string(),
+            ::open_rpc_schema::__private::serde_json::json!(#value),
+        );
+    }
+}
+
+/// A `json!` object literal with the given entries.
+fn json_object(entries: Vec<(&str, proc_macro2::TokenStream)>) -> proc_macro2::TokenStream {
+    let (keys, values): (Vec<_>, Vec<_>) = entries.into_iter().unzip();
+    quote!({ #(#keys: #values),* })
+}
+
+/// Everything the generated code needs to know about a single documented method.
+struct MethodSchema {
+    name: String,
+    summary: String,
+    arg_names: Vec<syn::Ident>,
+    arg_types: Vec<syn::Type>,
+    returns: syn::Type,
+    description: Option<String>,
+    /// Statements documenting everything else on `method_object`.
+    extras: proc_macro2::TokenStream,
+    /// The `extras` of the `slim` document, empty without `slim`.
+    slim_extras: proc_macro2::TokenStream,
+    /// Whether each param is required, for the static tables.
+    required: Vec<bool>,
+    tags: Vec<String>,
+    /// Statements adding what the method refers to to `document`.
+    components: proc_macro2::TokenStream,
+    /// The `#[cfg(..)]` attributes of the trait method, so a method compiled
+    /// out is also left out of the document.
+    cfg: Vec<syn::Attribute>,
+    /// The function of the `requests` module building a request to the
+    /// method, empty without `request_builders`.
+    request: proc_macro2::TokenStream,
+    /// The test checking the answers to the examples of the method, empty
+    /// without `golden_tests`.
+    golden: proc_macro2::TokenStream,
+    /// Where the warnings about the method point.
+    span: proc_macro2::Span,
+    /// Whether the params are documented under other names than the args,
+    /// by `expand_params` or `params_as_object`.
+    reshaped: bool,
+}
+
+impl MethodSchema {
+    fn new(method: &RpcMethod, options: &DocumentOptions) -> Result<Self> {
+        let args = compute_args(method);
+        let arg_names: Vec<_> = compute_arg_identifiers(&args)?
+            .into_iter()
+            .cloned()
+            .collect();
+        let mut arg_types = compute_arg_type(&args)?;
+        let returns = match &method.attr.kind {
+            AttributeKind::Rpc { returns, .. } => compute_returns(&method.trait_item, returns)?,
+            AttributeKind::PubSub { kind, .. } => {
+                // the subscription id types of the pubsub crates have no schema
+                for ty in &mut arg_types {
+                    if is_subscription_id(ty) {
+                        *ty = syn::parse_quote!(::open_rpc_schema::document::SubscriptionId);
                     }
                 }
+                match kind {
+                    PubSubMethodKind::Subscribe => {
+                        syn::parse_quote!(::open_rpc_schema::document::SubscriptionId)
+                    }
+                    PubSubMethodKind::Unsubscribe => compute_returns(&method.trait_item, &None)?,
+                }
+            }
+        };
+        let by_name =
+            method.attr.param_structure.or(options.param_structure) == Some(ParamStructure::ByName);
+        let required = required_params(&arg_types, by_name);
+        let extras = compute_method_extras(method, &arg_names, &arg_types, options, false)?;
+        let slim_extras = if options.slim {
+            compute_method_extras(method, &arg_names, &arg_types, options, true)?
+        } else {
+            quote!()
+        };
+        let components = compute_method_components(method, options)?;
+        let request = if options.request_builders {
+            compute_request_builder(method, &arg_names, &arg_types, options)
+        } else {
+            quote!()
+        };
+        let mut golden = options
+            .golden_tests
+            .as_ref()
+            .map(|handler| compute_golden_test(method, handler))
+            .unwrap_or_default();
+        golden.extend(compute_result_field_test(method));
+        // the attributes override what the doc comments say
+        let doc_text = if method.attr.no_doc_extraction {
+            None
+        } else {
+            docs::doc_text(&get_doc_comments(&method.trait_item.attrs))
+                .map(|text| docs::sanitize(&text, options.docs_base_url.as_deref()))
+        };
+        let (doc_summary, doc_description) = doc_text
+            .as_deref()
+            .map_or((None, None), docs::split_summary);
+        Ok(MethodSchema {
+            name: method.name().to_string(),
+            summary: method
+                .attr
+                .summary
+                .clone()
+                .or(doc_summary)
+                .unwrap_or_default(),
+            description: method.attr.description.clone().or(doc_description),
+            arg_names,
+            arg_types,
+            returns,
+            extras,
+            slim_extras,
+            required,
+            tags: method.attr.tags.clone(),
+            components,
+            request,
+            golden,
+            span: method.trait_item.sig.ident.span(),
+            reshaped: method.attr.expand_params || method.attr.params_as_object,
+            cfg: method
+                .trait_item
+                .attrs
+                .iter()
+                .filter(|attr| attr.path.is_ident("cfg"))
+                .cloned()
+                .collect(),
+        })
+    }
+
+    /// The `<subscription>Unsubscribe` method taking the subscription id and
+    /// returning whether it was removed, which pubsub servers conventionally
+    /// provide for a subscription declaring no unsubscribe method.
+    fn conventional_unsubscribe(subscription_name: &str, options: &DocumentOptions) -> Self {
+        let structure = options.param_structure.map(ParamStructure::to_tokens);
+        let extras = quote!(#structure method_object.mark_required(&[true]););
+        MethodSchema {
+            name: format!("{}Unsubscribe", subscription_name),
+            summary: String::new(),
+            arg_names: vec![syn::Ident::new("id", proc_macro2::Span::call_site())],
+            arg_types: vec![syn::parse_quote!(
+                ::open_rpc_schema::document::SubscriptionId
+            )],
+            returns: syn::parse_quote!(bool),
+            description: None,
+            slim_extras: if options.slim {
+                extras.clone()
+            } else {
+                quote!()
             },
+            extras,
+            required: vec![true],
+            tags: vec![],
+            components: quote!(),
+            cfg: vec![],
+            request: quote!(),
+            golden: quote!(),
+            span: proc_macro2::Span::call_site(),
+            reshaped: false,
+        }
+    }
+
+    /// What the clients of the method depend on, for the baseline check.
+    fn signature(&self, options: &DocumentOptions) -> baseline::Signature<'_> {
+        let params = self
+            .arg_names
+            .iter()
+            .zip(&self.arg_types)
+            .zip(&self.required)
+            .map(|((name, ty), required)| baseline::Param {
+                name: name.to_string(),
+                required: *required,
+                shape: schema_shape(ty, options),
+            });
+        let returns = &self.returns;
+        baseline::Signature {
+            name: &self.name,
+            params: (!self.reshaped).then(|| params.collect()),
+            returns: quote!(#returns).to_string(),
+            result_shape: schema_shape(returns, options),
+            span: self.span,
+        }
+    }
+
+    /// Statement linking the result of a subscribe method, the subscription
+    /// id, to the param of this unsubscribe method.
+    fn link_from_subscribe(&self) -> proc_macro2::TokenStream {
+        let name = &self.name;
+        let params = match self.arg_names.first() {
+            Some(id) => {
+                let id = id.to_string();
+                quote!(Some(
+                    ::open_rpc_schema::__private::serde_json::json!({ #id: "$result" })
+                ))
+            }
+            None => quote!(None),
+        };
+        quote! {
+            method_object
+                .links
+                .get_or_insert_with(Default::default)
+                .push(LinkOrReference::LinkObject(LinkObject {
+                    name: Some(#name.to_string()),
+                    summary: None,
+                    method: Some(#name.to_string()),
+                    description: None,
+                    params: #params,
+                    server: None,
+                }));
+        }
+    }
+
+    /// The description and extras of the full or the `slim` document.
+    fn embedded(&self, slim: bool) -> (&Option<String>, &proc_macro2::TokenStream) {
+        if slim {
+            (&None, &self.slim_extras)
+        } else {
+            (&self.description, &self.extras)
+        }
+    }
+
+    /// Expands to an expression building the `MethodObject` inline.
+    fn to_method_object(&self, options: &DocumentOptions, slim: bool) -> proc_macro2::TokenStream {
+        let MethodSchema {
+            name: rpc_name,
+            summary,
+            arg_names,
+            arg_types: args_types,
+            returns,
+            cfg,
+            ..
+        } = self;
+        let (description, extras) = self.embedded(slim);
+        let description = option_str(description, quote!(.to_string()));
+        let returns_schema = schema_fn(returns, options);
+        let args_schemas = args_types.iter().map(|ty| schema_fn(ty, options));
+        quote! {#(#cfg)* {
+            let mut method_object = MethodObject::new(#rpc_name.to_string(), #description);
+            let returns = ContentDescriptorOrReference::from_schema(
+                ::core::stringify!(#returns).to_string(),
+                None,
+                #returns_schema(),
+            );
+            method_object.result = returns;
+            method_object.params = vec![#(
+                ContentDescriptorOrReference::from_schema(
+                    ::core::stringify!(#arg_names).to_string(),
+                    None,
+                    #args_schemas(),
+                )
+            ),*];
+            method_object.summary = Some(#summary.to_string());
+            #extras
+            method_object
+        }}
+    }
+
+    /// Expands to a `MethodEntry` row of the compact metadata table.
+    fn to_method_entry(&self, options: &DocumentOptions, slim: bool) -> proc_macro2::TokenStream {
+        let MethodSchema {
+            name,
+            summary,
+            arg_names,
+            arg_types,
+            returns,
+            cfg,
+            ..
+        } = self;
+        let (description, extras) = self.embedded(slim);
+        let description = option_str(description, quote!());
+        let extend = if extras.is_empty() {
+            quote!(None)
+        } else {
+            quote!(Some(|method_object: &mut MethodObject| { #extras }))
+        };
+        let args_schemas = arg_types.iter().map(|ty| schema_fn(ty, options));
+        let returns_schema = schema_fn(returns, options);
+        quote! {
+            #(#cfg)*
+            ::open_rpc_schema::table::MethodEntry {
+                name: #name,
+                summary: #summary,
+                description: #description,
+                params: &[#(::open_rpc_schema::table::ContentEntry {
+                    name: ::core::stringify!(#arg_names),
+                    schema: #args_schemas,
+                },)*],
+                result: ::open_rpc_schema::table::ContentEntry {
+                    name: ::core::stringify!(#returns),
+                    schema: #returns_schema,
+                },
+                extend: #extend,
+            }
+        }
+    }
+
+    /// Expands to a `ConstMethod` row when every param and the result are
+    /// primitives.
+    fn to_const_method(&self) -> Option<proc_macro2::TokenStream> {
+        let MethodSchema {
+            name,
+            summary,
+            arg_names,
+            arg_types,
+            returns,
+            description,
+            cfg,
+            ..
+        } = self;
+        let description = option_str(description, quote!());
+        let arg_primitives = arg_types
+            .iter()
+            .map(primitive_of)
+            .collect::<Option<Vec<_>>>()?;
+        let returns_primitive = primitive_of(returns)?;
+        Some(quote! {
+            #(#cfg)*
+            ::open_rpc_schema::table::ConstMethod {
+                name: #name,
+                summary: #summary,
+                description: #description,
+                params: &[#(::open_rpc_schema::table::ConstContent {
+                    name: ::core::stringify!(#arg_names),
+                    ty: ::open_rpc_schema::table::Primitive::#arg_primitives,
+                },)*],
+                result: ::open_rpc_schema::table::ConstContent {
+                    name: ::core::stringify!(#returns),
+                    ty: ::open_rpc_schema::table::Primitive::#returns_primitive,
+                },
+            }
+        })
+    }
+
+    /// Expands to a `StaticMethod` row of the `STATIC_METHODS` table.
+    fn to_static_method(&self, options: &DocumentOptions) -> proc_macro2::TokenStream {
+        let MethodSchema {
+            name,
+            summary,
+            arg_names,
+            arg_types,
+            returns,
+            required,
+            tags,
+            cfg,
+            ..
+        } = self;
+        let (description, _) = self.embedded(options.slim);
+        let description = option_str(description, quote!());
+        let primitive = |ty: &syn::Type| match primitive_of(ty) {
+            Some(primitive) => quote!(Some(::open_rpc_schema::table::Primitive::#primitive)),
+            None => quote!(None),
+        };
+        let arg_primitives = arg_types.iter().map(primitive);
+        let returns_primitive = primitive(returns);
+        quote! {
+            #(#cfg)*
+            ::open_rpc_schema::table::StaticMethod {
+                name: #name,
+                summary: #summary,
+                description: #description,
+                tags: &[#(#tags),*],
+                params: &[#(::open_rpc_schema::table::StaticContent {
+                    name: ::core::stringify!(#arg_names),
+                    rust_type: ::core::stringify!(#arg_types),
+                    required: #required,
+                    primitive: #arg_primitives,
+                },)*],
+                result: ::open_rpc_schema::table::StaticContent {
+                    name: ::core::stringify!(#returns),
+                    rust_type: ::core::stringify!(#returns),
+                    required: true,
+                    primitive: #returns_primitive,
+                },
+            }
+        }
+    }
+}
+
+/// `Some(#value #suffix)` or `None`, for optional string fields.
+fn option_str(
+    value: &Option<String>,
+    suffix: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    match value {
+        Some(value) => quote!(Some(#value #suffix)),
+        None => quote!(None),
+    }
+}
+
+/// The `Primitive` variant matching `ty`, if it is one.
+fn primitive_of(ty: &syn::Type) -> Option<syn::Ident> {
+    let variant = match ty {
+        syn::Type::Tuple(tuple) if tuple.elems.is_empty() => "Unit",
+        syn::Type::Path(syn::TypePath { qself: None, path }) => {
+            match path.get_ident()?.to_string().as_str() {
+                "bool" => "Bool",
+                "char" => "Char",
+                "String" => "String",
+                "u8" => "U8",
+                "u16" => "U16",
+                "u32" => "U32",
+                "u64" => "U64",
+                "u128" => "U128",
+                "usize" => "Usize",
+                "i8" => "I8",
+                "i16" => "I16",
+                "i32" => "I32",
+                "i64" => "I64",
+                "i128" => "I128",
+                "isize" => "Isize",
+                "f32" => "F32",
+                "f64" => "F64",
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+    Some(syn::Ident::new(variant, proc_macro2::Span::call_site()))
+}
+
+fn get_doc_comments(attrs: &[syn::Attribute]) -> Vec<syn::Attribute> {
+    let mut doc_comments = vec![];
+    for attr in attrs {
+        let syn::PathSegment { ident, .. } = &attr.path.segments[0];
+        if ident == "doc" {
+            doc_comments.push(attr.to_owned());
         }
     }
     doc_comments
 }
 
-fn compute_args(method: &syn::TraitItemMethod) -> Punctuated<syn::FnArg, syn::token::Comma> {
+fn compute_args(method: &RpcMethod) -> Punctuated<syn::FnArg, syn::token::Comma> {
+    let pubsub_kind = match &method.attr.kind {
+        AttributeKind::PubSub { kind, .. } => Some(kind),
+        AttributeKind::Rpc { .. } => None,
+    };
     let mut args = Punctuated::new();
-    for arg in &method.sig.inputs {
+    for arg in &method.trait_item.sig.inputs {
         let ty = match arg {
             syn::FnArg::Typed(syn::PatType { ty, .. }) => ty,
             _ => continue,
@@ -92,10 +1624,21 @@ fn compute_args(method: &syn::TraitItemMethod) -> Punctuated<syn::FnArg, syn::to
             }) => segments,
             _ => continue,
         };
-        let ident = match &segments[0] {
-            syn::PathSegment { ident, .. } => ident,
-        };
-        if ident.to_string() == "Self" {
+        let syn::PathSegment { ident, arguments } = &segments[0];
+        if ident == "Self" {
+            continue;
+        }
+        // the optional metadata of unsubscribe methods
+        if pubsub_kind == Some(&PubSubMethodKind::Unsubscribe)
+            && ident == "Option"
+            && is_self_type(get_first_type_argument(arguments))
+        {
+            continue;
+        }
+        // the sink a subscribe method pushes its notifications to
+        if pubsub_kind == Some(&PubSubMethodKind::Subscribe)
+            && segments.last().is_some_and(|s| s.ident == "Subscriber")
+        {
             continue;
         }
         args.push(arg.to_owned());
@@ -103,6 +1646,212 @@ fn compute_args(method: &syn::TraitItemMethod) -> Punctuated<syn::FnArg, syn::to
     args
 }
 
+/// Whether a pubsub method takes the `Metadata`, or the optional `Metadata`
+/// of unsubscribe methods.
+fn takes_metadata(method: &syn::TraitItemMethod) -> bool {
+    method.sig.inputs.iter().any(|arg| match arg {
+        syn::FnArg::Typed(syn::PatType { ty, .. }) => match &**ty {
+            syn::Type::Path(syn::TypePath { path, .. }) => {
+                let syn::PathSegment { ident, arguments } = &path.segments[0];
+                ident == "Self"
+                    || (ident == "Option" && is_self_type(get_first_type_argument(arguments)))
+            }
+            _ => false,
+        },
+        syn::FnArg::Receiver(_) => false,
+    })
+}
+
+fn is_self_type(ty: Option<syn::Type>) -> bool {
+    matches!(ty, Some(syn::Type::Path(syn::TypePath { path, .. })) if path.segments[0].ident == "Self")
+}
+
+/// Whether each param must be given. By position only the trailing run of
+/// `Option` params may be left out, an `Option` before a required param has
+/// to be given, if only as `null`.
+fn required_params(arg_types: &[syn::Type], by_name: bool) -> Vec<bool> {
+    let trailing = arg_types
+        .iter()
+        .rev()
+        .take_while(|ty| is_option(ty))
+        .count();
+    arg_types
+        .iter()
+        .enumerate()
+        .map(|(i, ty)| {
+            if by_name {
+                !is_option(ty)
+            } else {
+                i < arg_types.len() - trailing
+            }
+        })
+        .collect()
+}
+
+fn is_option(ty: &syn::Type) -> bool {
+    matches!(ty, syn::Type::Path(syn::TypePath { path, .. })
+        if path.segments.last().is_some_and(|segment| segment.ident == "Option"))
+}
+
+/// The schema of `ty` as far as the macro knows it: the types schemars
+/// documents the primitive and collection types with, and the component of
+/// any other named type.
+fn schema_shape(ty: &syn::Type, options: &DocumentOptions) -> baseline::Shape {
+    use baseline::Shape;
+    let simple = |types: &[&'static str]| Shape::Types(types.to_vec());
+    match ty {
+        syn::Type::Reference(reference) => return schema_shape(&reference.elem, options),
+        syn::Type::Array(_) | syn::Type::Slice(_) => return simple(&["array"]),
+        syn::Type::Tuple(tuple) if tuple.elems.is_empty() => return simple(&["null"]),
+        syn::Type::Path(path) if path.qself.is_none() => {}
+        _ => return Shape::Unknown,
+    }
+    if let Some(inner) = option_inner(ty) {
+        return match schema_shape(inner, options) {
+            Shape::Types(mut types) if !types.contains(&"null") => {
+                types.push("null");
+                types.sort_unstable();
+                Shape::Types(types)
+            }
+            _ => Shape::Unknown,
+        };
+    }
+    let name = match ty {
+        syn::Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.ident.to_string()),
+        _ => None,
+    };
+    match name.as_deref() {
+        Some(
+            "u8" | "u16" | "u32" | "u64" | "u128" | "usize" | "i8" | "i16" | "i32" | "i64" | "i128"
+            | "isize",
+        ) => simple(&["integer"]),
+        Some("f32" | "f64") => simple(&["number"]),
+        Some("bool") => simple(&["boolean"]),
+        Some("String" | "str" | "char") => simple(&["string"]),
+        Some("Vec" | "VecDeque" | "HashSet" | "BTreeSet") => simple(&["array"]),
+        Some("HashMap" | "BTreeMap") => simple(&["object"]),
+        // generic params and smart pointers document whatever they hold
+        Some("Box" | "Rc" | "Arc" | "Cow" | "Value") | None => Shape::Unknown,
+        Some(_) => component_name(ty, options).map_or(Shape::Unknown, Shape::Component),
+    }
+}
+
+/// The `T` of an `Option<T>`.
+fn option_inner(ty: &syn::Type) -> Option<&syn::Type> {
+    let segment = match ty {
+        syn::Type::Path(syn::TypePath { path, .. }) => path.segments.last()?,
+        _ => return None,
+    };
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) if segment.ident == "Option" => {
+            match args.args.first()? {
+                syn::GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Checks a `one_of` value against the type of its param, as far as the
+/// primitive types tell. Other types may serialize to anything.
+fn check_literal(lit: &syn::Lit, ty: &syn::Type) -> Result<()> {
+    let ty = match ty {
+        syn::Type::Reference(reference) => &*reference.elem,
+        ty => ty,
+    };
+    let name = match ty {
+        syn::Type::Path(syn::TypePath { path, .. }) => match path.segments.last() {
+            Some(segment) => segment.ident.to_string(),
+            None => return Ok(()),
+        },
+        _ => return Ok(()),
+    };
+    let fits = match (name.as_str(), lit) {
+        ("String" | "str" | "char", syn::Lit::Str(_)) => true,
+        ("bool", syn::Lit::Bool(_)) => true,
+        ("f32" | "f64", syn::Lit::Int(_) | syn::Lit::Float(_)) => true,
+        ("u8", syn::Lit::Int(int)) => int.base10_parse::<u8>().is_ok(),
+        ("u16", syn::Lit::Int(int)) => int.base10_parse::<u16>().is_ok(),
+        ("u32", syn::Lit::Int(int)) => int.base10_parse::<u32>().is_ok(),
+        ("u64" | "usize", syn::Lit::Int(int)) => int.base10_parse::<u64>().is_ok(),
+        ("i8", syn::Lit::Int(int)) => int.base10_parse::<i8>().is_ok(),
+        ("i16", syn::Lit::Int(int)) => int.base10_parse::<i16>().is_ok(),
+        ("i32", syn::Lit::Int(int)) => int.base10_parse::<i32>().is_ok(),
+        ("i64" | "isize", syn::Lit::Int(int)) => int.base10_parse::<i64>().is_ok(),
+        (
+            "String" | "str" | "char" | "bool" | "f32" | "f64" | "u8" | "u16" | "u32" | "u64"
+            | "usize" | "i8" | "i16" | "i32" | "i64" | "isize",
+            _,
+        ) => false,
+        _ => true,
+    };
+    if fits {
+        Ok(())
+    } else {
+        let msg = format!("Expected a `{}` value", name);
+        Err(syn::Error::new_spanned(lit, msg))
+    }
+}
+
+fn is_subscription_id(ty: &syn::Type) -> bool {
+    matches!(ty, syn::Type::Path(syn::TypePath { path, .. })
+        if path.segments.last().is_some_and(|s| s.ident == "SubscriptionId"))
+}
+
+/// The name of the notification envelope of a subscribe method and the type
+/// of its results, the item type of its `Subscriber<T>` param.
+fn notification_of(method: &RpcMethod) -> Option<(String, syn::Type)> {
+    let subscription = match &method.attr.kind {
+        AttributeKind::PubSub {
+            subscription_name,
+            kind: PubSubMethodKind::Subscribe,
+        } => subscription_name,
+        _ => return None,
+    };
+    let item = method
+        .trait_item
+        .sig
+        .inputs
+        .iter()
+        .find_map(|arg| match arg {
+            syn::FnArg::Typed(syn::PatType { ty, .. }) => match &**ty {
+                syn::Type::Path(syn::TypePath { path, .. }) => path
+                    .segments
+                    .last()
+                    .filter(|segment| segment.ident == "Subscriber")
+                    .and_then(|segment| get_first_type_argument(&segment.arguments)),
+                _ => None,
+            },
+            _ => None,
+        })?;
+    Some((notification_ident(subscription), item))
+}
+
+/// `slot` becomes `SlotNotification`, `accountNotification` becomes
+/// `AccountNotification`.
+fn notification_ident(subscription: &str) -> String {
+    let mut name: String = subscription
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect();
+    if !name.ends_with("Notification") {
+        name.push_str("Notification");
+    }
+    name
+}
+
 fn compute_arg_type(args: &Punctuated<syn::FnArg, syn::token::Comma>) -> Result<Vec<syn::Type>> {
     let mut types = vec![];
     for arg in args {
@@ -145,7 +1894,14 @@ fn compute_returns(method: &syn::TraitItemMethod, returns: &Option<String>) -> R
         None => None,
     };
     let returns = match returns {
-        None => try_infer_returns(&method.sig.output),
+        None => match try_infer_returns(&method.sig.output) {
+            Some(inferred) if is_erased(&inferred) => {
+                let msg = "The result type is erased, document its shape with e.g. \
+                           `#[rpc(result = \"Vec<TokenBalance>\")]`";
+                return Err(syn::Error::new_spanned(inferred, msg));
+            }
+            inferred => inferred,
+        },
         _ => returns,
     };
     let returns = match returns {
@@ -159,6 +1915,22 @@ fn compute_returns(method: &syn::TraitItemMethod, returns: &Option<String>) -> R
     Ok(returns)
 }
 
+/// Whether `ty` hides its shape behind a `dyn` or `impl` trait.
+fn is_erased(ty: &syn::Type) -> bool {
+    struct Erased(bool);
+    impl<'a> syn::visit::Visit<'a> for Erased {
+        fn visit_type_trait_object(&mut self, _: &'a syn::TypeTraitObject) {
+            self.0 = true;
+        }
+        fn visit_type_impl_trait(&mut self, _: &'a syn::TypeImplTrait) {
+            self.0 = true;
+        }
+    }
+    let mut erased = Erased(false);
+    syn::visit::Visit::visit_type(&mut erased, ty);
+    erased.0
+}
+
 fn try_infer_returns(output: &syn::ReturnType) -> Option<syn::Type> {
     let extract_path_segments = |ty: &syn::Type| match ty {
         syn::Type::Path(syn::TypePath {
@@ -170,18 +1942,17 @@ fn try_infer_returns(output: &syn::ReturnType) -> Option<syn::Type> {
 
     match output {
         syn::ReturnType::Type(_, ty) => {
-            let segments = extract_path_segments(&**ty)?;
-            let check_segment = |seg: &syn::PathSegment| match seg {
-                syn::PathSegment {
+            let segments = extract_path_segments(ty)?;
+            let check_segment = |seg: &syn::PathSegment| {
+                let syn::PathSegment {
                     ident, arguments, ..
-                } => {
-                    let id = ident.to_string();
-                    let inner = get_first_type_argument(arguments);
-                    if id.ends_with("Result") {
-                        Ok(inner)
-                    } else {
-                        Err(inner)
-                    }
+                } = seg;
+                let id = ident.to_string();
+                let inner = get_first_type_argument(arguments);
+                if id.ends_with("Result") {
+                    Ok(inner)
+                } else {
+                    Err(Box::new(inner))
                 }
             };
             // Try out first argument (Result<X>) or nested types like:
@@ -189,7 +1960,7 @@ fn try_infer_returns(output: &syn::ReturnType) -> Option<syn::Type> {
             match check_segment(&segments[0]) {
                 Ok(returns) => Some(returns?),
                 Err(inner) => {
-                    let segments = extract_path_segments(&inner?)?;
+                    let segments = extract_path_segments(&(*inner)?)?;
                     check_segment(&segments[0]).ok().flatten()
                 }
             }
@@ -198,6 +1969,35 @@ fn try_infer_returns(output: &syn::ReturnType) -> Option<syn::Type> {
     }
 }
 
+/// The `E` of a method returning `Result<T, E>`, or a result nested like
+/// `BoxFuture<Result<T, E>>`.
+fn try_infer_error(output: &syn::ReturnType) -> Option<syn::Type> {
+    let mut ty = match output {
+        syn::ReturnType::Type(_, ty) => &**ty,
+        syn::ReturnType::Default => return None,
+    };
+    for _ in 0..2 {
+        let segment = match ty {
+            syn::Type::Path(path) => path.path.segments.last()?,
+            _ => return None,
+        };
+        let mut types = match &segment.arguments {
+            syn::PathArguments::AngleBracketed(args) => {
+                args.args.iter().filter_map(|arg| match arg {
+                    syn::GenericArgument::Type(ty) => Some(ty),
+                    _ => None,
+                })
+            }
+            _ => return None,
+        };
+        if segment.ident.to_string().ends_with("Result") {
+            return types.nth(1).cloned();
+        }
+        ty = types.next()?;
+    }
+    None
+}
+
 fn get_first_type_argument(args: &syn::PathArguments) -> Option<syn::Type> {
     match args {
         syn::PathArguments::AngleBracketed(syn::AngleBracketedGenericArguments {
@@ -216,10 +2016,32 @@ fn get_first_type_argument(args: &syn::PathArguments) -> Option<syn::Type> {
     }
 }
 
+/// `getConfirmedBlock` as `get_confirmed_block`.
+fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for c in name.chars() {
+        if c.is_ascii_uppercase() {
+            if !snake.is_empty() && !snake.ends_with('_') {
+                snake.push('_');
+            }
+            snake.push(c.to_ascii_lowercase());
+        } else if c.is_ascii_alphanumeric() {
+            snake.push(c);
+        } else {
+            snake.push('_');
+        }
+    }
+    snake
+}
+
 pub enum MethodRegistration {
     Standard {
         method: RpcMethod,
-        has_metadata: bool,
+    },
+    PubSub {
+        name: String,
+        subscribes: Vec<RpcMethod>,
+        unsubscribe: Option<RpcMethod>,
     },
 }
 
@@ -227,11 +2049,43 @@ pub enum MethodRegistration {
 pub struct RpcMethod {
     pub attr: RpcMethodAttribute,
     pub trait_item: syn::TraitItemMethod,
+    /// Names the items generated for the method, e.g. its request builder.
+    pub ident: syn::Ident,
 }
 
 impl RpcMethod {
     pub fn new(attr: RpcMethodAttribute, trait_item: syn::TraitItemMethod) -> RpcMethod {
-        RpcMethod { attr, trait_item }
+        let ident = trait_item.sig.ident.clone();
+        RpcMethod {
+            attr,
+            trait_item,
+            ident,
+        }
+    }
+
+    /// A method documented by a further `#[rpc]` attribute of the trait
+    /// method, whose generated items are named after its wire name since the
+    /// Rust name is taken.
+    pub fn stacked(
+        attr: RpcMethodAttribute,
+        trait_item: syn::TraitItemMethod,
+    ) -> Result<RpcMethod> {
+        let name = snake_case(&attr.name);
+        let ident = syn::parse_str::<syn::Ident>(&name)
+            .ok()
+            .filter(|_| name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_'))
+            .ok_or_else(|| {
+                let msg = format!(
+                    "The items generated for `{}` would be named `{}`, which is not an identifier",
+                    attr.name, name
+                );
+                syn::Error::new_spanned(&attr.attr, msg)
+            })?;
+        Ok(RpcMethod {
+            attr,
+            trait_item,
+            ident,
+        })
     }
 
     pub fn attr(&self) -> &RpcMethodAttribute {
@@ -0,0 +1,1868 @@
+#![allow(dead_code)]
+
+use open_rpc_derive::{document_external_rpc, document_rpc, openrpc_json, pubsub, rpc};
+use open_rpc_schema::table::Primitive;
+use open_rpc_schema::view::DocumentView;
+use serde_json::json;
+
+mod inline {
+    use super::*;
+
+    /// Arithmetic over the wire, see [`Rpc::add`].
+    #[document_rpc]
+    pub trait Rpc {
+        /// Adds two numbers.
+        ///
+        /// Overflow wraps around.
+        #[rpc(name = "add", summary = "Adds two numbers")]
+        fn add(&self, a: u64, b: u64) -> Result<u64, String>;
+        /// Checks the server
+        /// is up.
+        #[rpc]
+        fn ping(&self) -> Result<String, String>;
+    }
+}
+
+mod compact {
+    use super::*;
+
+    /// Arithmetic over the wire, see [`Rpc::add`].
+    #[document_rpc(compact, batch(max_size = 50))]
+    pub trait Rpc {
+        /// Adds two numbers.
+        ///
+        /// Overflow wraps around.
+        #[rpc(name = "add", summary = "Adds two numbers", timeout_ms = 5000)]
+        fn add(&self, a: u64, b: u64) -> Result<u64, String>;
+        /// Checks the server
+        /// is up.
+        #[rpc(idempotent)]
+        fn ping(&self) -> Result<String, String>;
+    }
+}
+
+#[test]
+fn cached_schema_is_shared_across_threads() {
+    let document: &'static _ = inline::cached_schema();
+    let names = std::thread::spawn(|| {
+        inline::cached_schema()
+            .methods
+            .iter()
+            .map(|m| m.name.clone())
+            .collect::<Vec<_>>()
+    })
+    .join()
+    .unwrap();
+    assert_eq!(names, ["add", "ping"]);
+    assert!(std::ptr::eq(document, inline::cached_schema()));
+}
+
+#[test]
+fn string_encoded_integers_are_coerced() {
+    let validator = inline::openrpc_schema_Rpc::validator();
+    let params = json!(["18446744073709551615", 2]);
+    assert!(validator.validate_params("add", &params).is_err());
+    let params = validator.accept_params("add", params).unwrap();
+    assert_eq!(params, json!([u64::MAX, 2]));
+    assert!(validator.accept_params("add", json!(["-1", 2])).is_err());
+}
+
+#[test]
+fn compact_mode_builds_the_same_document() {
+    let inline = serde_json::to_value(inline::gen_schema()).unwrap();
+    assert_eq!(
+        inline["info"]["description"],
+        "Arithmetic over the wire, see `Rpc::add`."
+    );
+    let mut compact = serde_json::to_value(compact::gen_schema()).unwrap();
+    let batch = compact.as_object_mut().unwrap().remove("x-batch");
+    assert_eq!(batch, Some(json!({ "supported": true, "maxSize": 50 })));
+    let timeout = compact["methods"][0]
+        .as_object_mut()
+        .unwrap()
+        .remove("x-timeout");
+    assert_eq!(timeout, Some(json!(5000)));
+    let idempotent = compact["methods"][1]
+        .as_object_mut()
+        .unwrap()
+        .remove("x-idempotent");
+    assert_eq!(idempotent, Some(json!(true)));
+    assert_eq!(inline, compact);
+    assert_eq!(compact["methods"][0]["name"], "add");
+    assert_eq!(compact["methods"][0]["params"][1]["name"], "b");
+    assert_eq!(compact["methods"][1]["result"]["name"], "String");
+    assert_eq!(compact["methods"][0]["summary"], "Adds two numbers");
+    assert_eq!(
+        compact["methods"][0]["description"],
+        "Overflow wraps around."
+    );
+    assert_eq!(compact["methods"][1]["summary"], "Checks the server is up.");
+
+    let const_methods = inline::openrpc_schema_Rpc::CONST_METHODS;
+    assert_eq!(const_methods.len(), 2);
+    assert_eq!(const_methods[0].params[1].ty, Primitive::U64);
+}
+
+mod paginated {
+    use super::*;
+    use open_rpc_schema::schemars::JsonSchema;
+
+    #[derive(JsonSchema)]
+    #[schemars(crate = "open_rpc_schema::schemars")]
+    pub struct Page {
+        pub items: Vec<String>,
+        pub next: Option<String>,
+    }
+
+    #[document_rpc]
+    pub trait Rpc {
+        #[rpc(paginated(cursor_param = "before", limit_param = "limit", result_field = "items"))]
+        fn list(&self, before: Option<String>, limit: u32) -> Result<Page, String>;
+    }
+}
+
+#[test]
+fn pagination_is_documented() {
+    let document = serde_json::to_value(paginated::gen_schema()).unwrap();
+    assert_eq!(
+        document["methods"][0]["x-pagination"],
+        json!({ "cursorParam": "before", "limitParam": "limit", "resultField": "items" })
+    );
+}
+
+#[test]
+fn misspelled_result_fields_are_caught() {
+    let mut document = paginated::gen_schema();
+    assert!(document.undeclared_result_fields().is_empty());
+    document.methods[0].extensions.insert(
+        "x-pagination".to_string(),
+        json!({ "resultField": "itemz" }),
+    );
+    assert_eq!(
+        document.undeclared_result_fields(),
+        [("list".to_string(), "itemz".to_string())]
+    );
+}
+
+mod authenticated {
+    use super::*;
+
+    #[document_rpc(auth(scheme = "bearer", description = "A token from /login"))]
+    pub trait Rpc {
+        #[rpc(name = "getBalance", timeout_ms = 5000, sla = "p99<200ms")]
+        fn get_balance(&self, addr: String) -> Result<u64, String>;
+        #[rpc(name = "getSlot")]
+        fn get_slot(&self) -> Result<u64, String>;
+    }
+}
+
+#[test]
+fn auth_scheme_is_documented() {
+    let document = serde_json::to_value(authenticated::gen_schema()).unwrap();
+    assert_eq!(
+        document["x-auth"],
+        json!({ "scheme": "bearer", "description": "A token from /login" })
+    );
+}
+
+#[test]
+fn timeout_and_sla_are_documented() {
+    let document = serde_json::to_value(authenticated::gen_schema()).unwrap();
+    assert_eq!(document["methods"][0]["x-timeout"], 5000);
+    assert_eq!(document["methods"][0]["x-sla"], "p99<200ms");
+    assert!(document["methods"][1].get("x-timeout").is_none());
+    assert!(document["methods"][1].get("x-sla").is_none());
+}
+
+#[test]
+fn static_methods_cover_every_method() {
+    use open_rpc_schema::table::find_static_method;
+    let list = find_static_method(paginated::openrpc_schema_Rpc::STATIC_METHODS, "list").unwrap();
+    assert_eq!(list.result.rust_type, "Page");
+    assert_eq!(list.result.primitive, None);
+    let before = list.param("before").unwrap();
+    assert_eq!(before.rust_type, "Option < String >");
+    assert!(before.required);
+    assert_eq!(list.param("limit").unwrap().primitive, Some(Primitive::U32));
+    assert!(paginated::openrpc_schema_Rpc::CONST_METHODS.is_empty());
+
+    let add = &inline::openrpc_schema_Rpc::STATIC_METHODS[0];
+    assert_eq!(add.description, Some("Overflow wraps around."));
+}
+
+mod titled {
+    use super::*;
+
+    #[document_rpc]
+    pub trait Rpc {
+        #[rpc(
+            name = "getBlock",
+            param_title(config = "Block query configuration"),
+            result_title = "Encoded block"
+        )]
+        fn get_block(&self, slot: u64, config: Option<String>) -> Result<String, String>;
+    }
+}
+
+#[test]
+fn param_and_result_titles() {
+    let document = serde_json::to_value(titled::gen_schema()).unwrap();
+    let method = &document["methods"][0];
+    assert_eq!(
+        method["params"][1]["schema"]["title"],
+        "Block query configuration"
+    );
+    assert_eq!(method["params"][0]["schema"]["title"], "uint64");
+    assert_eq!(method["result"]["schema"]["title"], "Encoded block");
+}
+
+mod conventions {
+    use super::*;
+    use open_rpc_schema::schemars::JsonSchema;
+
+    #[derive(JsonSchema)]
+    #[schemars(crate = "open_rpc_schema::schemars")]
+    pub struct BlockConfig {
+        pub full: Option<bool>,
+    }
+
+    #[derive(JsonSchema)]
+    #[schemars(crate = "open_rpc_schema::schemars")]
+    pub struct Query {
+        pub config: BlockConfig,
+        pub extra: serde_json::Value,
+    }
+
+    #[document_rpc(schemars(
+        inline_subschemas = false,
+        definitions_path = "#/components/schemas/",
+        option_nullable = true,
+        option_add_null_type = false,
+        bool_schemas = true
+    ))]
+    pub trait Rpc {
+        #[rpc(name = "getBlock")]
+        fn get_block(&self, query: Query) -> Result<u64, String>;
+    }
+}
+
+#[test]
+fn schemars_settings_shape_the_components() {
+    let document = serde_json::to_value(conventions::gen_schema()).unwrap();
+    let query = &document["methods"][0]["params"][0]["schema"];
+    assert_eq!(
+        query["properties"]["config"],
+        json!({ "$ref": "#/components/schemas/BlockConfig" })
+    );
+    assert_eq!(query["properties"]["extra"], true);
+    assert!(query.get("definitions").is_none());
+    assert_eq!(
+        document["components"]["schemas"]["BlockConfig"]["properties"]["full"],
+        json!({ "type": "boolean", "nullable": true })
+    );
+
+    let validator = conventions::openrpc_schema_Rpc::validator();
+    let params = json!([{ "config": { "full": true }, "extra": 1 }]);
+    assert!(validator.validate_params("getBlock", &params).is_ok());
+    let params = json!([{ "config": { "full": 1 }, "extra": 1 }]);
+    assert!(validator.validate_params("getBlock", &params).is_err());
+}
+
+mod named {
+    use super::*;
+    use open_rpc_schema::schemars::JsonSchema;
+
+    pub mod v1 {
+        use super::*;
+
+        #[derive(JsonSchema)]
+        #[schemars(crate = "open_rpc_schema::schemars")]
+        pub struct Failure {
+            pub slot: u64,
+        }
+    }
+
+    pub mod v2 {
+        use super::*;
+
+        #[derive(JsonSchema)]
+        #[schemars(crate = "open_rpc_schema::schemars")]
+        pub struct Failure {
+            pub reason: String,
+        }
+    }
+
+    #[derive(JsonSchema)]
+    #[schemars(crate = "open_rpc_schema::schemars")]
+    pub struct Block {
+        pub slot: u64,
+    }
+
+    #[derive(JsonSchema)]
+    #[schemars(crate = "open_rpc_schema::schemars")]
+    pub struct Page<T> {
+        pub items: Vec<T>,
+    }
+
+    #[document_rpc(
+        component_names(
+            module_path,
+            generics,
+            rename(v2_Failure = "RejectionDetails", Block = "LedgerBlock")
+        ),
+        schemars(inline_subschemas = false, definitions_path = "#/components/schemas/")
+    )]
+    pub trait Rpc {
+        #[rpc(
+            name = "getBlocks",
+            errors(
+                (code = -32010, message = "Slot skipped", data = "v1::Failure"),
+                (code = -32011, message = "Rejected", data = "v2::Failure"),
+                (code = -32012, message = "Partial page", data = "Page<Block>")
+            )
+        )]
+        fn get_blocks(&self, page: Page<Block>) -> Result<u64, String>;
+    }
+}
+
+#[test]
+fn component_names_follow_the_strategy() {
+    let document = serde_json::to_value(named::gen_schema()).unwrap();
+    let mut names: Vec<_> = document["components"]["schemas"]
+        .as_object()
+        .unwrap()
+        .keys()
+        .collect();
+    names.sort();
+    assert_eq!(
+        names,
+        [
+            "LedgerBlock",
+            "Page_for_Block",
+            "RejectionDetails",
+            "v1_Failure"
+        ]
+    );
+    let errors = &document["methods"][0]["errors"];
+    assert_eq!(
+        errors[1]["data"],
+        json!({ "$ref": "#/components/schemas/RejectionDetails" })
+    );
+    assert_eq!(
+        document["methods"][0]["params"][0]["schema"]["properties"]["items"]["items"],
+        json!({ "$ref": "#/components/schemas/LedgerBlock" })
+    );
+}
+
+mod namesakes {
+    use super::*;
+
+    #[document_rpc(schemars(
+        inline_subschemas = false,
+        definitions_path = "#/components/schemas/"
+    ))]
+    pub trait Rpc {
+        #[rpc(name = "getFailures")]
+        fn get_failures(&self) -> Result<Vec<named::v1::Failure>, String>;
+
+        #[rpc(name = "getRejections")]
+        fn get_rejections(&self) -> Result<Vec<named::v2::Failure>, String>;
+    }
+}
+
+#[test]
+fn namesake_definitions_are_hoisted_apart() {
+    let document = serde_json::to_value(namesakes::gen_schema()).unwrap();
+    let schemas = &document["components"]["schemas"];
+    assert_eq!(schemas.as_object().unwrap().len(), 2);
+    assert!(schemas["Failure"]["properties"].get("slot").is_some());
+    assert!(schemas["Failure_2"]["properties"].get("reason").is_some());
+    assert_eq!(
+        document["methods"][0]["result"]["schema"]["items"],
+        json!({ "$ref": "#/components/schemas/Failure" })
+    );
+    assert_eq!(
+        document["methods"][1]["result"]["schema"]["items"],
+        json!({ "$ref": "#/components/schemas/Failure_2" })
+    );
+}
+
+mod deduped {
+    use super::*;
+    use open_rpc_schema::schemars::JsonSchema;
+
+    #[derive(JsonSchema)]
+    #[schemars(crate = "open_rpc_schema::schemars")]
+    pub struct Account {
+        pub lamports: u64,
+    }
+
+    #[derive(JsonSchema)]
+    #[schemars(crate = "open_rpc_schema::schemars")]
+    pub struct Balance {
+        pub lamports: u64,
+    }
+
+    #[derive(JsonSchema)]
+    #[schemars(crate = "open_rpc_schema::schemars")]
+    pub struct Transfer {
+        pub from: Account,
+        pub to: Balance,
+    }
+
+    #[derive(JsonSchema)]
+    #[schemars(crate = "open_rpc_schema::schemars")]
+    pub struct Shortfall {
+        pub lamports: u64,
+    }
+
+    #[document_rpc(
+        dedupe_schemas,
+        schemars(inline_subschemas = false, definitions_path = "#/components/schemas/")
+    )]
+    pub trait Rpc {
+        #[rpc(
+            name = "transfer",
+            errors((code = -32010, message = "Insufficient funds", data = "Shortfall"))
+        )]
+        fn transfer(&self, transfer: Transfer) -> Result<Balance, String>;
+    }
+}
+
+#[test]
+fn identical_schemas_are_merged() {
+    let document = serde_json::to_value(deduped::gen_schema()).unwrap();
+    let schemas = document["components"]["schemas"].as_object().unwrap();
+    let mut names: Vec<_> = schemas.keys().collect();
+    names.sort();
+    assert_eq!(names, ["Account"]);
+    assert_eq!(
+        schemas["Account"]["x-aliases"],
+        json!(["Balance", "Shortfall"])
+    );
+
+    let method = &document["methods"][0];
+    let account = json!({ "$ref": "#/components/schemas/Account" });
+    assert_eq!(method["params"][0]["schema"]["properties"]["to"], account);
+    assert_eq!(method["errors"][0]["data"], account);
+}
+
+mod configured {
+    use super::*;
+    use open_rpc_schema::schemars::JsonSchema;
+
+    #[derive(JsonSchema, serde::Deserialize)]
+    #[schemars(crate = "open_rpc_schema::schemars")]
+    #[serde(rename_all = "camelCase")]
+    pub struct RpcConfig {
+        pub commitment: Option<String>,
+        pub encoding: Option<String>,
+        pub min_context_slot: Option<u64>,
+    }
+
+    #[document_rpc(shared_config = "RpcConfig")]
+    pub trait Rpc {
+        #[rpc(name = "getBalance", with_config)]
+        fn get_balance(&self, address: String) -> Result<u64, String>;
+
+        #[rpc(name = "getVersion")]
+        fn get_version(&self) -> Result<String, String>;
+    }
+
+    pub struct Server;
+
+    #[document_rpc]
+    impl Rpc for Server {
+        fn get_balance(&self, _address: String, config: Option<RpcConfig>) -> Result<u64, String> {
+            Ok(config
+                .and_then(|config| config.min_context_slot)
+                .unwrap_or_default())
+        }
+
+        fn get_version(&self) -> Result<String, String> {
+            Ok("1.18".to_string())
+        }
+    }
+}
+
+#[test]
+fn shared_config_is_appended() {
+    use configured::Rpc;
+
+    let document = serde_json::to_value(configured::gen_schema()).unwrap();
+    let params = &document["methods"][0]["params"];
+    assert_eq!(params[1]["name"], "config");
+    assert_eq!(params[1]["required"], false);
+    let properties = params[1]["schema"]["properties"].as_object().unwrap();
+    let mut fields: Vec<_> = properties.keys().collect();
+    fields.sort();
+    assert_eq!(fields, ["commitment", "encoding", "minContextSlot"]);
+    assert!(document["methods"][1]["params"]
+        .as_array()
+        .unwrap()
+        .is_empty());
+
+    let config = configured::RpcConfig {
+        commitment: None,
+        encoding: None,
+        min_context_slot: Some(7),
+    };
+    assert_eq!(
+        configured::Server.get_balance("addr".to_string(), Some(config)),
+        Ok(7)
+    );
+}
+
+mod throttled {
+    use super::*;
+
+    #[document_rpc]
+    pub trait Rpc {
+        #[rpc(
+            name = "getBlock",
+            idempotent,
+            rate_limit(requests = 10, per_ms = 1000),
+            timeout_ms = 500
+        )]
+        fn get_block(&self, slot: u64) -> Result<String, String>;
+
+        #[rpc(name = "sendTransaction")]
+        fn send_transaction(&self, transaction: String) -> Result<String, String>;
+    }
+}
+
+#[test]
+fn call_policies_follow_the_annotations() {
+    use open_rpc_schema::policy::RateLimit;
+    use std::time::Duration;
+
+    let document = serde_json::to_value(throttled::gen_schema()).unwrap();
+    assert_eq!(
+        document["methods"][0]["x-rate-limit"],
+        json!({ "requests": 10, "perMs": 1000 })
+    );
+
+    let policies = throttled::openrpc_schema_Rpc::call_policies();
+    let get_block = &policies["getBlock"];
+    assert!(get_block.retry.is_some());
+    assert_eq!(
+        get_block.rate_limit,
+        Some(RateLimit {
+            requests: 10,
+            per: Duration::from_secs(1)
+        })
+    );
+    assert_eq!(get_block.timeout, Some(Duration::from_millis(500)));
+    let send_transaction = &policies["sendTransaction"];
+    assert!(send_transaction.retry.is_none());
+    assert!(send_transaction.rate_limit.is_none());
+}
+
+mod baselined {
+    // the breaking changes are reported as deprecation warnings
+    #![allow(deprecated)]
+    use super::*;
+
+    #[document_rpc(baseline = "tests/baselines/blocks.json")]
+    pub trait Rpc {
+        #[rpc(name = "getBlock")]
+        fn get_block(&self, slot: u64, commitment: String) -> Result<String, String>;
+
+        #[rpc(name = "getSlot")]
+        fn get_slot(&self, config: Option<String>) -> Result<u64, String>;
+    }
+}
+
+#[test]
+fn breaking_changes_against_the_baseline() {
+    assert_eq!(
+        baselined::openrpc_schema_Rpc::BREAKING_CHANGES,
+        [
+            "param `config` of `getBlock` was removed",
+            "required param `commitment` was added to `getBlock`",
+            "method `getBlockTime` was removed",
+        ]
+    );
+}
+
+mod retyped {
+    #![allow(deprecated)]
+    use super::*;
+
+    #[document_rpc(baseline = "tests/baselines/blocks.json")]
+    pub trait Rpc {
+        #[rpc(name = "getBlock")]
+        fn get_block(&self, slot: String, config: Option<String>) -> Result<String, String>;
+        #[rpc(name = "getSlot")]
+        fn get_slot(&self, config: Option<String>) -> Result<u64, String>;
+        #[rpc(name = "getBlockTime")]
+        fn get_block_time(&self) -> Result<i64, String>;
+    }
+}
+
+#[test]
+fn retyped_params_break_the_baseline() {
+    assert_eq!(
+        retyped::openrpc_schema_Rpc::BREAKING_CHANGES,
+        ["the schema of param `slot` of `getBlock` changed from `integer` to `string`"]
+    );
+}
+
+mod constrained {
+    use super::*;
+
+    #[document_rpc]
+    pub trait Rpc {
+        /// Stored in the scratch bucket until the gc rewrite lands.
+        #[rpc(
+            max_bytes(data = 1048576),
+            no_doc_extraction,
+            description = "Uploads a blob"
+        )]
+        fn upload(&self, data: String) -> Result<(), String>;
+        #[rpc(range(limit = "1..=1000", offset = "0..100"))]
+        fn list(&self, limit: u32, offset: u32) -> Result<Vec<String>, String>;
+        #[rpc(pattern(address = "^[1-9A-HJ-NP-Za-km-z]{32,44}$"))]
+        fn balance(&self, address: String) -> Result<u64, String>;
+        #[rpc(param_example(slot = "12345"))]
+        fn block(&self, slot: u64) -> Result<String, String>;
+        #[rpc(
+            one_of(commitment = ["processed", "confirmed", "finalized"]),
+            param_example(commitment = "finalized")
+        )]
+        fn slot(&self, commitment: Option<String>) -> Result<u64, String>;
+        #[rpc(range(lamports = "9007199254740993..", delta = "-0.5..=0.5"))]
+        fn transfer(&self, lamports: u64, delta: f64) -> Result<(), String>;
+        #[rpc(one_of(version = [0, 1]))]
+        fn encode(&self, version: u8) -> Result<String, String>;
+        #[rpc(param_example(pin = "0042", label = "null"))]
+        fn unlock(&self, pin: String, label: Option<String>) -> Result<bool, String>;
+    }
+}
+
+#[test]
+fn param_constraints_are_documented() {
+    let document = serde_json::to_value(constrained::gen_schema()).unwrap();
+    assert_eq!(document["methods"][0].get("summary"), Some(&json!("")));
+    assert_eq!(document["methods"][0]["description"], "Uploads a blob");
+    let data = &document["methods"][0]["params"][0]["schema"];
+    assert_eq!(data["maxLength"], 1048576);
+    assert_eq!(data["x-max-bytes"], 1048576);
+    let limit = &document["methods"][1]["params"][0]["schema"];
+    assert_eq!(
+        (&limit["minimum"], &limit["maximum"]),
+        (&json!(1), &json!(1000))
+    );
+    let offset = &document["methods"][1]["params"][1]["schema"];
+    assert_eq!(offset["exclusiveMaximum"], json!(100));
+    let lamports = &document["methods"][5]["params"][0]["schema"];
+    assert_eq!(lamports["minimum"], json!(9007199254740993u64));
+    let delta = &document["methods"][5]["params"][1]["schema"];
+    assert_eq!(
+        (&delta["minimum"], &delta["maximum"]),
+        (&json!(-0.5), &json!(0.5))
+    );
+    let address = &document["methods"][2]["params"][0]["schema"];
+    assert_eq!(address["pattern"], "^[1-9A-HJ-NP-Za-km-z]{32,44}$");
+    let commitment = &document["methods"][4]["params"][0]["schema"];
+    assert_eq!(
+        commitment["enum"],
+        json!(["processed", "confirmed", "finalized", null])
+    );
+    let version = &document["methods"][6]["params"][0]["schema"];
+    assert_eq!(version["enum"], json!([0, 1]));
+    let unlock = &document["methods"][7]["params"];
+    assert_eq!(unlock[0]["schema"]["examples"], json!(["0042"]));
+    assert_eq!(unlock[1]["schema"]["examples"], json!(["null"]));
+    assert_eq!(commitment["examples"], json!(["finalized"]));
+    let slot = &document["methods"][3]["params"][0]["schema"];
+    assert_eq!(slot["examples"], json!([12345]));
+}
+
+mod shared_examples {
+    use super::*;
+
+    #[document_rpc(
+        examples(address = "9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin"),
+        synthesize_examples(seed = 42),
+        external_docs(url = "https://docs.example.com", description = "Developer portal")
+    )]
+    pub trait Rpc {
+        #[rpc(param_example_ref(address = "address"))]
+        fn balance(&self, address: String) -> Result<u64, String>;
+        #[rpc(param_example_ref(owner = "address"))]
+        fn accounts(&self, owner: String) -> Result<Vec<String>, String>;
+    }
+}
+
+#[test]
+fn shared_examples_are_reused() {
+    let document = serde_json::to_value(shared_examples::gen_schema()).unwrap();
+    let address = "9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin";
+    assert_eq!(
+        document["components"]["examples"]["address"]["value"],
+        address
+    );
+    assert_eq!(document["externalDocs"]["description"], "Developer portal");
+    for method in document["methods"].as_array().unwrap() {
+        assert_eq!(method["params"][0]["schema"]["examples"], json!([address]));
+        assert_eq!(method["examples"][0]["params"][0]["value"], address);
+    }
+}
+
+mod with_metadata {
+    use super::*;
+
+    #[document_rpc(metadata(
+        header(name = "Authorization", description = "Bearer token of the caller"),
+        header(name = "X-Forwarded-For")
+    ))]
+    pub trait Rpc {
+        type Metadata;
+
+        #[rpc(meta)]
+        fn whoami(&self, meta: Self::Metadata) -> Result<String, String>;
+        #[rpc]
+        fn ping(&self) -> Result<String, String>;
+    }
+}
+
+#[test]
+fn metadata_inputs_are_documented() {
+    let document = serde_json::to_value(with_metadata::gen_schema()).unwrap();
+    assert_eq!(
+        document["x-metadata"],
+        json!([
+            { "in": "header", "name": "Authorization", "description": "Bearer token of the caller" },
+            { "in": "header", "name": "X-Forwarded-For" },
+        ])
+    );
+    assert_eq!(document["methods"][0]["params"], json!([]));
+    assert_eq!(
+        document["methods"][0]["x-metadata"],
+        json!(["Authorization", "X-Forwarded-For"])
+    );
+    assert!(document["methods"][1].get("x-metadata").is_none());
+}
+
+mod subscriptions {
+    use super::*;
+
+    // stand-ins for the jsonrpc-pubsub types
+    pub struct Subscriber<T>(T);
+    pub enum SubscriptionId {
+        Number(u64),
+    }
+
+    #[document_rpc]
+    pub trait Rpc {
+        type Metadata;
+
+        #[pubsub(subscription = "slot", subscribe, name = "slotSubscribe")]
+        fn subscribe(&self, meta: Self::Metadata, subscriber: Subscriber<u64>, commitment: String);
+        #[pubsub(subscription = "slot", unsubscribe, name = "slotUnsubscribe")]
+        fn unsubscribe(
+            &self,
+            meta: Option<Self::Metadata>,
+            id: SubscriptionId,
+        ) -> Result<bool, String>;
+
+        #[pubsub(subscription = "account", subscribe, name = "accountSubscribe")]
+        fn account_subscribe(
+            &self,
+            meta: Self::Metadata,
+            subscriber: Subscriber<u64>,
+            address: String,
+        );
+    }
+}
+
+#[test]
+fn subscriptions_link_to_their_unsubscribe_method() {
+    let document = serde_json::to_value(subscriptions::gen_schema()).unwrap();
+    let subscribe = &document["methods"][0];
+    assert_eq!(subscribe["name"], "slotSubscribe");
+    assert_eq!(subscribe["params"][0]["name"], "commitment");
+    assert_eq!(subscribe["params"].as_array().unwrap().len(), 1);
+    assert_eq!(
+        subscribe["result"]["schema"]["anyOf"]
+            .as_array()
+            .unwrap()
+            .len(),
+        2
+    );
+    assert_eq!(
+        subscribe["links"],
+        json!([{ "name": "slotUnsubscribe", "method": "slotUnsubscribe", "params": { "id": "$result" } }])
+    );
+    let unsubscribe = &document["methods"][1];
+    assert_eq!(
+        unsubscribe["params"][0]["schema"],
+        subscribe["result"]["schema"]
+    );
+    assert_eq!(unsubscribe["result"]["schema"]["type"], "boolean");
+
+    let generated = &document["methods"][3];
+    assert_eq!(
+        document["methods"][2]["links"][0]["method"],
+        "accountUnsubscribe"
+    );
+    assert_eq!(generated["name"], "accountUnsubscribe");
+    assert_eq!(
+        generated["params"][0]["schema"],
+        subscribe["result"]["schema"]
+    );
+    assert_eq!(generated["result"]["schema"]["type"], "boolean");
+}
+
+#[test]
+fn subscriptions_document_their_notification_frames() {
+    let document = serde_json::to_value(subscriptions::gen_schema()).unwrap();
+    assert_eq!(
+        document["methods"][0]["x-notification"],
+        json!({ "$ref": "#/components/schemas/SlotNotification" })
+    );
+    let envelope = &document["components"]["schemas"]["SlotNotification"];
+    assert_eq!(envelope["properties"]["method"]["const"], "slot");
+    assert_eq!(
+        envelope["properties"]["params"]["properties"]["result"]["type"],
+        "integer"
+    );
+    assert!(document["methods"][1].get("x-notification").is_none());
+
+    let frame: subscriptions::openrpc_schema_Rpc::SlotNotification = serde_json::from_value(
+        json!({ "jsonrpc": "2.0", "method": "slot", "params": { "subscription": 1, "result": 7 } }),
+    )
+    .unwrap();
+    assert_eq!(frame.params.result, 7);
+}
+
+#[test]
+fn registrations_expose_the_parsed_methods() {
+    use open_rpc_schema::registration::RegistrationKind;
+    let registrations = subscriptions::openrpc_schema_Rpc::REGISTRATIONS;
+    let names: Vec<_> = registrations.iter().map(|r| r.name).collect();
+    assert_eq!(
+        names,
+        ["slotSubscribe", "slotUnsubscribe", "accountSubscribe"]
+    );
+    let unsubscribe = &registrations[1];
+    assert_eq!(unsubscribe.kind, RegistrationKind::Unsubscribe);
+    assert_eq!(unsubscribe.rust_name, "unsubscribe");
+    assert_eq!(unsubscribe.subscription, Some("slot"));
+    assert!(unsubscribe.has_metadata);
+    assert_eq!(unsubscribe.params[0].rust_type, "SubscriptionId");
+    assert_eq!(unsubscribe.returns, "Result < bool, String >");
+
+    let json = serde_json::to_value(inline::openrpc_schema_Rpc::REGISTRATIONS).unwrap();
+    assert_eq!(
+        json[0],
+        json!({
+            "name": "add",
+            "rustName": "add",
+            "kind": "method",
+            "aliases": [],
+            "hasMetadata": false,
+            "params": [
+                { "name": "a", "rustType": "u64" },
+                { "name": "b", "rustType": "u64" },
+            ],
+            "returns": "Result < u64, String >",
+        })
+    );
+}
+
+mod mailing {
+    use super::*;
+    use open_rpc_schema::schemars::JsonSchema;
+
+    #[derive(JsonSchema)]
+    #[schemars(crate = "open_rpc_schema::schemars")]
+    pub struct Subscriber {
+        pub email: String,
+    }
+
+    #[document_rpc]
+    pub trait Rpc {
+        #[rpc(name = "addSubscriber")]
+        fn add_subscriber(&self, subscriber: Subscriber) -> Result<bool, String>;
+    }
+}
+
+#[test]
+fn only_pubsub_methods_drop_their_subscriber() {
+    let document = serde_json::to_value(mailing::gen_schema()).unwrap();
+    let params = &document["methods"][0]["params"];
+    assert_eq!(params[0]["name"], "subscriber");
+    assert_eq!(params[0]["schema"]["properties"]["email"]["type"], "string");
+}
+
+mod with_errors {
+    use super::*;
+    use open_rpc_schema::schemars::JsonSchema;
+
+    #[derive(JsonSchema)]
+    #[schemars(crate = "open_rpc_schema::schemars")]
+    pub struct TxFailureDetails {
+        pub slot: u64,
+        pub logs: Vec<String>,
+    }
+
+    #[document_rpc]
+    pub trait Rpc {
+        #[rpc(errors(
+            (code = -32010, message = "Tx failed", data = "TxFailureDetails"),
+            (code = -32011, message = "Blockhash not found")
+        ))]
+        fn send_transaction(&self, tx: String) -> Result<String, String>;
+    }
+}
+
+mod pruned {
+    use super::*;
+    use open_rpc_schema::schemars::JsonSchema;
+
+    #[derive(JsonSchema)]
+    #[schemars(crate = "open_rpc_schema::schemars")]
+    pub struct AuditDetails {
+        pub operator: String,
+    }
+
+    #[document_rpc]
+    pub trait Rpc {
+        #[rpc(tags("public"))]
+        fn get_slot(&self) -> Result<u64, String>;
+        #[rpc(tags("internal"), errors((code = -32020, message = "Audit failed", data = "AuditDetails")))]
+        fn audit(&self) -> Result<bool, String>;
+        #[cfg(any())]
+        #[rpc(errors((code = -32021, message = "Gone", data = "AuditDetails")))]
+        fn removed(&self) -> Result<bool, String>;
+    }
+
+    pub mod compact {
+        use super::*;
+
+        #[document_rpc(compact)]
+        pub trait Rpc {
+            #[rpc(name = "getSlot")]
+            fn get_slot(&self) -> Result<u64, String>;
+            #[cfg(any())]
+            #[rpc(name = "removed")]
+            fn removed(&self) -> Result<bool, String>;
+        }
+    }
+}
+
+#[test]
+fn filtered_documents_drop_unreferenced_components() {
+    let schemas = |document: &open_rpc_schema::document::OpenrpcDocument| {
+        document
+            .components
+            .as_ref()
+            .and_then(|components| components.schemas.as_ref())
+            .map_or(0, |schemas| schemas.len())
+    };
+    let document = pruned::gen_schema();
+    let names: Vec<_> = document.methods.iter().map(|m| m.name.as_str()).collect();
+    assert_eq!(names, ["get_slot", "audit"]);
+    assert_eq!(schemas(&document), 1);
+    assert_eq!(schemas(&pruned::gen_schema_for_tags(&["public"])), 0);
+    assert_eq!(schemas(&pruned::gen_schema_for_tags(&["internal"])), 1);
+
+    assert_eq!(pruned::compact::gen_schema().methods.len(), 1);
+    assert_eq!(pruned::compact::openrpc_schema_Rpc::CONST_METHODS.len(), 1);
+}
+
+mod harvested_errors {
+    use super::*;
+    use open_rpc_derive::OpenRpcError;
+
+    #[derive(Debug, OpenRpcError)]
+    pub enum TxError {
+        #[error("Transaction simulation failed")]
+        #[rpc_error(code = -32002)]
+        SimulationFailed,
+        #[rpc_error(code = -32003, message = "Signature verification failed")]
+        BadSignature(String),
+    }
+
+    #[document_rpc(compact)]
+    pub trait Rpc {
+        #[rpc(errors((code = -32010, message = "Tx failed")))]
+        fn send_transaction(&self, tx: String) -> Result<String, TxError>;
+        #[rpc]
+        fn slot(&self) -> Result<u64, String>;
+    }
+}
+
+#[test]
+fn errors_are_harvested_from_the_error_type() {
+    let document = serde_json::to_value(harvested_errors::gen_schema()).unwrap();
+    let codes: Vec<_> = document["methods"][0]["errors"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|error| (error["code"].as_i64().unwrap(), error["message"].clone()))
+        .collect();
+    assert_eq!(
+        codes,
+        [
+            (-32010, json!("Tx failed")),
+            (-32002, json!("Transaction simulation failed")),
+            (-32003, json!("Signature verification failed")),
+        ]
+    );
+    assert!(document["methods"][1].get("errors").is_none());
+}
+
+mod standard_errors {
+    use super::*;
+
+    #[document_rpc(standard_errors)]
+    pub trait Rpc {
+        #[rpc(errors((code = -32010, message = "Tx failed")))]
+        fn send_transaction(&self, tx: String) -> Result<String, String>;
+        #[rpc]
+        fn slot(&self) -> Result<u64, String>;
+    }
+}
+
+#[test]
+fn standard_errors_are_documented_on_every_method() {
+    let document = serde_json::to_value(standard_errors::gen_schema()).unwrap();
+    let codes = |method: usize| -> Vec<_> {
+        document["methods"][method]["errors"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|error| (error["code"].as_i64().unwrap(), error["message"].clone()))
+            .collect()
+    };
+    assert_eq!(
+        codes(0),
+        [
+            (-32010, json!("Tx failed")),
+            (-32700, json!("Parse error")),
+            (-32600, json!("Invalid Request")),
+            (-32601, json!("Method not found")),
+            (-32602, json!("Invalid params")),
+        ]
+    );
+    assert_eq!(codes(1)[0], (-32700, json!("Parse error")));
+    assert_eq!(codes(1).len(), 4);
+}
+
+#[test]
+fn errors_reference_their_data_schema() {
+    let document = serde_json::to_value(with_errors::gen_schema()).unwrap();
+    let errors = &document["methods"][0]["errors"];
+    assert_eq!(errors[0]["code"], -32010);
+    assert_eq!(errors[0]["message"], "Tx failed");
+    assert_eq!(
+        errors[0]["data"]["$ref"],
+        "#/components/schemas/TxFailureDetails"
+    );
+    assert_eq!(
+        errors[1],
+        json!({ "code": -32011, "message": "Blockhash not found" })
+    );
+    let schema = &document["components"]["schemas"]["TxFailureDetails"];
+    assert_eq!(schema["properties"]["slot"]["type"], "integer");
+}
+
+mod expanded {
+    use super::*;
+    use open_rpc_schema::schemars::JsonSchema;
+
+    #[derive(JsonSchema, serde::Serialize)]
+    #[schemars(crate = "open_rpc_schema::schemars")]
+    pub struct SignaturesOptions {
+        pub address: String,
+        pub before: Option<String>,
+        pub limit: Option<u32>,
+    }
+
+    #[derive(JsonSchema, serde::Serialize)]
+    #[schemars(crate = "open_rpc_schema::schemars")]
+    pub enum Commitment {
+        Processed,
+        Finalized,
+    }
+
+    #[document_rpc(request_builders)]
+    pub trait Rpc {
+        #[rpc(expand_params)]
+        fn signatures(&self, options: SignaturesOptions) -> Result<Vec<String>, String>;
+        #[rpc(params_as_object)]
+        fn transfer(&self, to: String, amount: u64, memo: Option<String>)
+            -> Result<String, String>;
+        #[rpc(expand_params)]
+        fn commit(&self, commitment: Commitment) -> Result<u64, String>;
+    }
+}
+
+#[test]
+fn struct_param_is_expanded_into_named_params() {
+    let document = serde_json::to_value(expanded::gen_schema()).unwrap();
+    let method = &document["methods"][0];
+    assert_eq!(method["paramStructure"], "by-name");
+    let params: Vec<_> = method["params"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|param| (param["name"].clone(), param["required"].clone()))
+        .collect();
+    assert_eq!(
+        params,
+        [
+            (json!("address"), json!(true)),
+            (json!("before"), json!(false)),
+            (json!("limit"), json!(false)),
+        ]
+    );
+    assert_eq!(method["params"][0]["schema"]["type"], "string");
+}
+
+#[test]
+fn param_without_properties_is_not_expanded() {
+    let document = serde_json::to_value(expanded::gen_schema()).unwrap();
+    let method = &document["methods"][2];
+    assert!(method.get("paramStructure").is_none());
+    let params = method["params"].as_array().unwrap();
+    assert_eq!(params.len(), 1);
+    assert_eq!(params[0]["name"], "commitment");
+    assert_eq!(
+        params[0]["schema"]["enum"],
+        json!(["Processed", "Finalized"])
+    );
+}
+
+#[test]
+fn expanded_requests_follow_the_document() {
+    use expanded::openrpc_schema_Rpc::{requests, validator};
+    let options = expanded::SignaturesOptions {
+        address: "alice".to_string(),
+        before: None,
+        limit: Some(10),
+    };
+    let signatures = requests::signatures(options);
+    assert_eq!(
+        signatures["params"],
+        json!({ "address": "alice", "before": null, "limit": 10 })
+    );
+    assert!(validator()
+        .validate_params("signatures", &signatures["params"])
+        .is_ok());
+
+    let commit = requests::commit(expanded::Commitment::Finalized);
+    assert_eq!(commit["params"], json!(["Finalized"]));
+    assert!(validator()
+        .validate_params("commit", &commit["params"])
+        .is_ok());
+}
+
+#[test]
+fn params_are_documented_as_one_object() {
+    let document = serde_json::to_value(expanded::gen_schema()).unwrap();
+    assert_eq!(document["methods"][1]["paramStructure"], "by-position");
+    let params = document["methods"][1]["params"].as_array().unwrap();
+    assert_eq!(params.len(), 1);
+    let schema = &params[0]["schema"];
+    assert_eq!(schema["type"], "object");
+    assert_eq!(schema["properties"]["amount"]["format"], "uint64");
+    assert_eq!(schema["required"], json!(["amount", "to"]));
+}
+
+mod vendored {
+    use super::*;
+
+    #[derive(serde::Deserialize)]
+    pub struct Document {
+        pub openrpc: String,
+        pub methods: Vec<Method>,
+    }
+
+    #[derive(serde::Deserialize)]
+    pub struct Method {
+        pub name: String,
+    }
+
+    #[document_rpc(backend = "open_rpc_schema::backend::Json<Document>")]
+    pub trait Rpc {
+        #[rpc]
+        fn slot(&self) -> Result<u64, String>;
+    }
+}
+
+#[test]
+fn document_is_converted_by_the_backend() {
+    let document: vendored::Document =
+        vendored::openrpc_schema_Rpc::gen_backend_document().unwrap();
+    assert_eq!(document.openrpc, "1.2.6");
+    assert_eq!(document.methods[0].name, "slot");
+}
+
+mod custom_generator {
+    use super::*;
+    use open_rpc_schema::document::JSONSchema;
+    use open_rpc_schema::generator::GenerateSchema;
+
+    /// The schema trait of another generator, which `Lamports` implements
+    /// instead of `JsonSchema`.
+    pub trait Describe {
+        fn describe() -> serde_json::Value;
+    }
+
+    pub struct Lamports(pub u64);
+
+    impl Describe for Lamports {
+        fn describe() -> serde_json::Value {
+            json!({ "type": "integer", "description": "An amount of lamports" })
+        }
+    }
+
+    impl Describe for String {
+        fn describe() -> serde_json::Value {
+            json!({ "type": "string" })
+        }
+    }
+
+    pub struct Describer;
+
+    impl<T: Describe> GenerateSchema<T> for Describer {
+        fn generate() -> JSONSchema {
+            JSONSchema::from_json(T::describe()).unwrap()
+        }
+    }
+
+    #[document_rpc(schema_generator = "Describer")]
+    pub trait Rpc {
+        #[rpc]
+        fn balance(&self, address: String) -> Result<Lamports, String>;
+    }
+
+    pub mod compact {
+        use super::*;
+
+        #[document_rpc(compact, schema_generator = "super::Describer")]
+        pub trait Rpc {
+            #[rpc]
+            fn balance(&self, address: String) -> Result<Lamports, String>;
+        }
+    }
+}
+
+#[test]
+fn schemas_come_from_the_schema_generator() {
+    let inline = serde_json::to_value(custom_generator::gen_schema()).unwrap();
+    let compact = serde_json::to_value(custom_generator::compact::gen_schema()).unwrap();
+    assert_eq!(inline, compact);
+    let result = &inline["methods"][0]["result"]["schema"];
+    assert_eq!(result["description"], "An amount of lamports");
+    assert_eq!(
+        inline["methods"][0]["params"][0]["schema"]["type"],
+        "string"
+    );
+}
+
+mod erased {
+    use super::*;
+    use open_rpc_schema::schemars::JsonSchema;
+
+    #[derive(JsonSchema)]
+    #[schemars(crate = "open_rpc_schema::schemars")]
+    pub struct TokenBalance {
+        pub mint: String,
+        pub amount: u64,
+    }
+
+    pub trait Serialize {}
+
+    #[document_rpc]
+    pub trait Rpc {
+        #[rpc(result = "Vec<TokenBalance>")]
+        fn token_balances(&self, owner: String) -> Result<Box<dyn Serialize>, String>;
+    }
+}
+
+#[test]
+fn erased_result_is_documented_by_the_given_type() {
+    let document = serde_json::to_value(erased::gen_schema()).unwrap();
+    let schema = &document["methods"][0]["result"]["schema"];
+    assert_eq!(schema["type"], "array");
+    assert_eq!(schema["items"]["properties"]["amount"]["format"], "uint64");
+}
+
+mod positional {
+    use super::*;
+
+    #[document_rpc(param_structure = "by-position")]
+    pub trait Rpc {
+        #[rpc]
+        fn slot(&self, commitment: String) -> Result<u64, String>;
+        #[rpc(param_structure = "either")]
+        fn balance(&self, address: String) -> Result<u64, String>;
+        #[rpc]
+        fn blocks(
+            &self,
+            start: u64,
+            end: Option<u64>,
+            limit: Option<u64>,
+        ) -> Result<Vec<u64>, String>;
+        #[rpc]
+        fn leader(&self, slot: Option<u64>, commitment: String) -> Result<String, String>;
+        #[rpc(param_structure = "by-name")]
+        fn supply(&self, commitment: Option<String>, exclude: bool) -> Result<u64, String>;
+        #[rpc(params = "named")]
+        fn stake(&self, amount: u64) -> Result<bool, String>;
+        #[rpc(params = "raw")]
+        fn unstake(&self, amount: u64) -> Result<bool, String>;
+    }
+}
+
+#[test]
+fn param_structure_defaults_to_the_trait_level_one() {
+    let document = serde_json::to_value(positional::gen_schema()).unwrap();
+    assert_eq!(document["methods"][0]["paramStructure"], "by-position");
+    assert_eq!(document["methods"][1]["paramStructure"], "either");
+}
+
+#[test]
+fn param_structure_follows_the_params_key() {
+    let document = serde_json::to_value(positional::gen_schema()).unwrap();
+    assert_eq!(document["methods"][5]["paramStructure"], "by-name");
+    assert_eq!(document["methods"][6]["paramStructure"], "by-position");
+}
+
+#[test]
+fn only_trailing_optional_params_are_not_required() {
+    let document = serde_json::to_value(positional::gen_schema()).unwrap();
+    let required = |method: usize| {
+        document["methods"][method]["params"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|param| param["required"].as_bool().unwrap())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(required(2), [true, false, false]);
+    assert_eq!(required(3), [true, true]);
+    assert_eq!(required(4), [false, true]);
+}
+
+mod traits {
+    use super::*;
+
+    #[document_rpc(trait_tag)]
+    pub trait Accounts {
+        #[rpc(tags("public"))]
+        fn balance(&self, address: String) -> Result<u64, String>;
+    }
+
+    pub mod cluster {
+        use super::*;
+
+        #[document_rpc(compact, trait_tag = "cluster")]
+        pub trait Cluster {
+            #[rpc]
+            fn peers(&self) -> Result<Vec<String>, String>;
+        }
+    }
+}
+
+#[test]
+fn methods_are_tagged_with_their_trait() {
+    let accounts = traits::openrpc_schema_Accounts::gen_schema();
+    let tags: Vec<_> = accounts.methods[0].tag_names().collect();
+    assert_eq!(tags, ["public", "Accounts"]);
+    let cluster = traits::cluster::gen_schema();
+    assert_eq!(
+        cluster.methods[0].tag_names().collect::<Vec<_>>(),
+        ["cluster"]
+    );
+}
+
+mod prefixed {
+    use super::*;
+
+    #[document_rpc(display_strip_prefix = "eth_")]
+    pub trait Rpc {
+        #[rpc(name = "eth_blockNumber")]
+        fn block_number(&self) -> Result<u64, String>;
+        #[rpc(name = "net_version", readonly)]
+        fn version(&self) -> Result<String, String>;
+    }
+}
+
+#[test]
+fn display_names_strip_the_prefix() {
+    let document = serde_json::to_value(prefixed::gen_schema()).unwrap();
+    assert_eq!(document["methods"][0]["name"], "eth_blockNumber");
+    assert_eq!(document["methods"][0]["x-display-name"], "blockNumber");
+    assert!(document["methods"][1].get("x-display-name").is_none());
+}
+
+#[test]
+fn readonly_methods_are_marked() {
+    let document = serde_json::to_value(prefixed::gen_schema()).unwrap();
+    assert!(document["methods"][0].get("x-readonly").is_none());
+    assert_eq!(document["methods"][1]["x-readonly"], true);
+}
+
+mod plugins {
+    use super::*;
+
+    #[document_rpc]
+    pub trait Plugin {
+        #[rpc]
+        fn version(&self) -> Result<String, String>;
+    }
+
+    pub struct Metrics;
+
+    #[document_rpc]
+    impl Plugin for Metrics {
+        fn version(&self) -> Result<String, String> {
+            Ok("1.0.0".to_string())
+        }
+    }
+}
+
+#[test]
+fn document_is_reachable_from_trait_objects() {
+    let plugins: Vec<Box<dyn plugins::Plugin>> = vec![Box::new(plugins::Metrics)];
+    let document = plugins[0].openrpc_document();
+    assert_eq!(document.methods[0].name, "version");
+    assert!(std::ptr::eq(document, plugins::cached_schema()));
+}
+
+mod sampled {
+    use super::*;
+
+    #[document_rpc(code_samples, param_structure = "by-name")]
+    pub trait Rpc {
+        #[rpc(name = "getBalance")]
+        fn get_balance(&self, address: String, pending: Option<bool>) -> Result<u64, String>;
+    }
+}
+
+#[test]
+fn methods_carry_code_samples() {
+    let document = serde_json::to_value(sampled::gen_schema()).unwrap();
+    let samples = &document["methods"][0]["x-code-samples"];
+    assert_eq!(samples[0]["lang"], "Rust");
+    let rust = samples[0]["source"].as_str().unwrap();
+    assert_eq!(
+        rust,
+        "let address = \"echo\".to_string();\n\
+         let pending = Some(false);\n\
+         let result = client.get_balance(address, pending).await?;"
+    );
+    let curl = samples[1]["source"].as_str().unwrap();
+    assert!(curl.contains(r#""method":"getBalance""#));
+    assert!(curl.contains(r#""params":{"address":"#));
+    assert_eq!(samples[2]["lang"], "TypeScript");
+}
+
+#[test]
+fn pointers_into_the_document() {
+    let index = sampled::openrpc_schema_Rpc::pointer_index();
+    assert_eq!(
+        index.param("getBalance", "pending"),
+        Some("/methods/0/params/1")
+    );
+    let document = serde_json::to_value(sampled::cached_schema()).unwrap();
+    let param = document.pointer(index.param("getBalance", "pending").unwrap());
+    assert_eq!(param.unwrap()["name"], "pending");
+}
+
+#[test]
+fn invalid_params_are_rejected_with_their_schema() {
+    let validator = sampled::openrpc_schema_Rpc::validator();
+    assert!(validator
+        .validate_params("getBalance", &json!({ "address": "alice" }))
+        .is_ok());
+    let err = validator
+        .validate_params("getBalance", &json!({ "address": "alice", "pending": 1 }))
+        .unwrap_err();
+    assert_eq!(err.pointer, "/methods/0/params/1/schema");
+    assert_eq!(err.path, "/pending");
+    let data = err.to_error_object().data.unwrap();
+    assert_eq!(data["received"], 1);
+    assert_eq!(data["expected"]["type"], json!(["boolean", "null"]));
+    assert!(validator
+        .validate_params("getBalance", &json!(["alice"]))
+        .is_err());
+}
+
+mod strict {
+    use super::*;
+
+    #[document_rpc(param_structure = "by-name")]
+    pub trait Rpc {
+        #[rpc(name = "transfer", deny_unknown_params)]
+        fn transfer(&self, to: String, memo: Option<String>) -> Result<bool, String>;
+        #[rpc(name = "stake", params_as_object, deny_unknown_params)]
+        fn stake(&self, amount: u64) -> Result<bool, String>;
+    }
+}
+
+#[test]
+fn unknown_params_are_denied() {
+    let document = serde_json::to_value(strict::gen_schema()).unwrap();
+    assert_eq!(document["methods"][0]["x-deny-unknown-params"], true);
+    assert_eq!(
+        document["methods"][1]["params"][0]["schema"]["additionalProperties"],
+        false
+    );
+
+    let validator = strict::openrpc_schema_Rpc::validator();
+    assert!(validator
+        .validate_params("transfer", &json!({ "to": "bob" }))
+        .is_ok());
+    let err = validator
+        .validate_params("transfer", &json!({ "to": "bob", "mmeo": "hi" }))
+        .unwrap_err();
+    assert_eq!(err.message, "unknown param `mmeo`");
+    let err = validator
+        .validate_params("stake", &json!([{ "amount": 1, "ammount": 2 }]))
+        .unwrap_err();
+    assert_eq!(err.path, "/0");
+    assert_eq!(err.message, "unknown property `ammount`");
+}
+
+mod built {
+    use super::*;
+
+    #[document_rpc(request_builders)]
+    pub trait Rpc {
+        #[rpc(name = "getBlock")]
+        fn get_block(&self, slot: u64, full: Option<bool>) -> Result<String, String>;
+        #[rpc(name = "transfer", param_structure = "by-name")]
+        fn transfer(&self, to: String, amount: u64) -> Result<bool, String>;
+        #[rpc(name = "stake", params_as_object)]
+        fn stake(&self, amount: u64) -> Result<bool, String>;
+    }
+}
+
+#[test]
+fn request_builders_encode_the_params() {
+    use built::openrpc_schema_Rpc::requests;
+    assert_eq!(
+        requests::get_block(7, None),
+        json!({ "jsonrpc": "2.0", "id": 1, "method": "getBlock", "params": [7, null] })
+    );
+    assert_eq!(
+        requests::transfer("bob".to_string(), 3)["params"],
+        json!({ "to": "bob", "amount": 3 })
+    );
+    assert_eq!(requests::stake(5)["params"], json!([{ "amount": 5 }]));
+
+    let validator = built::openrpc_schema_Rpc::validator();
+    for request in [
+        requests::get_block(7, Some(true)),
+        requests::transfer("bob".to_string(), 3),
+        requests::stake(5),
+    ] {
+        let method = request["method"].as_str().unwrap();
+        assert!(validator
+            .validate_params(method, &request["params"])
+            .is_ok());
+    }
+}
+
+mod golden {
+    use super::*;
+    use serde_json::Value;
+
+    /// Stands in for the server in the generated golden tests.
+    pub fn answer(request: &Value) -> Value {
+        let result = match request["method"].as_str() {
+            Some("getSlot") => json!(42),
+            Some("getVersion") => json!("1.18.0"),
+            _ => Value::Null,
+        };
+        json!({ "jsonrpc": "2.0", "id": request["id"], "result": result })
+    }
+
+    #[document_rpc(synthesize_examples, golden_tests = "answer")]
+    pub trait Rpc {
+        #[rpc(name = "getSlot")]
+        fn get_slot(&self, finalized: bool) -> Result<u64, String>;
+        #[rpc(name = "getVersion")]
+        fn get_version(&self) -> Result<String, String>;
+    }
+}
+
+#[test]
+fn golden_checks_answers_to_the_examples() {
+    use open_rpc_schema::golden::check_examples;
+    let document = golden::openrpc_schema_Rpc::cached_schema();
+    assert_eq!(check_examples(document, "getSlot", golden::answer), Ok(1));
+    let failure = check_examples(
+        document,
+        "getSlot",
+        |request| json!({ "jsonrpc": "2.0", "id": request["id"], "result": "42" }),
+    )
+    .unwrap_err();
+    assert_eq!(failure.method, "getSlot");
+}
+
+mod slim {
+    use super::*;
+
+    /// The slot api.
+    #[document_rpc(slim, synthesize_examples, code_samples)]
+    pub trait Rpc {
+        /// Returns the current slot.
+        ///
+        /// A long explanation of commitment levels.
+        #[rpc(name = "getSlot", param_example(finalized = "true"))]
+        fn get_slot(&self, finalized: bool) -> Result<u64, String>;
+    }
+
+    pub mod compact {
+        use super::*;
+
+        #[document_rpc(slim, compact)]
+        pub trait Rpc {
+            /// Returns the current slot.
+            ///
+            /// A long explanation of commitment levels.
+            #[rpc(name = "getSlot", param_example(finalized = "true"))]
+            fn get_slot(&self, finalized: bool) -> Result<u64, String>;
+        }
+    }
+}
+
+#[test]
+fn slim_documents_are_embedded_without_examples_and_descriptions() {
+    use open_rpc_schema::document::SchemaProvider;
+    let full = serde_json::to_value(slim::openrpc_schema_Rpc::Schema::gen_schema()).unwrap();
+    assert!(full["methods"][0]["examples"].is_array());
+    assert!(full["methods"][0]["description"].is_string());
+    assert!(full["methods"][0]["x-code-samples"].is_array());
+    assert_eq!(full["info"]["description"], "The slot api.");
+
+    for embedded in [
+        slim::openrpc_schema_Rpc::cached_schema(),
+        slim::compact::openrpc_schema_Rpc::cached_schema(),
+    ] {
+        let embedded = serde_json::to_value(embedded).unwrap();
+        let method = &embedded["methods"][0];
+        assert_eq!(method["summary"], "Returns the current slot.");
+        assert!(method.get("description").is_none());
+        assert!(method.get("examples").is_none());
+        assert!(method.get("x-code-samples").is_none());
+        assert!(method["params"][0]["schema"].get("examples").is_none());
+    }
+    assert!(slim::openrpc_schema_Rpc::cached_schema()
+        .info
+        .description
+        .is_none());
+    let compact = slim::compact::openrpc_schema_Rpc::gen_schema();
+    let param = serde_json::to_value(&compact.methods[0].params[0]).unwrap();
+    assert_eq!(param["schema"]["examples"], json!([true]));
+}
+
+mod stacked {
+    use super::*;
+
+    #[document_rpc(request_builders)]
+    pub trait Rpc {
+        /// Returns the block at the slot.
+        #[rpc(name = "getBlock")]
+        #[rpc(name = "getConfirmedBlock", tags("deprecated"))]
+        fn get_block(&self, slot: u64) -> Result<String, String>;
+    }
+}
+
+#[test]
+fn stacked_rpc_attributes_document_several_methods() {
+    let document = serde_json::to_value(stacked::gen_schema()).unwrap();
+    let methods = document["methods"].as_array().unwrap();
+    assert_eq!(methods.len(), 2);
+    assert_eq!(methods[0]["name"], "getBlock");
+    assert_eq!(methods[1]["name"], "getConfirmedBlock");
+    assert_eq!(methods[0]["params"], methods[1]["params"]);
+    assert_eq!(methods[1]["summary"], "Returns the block at the slot.");
+    assert_eq!(methods[1]["tags"][0]["name"], "deprecated");
+
+    use stacked::openrpc_schema_Rpc::requests;
+    assert_eq!(requests::get_block(1)["method"], "getBlock");
+    assert_eq!(
+        requests::get_confirmed_block(1)["method"],
+        "getConfirmedBlock"
+    );
+}
+
+mod substrate {
+    use super::*;
+
+    #[document_rpc(discover_method = "system_openrpc")]
+    pub trait Rpc {
+        #[rpc(name = "system_health")]
+        fn health(&self) -> Result<bool, String>;
+    }
+}
+
+#[test]
+fn discover_method_is_configurable() {
+    assert_eq!(
+        substrate::openrpc_schema_Rpc::DISCOVER_METHOD,
+        "system_openrpc"
+    );
+    assert_eq!(sampled::openrpc_schema_Rpc::DISCOVER_METHOD, "rpc.discover");
+    let document = serde_json::to_value(substrate::gen_schema()).unwrap();
+    assert_eq!(document["x-discover-method"], "system_openrpc");
+    let document = serde_json::to_value(sampled::gen_schema()).unwrap();
+    assert!(document.get("x-discover-method").is_none());
+}
+
+mod tagged {
+    use super::*;
+
+    #[document_rpc(tag_groups(public("public"), internal("public", "internal")))]
+    pub trait Rpc {
+        #[rpc(tags("public"))]
+        fn balance(&self, address: String) -> Result<u64, String>;
+        #[rpc(tags("internal"))]
+        fn peers(&self) -> Result<Vec<String>, String>;
+        #[rpc(tags("internal"), roles("admin"))]
+        fn set_limits(&self, limit: u64) -> Result<(), String>;
+    }
+}
+
+#[test]
+fn json_of_the_document() {
+    let json: &'static str = openrpc_json!(tagged::Rpc);
+    let document: serde_json::Value = serde_json::from_str(json).unwrap();
+    assert_eq!(
+        document,
+        serde_json::to_value(tagged::gen_schema()).unwrap()
+    );
+}
+
+#[test]
+fn documents_are_split_by_tag() {
+    let names = |document: open_rpc_schema::document::OpenrpcDocument| {
+        document
+            .methods
+            .into_iter()
+            .map(|m| m.name)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        names(tagged::gen_schema_for_tags(&["internal"])),
+        ["peers", "set_limits"]
+    );
+    assert_eq!(
+        names(tagged::openrpc_schema_Rpc::gen_schema_public()),
+        ["balance"]
+    );
+    assert_eq!(
+        names(tagged::openrpc_schema_Rpc::gen_schema_internal()),
+        ["balance", "peers", "set_limits"]
+    );
+    let view = DocumentView::for_role("support").with_tags(&["internal"]);
+    assert_eq!(names(view.apply(tagged::cached_schema())), ["peers"]);
+    let view = DocumentView::for_role("admin");
+    assert_eq!(
+        names(view.apply(tagged::cached_schema())),
+        ["balance", "peers", "set_limits"]
+    );
+    assert_eq!(
+        names(tagged::openrpc_schema_Rpc::discover_for("public").unwrap()),
+        ["balance"]
+    );
+    assert!(tagged::openrpc_schema_Rpc::discover_for("admin").is_none());
+}
+
+#[allow(unexpected_cfgs)]
+mod gated {
+    use super::*;
+
+    #[document_rpc(feature = "not-enabled")]
+    pub trait Rpc {
+        #[rpc(name = "add")]
+        fn add(&self, a: u64, b: u64) -> Result<u64, String>;
+    }
+}
+
+#[test]
+fn gated_document_is_compiled_out() {
+    assert!(gated::gen_schema().methods.is_empty());
+    assert!(gated::openrpc_schema_Rpc::CONST_METHODS.is_empty());
+}
+
+mod accounts {
+    mod internal {
+        use open_rpc_derive::{document_rpc, rpc};
+
+        #[document_rpc]
+        pub trait AccountsRpc {
+            #[rpc(name = "getAccount")]
+            fn get_account(&self, addr: String) -> Result<u64, String>;
+        }
+    }
+
+    pub use self::internal::AccountsRpc;
+}
+
+struct Accounts;
+
+#[document_rpc]
+impl accounts::AccountsRpc for Accounts {
+    fn get_account(&self, _addr: String) -> Result<u64, String> {
+        Ok(0)
+    }
+}
+
+#[test]
+fn impl_resolves_re_exported_trait() {
+    use accounts::AccountsRpc;
+    let document = serde_json::to_value(Accounts.schema()).unwrap();
+    assert_eq!(document["methods"][0]["name"], "getAccount");
+}
+
+mod upstream {
+    pub trait AccountsApi {
+        fn get_balance(&self, addr: String) -> Result<u64, String>;
+        fn notify(&self, message: String);
+    }
+}
+
+document_external_rpc!(upstream::AccountsApi {
+    #[rpc(name = "getBalance")]
+    get_balance(addr: String) -> u64,
+    notify(message: String),
+});
+
+#[test]
+fn external_trait_is_documented() {
+    let document = serde_json::to_value(openrpc_schema_AccountsApi::gen_schema()).unwrap();
+    assert_eq!(document["methods"][0]["name"], "getBalance");
+    assert_eq!(document["methods"][0]["params"][0]["name"], "addr");
+    assert_eq!(document["methods"][1]["name"], "notify");
+}
+
+#[no_implicit_prelude]
+mod without_prelude {
+    type Result<T> = ::core::result::Result<T, ::std::string::String>;
+
+    #[::open_rpc_derive::document_rpc]
+    pub trait Rpc {
+        #[::open_rpc_derive::rpc(name = "add")]
+        fn add(&self, a: u64, b: u64) -> Result<u64>;
+    }
+
+    pub mod compact {
+        use super::Result;
+
+        #[::open_rpc_derive::document_rpc(compact)]
+        pub trait Rpc {
+            #[::open_rpc_derive::rpc(name = "add")]
+            fn add(&self, a: u64, b: u64) -> Result<u64>;
+        }
+    }
+}
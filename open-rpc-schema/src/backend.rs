@@ -0,0 +1,73 @@
+//! Handing the generated document to another OpenRPC model.
+//!
+//! `#[document_rpc(backend = "path::to::Backend")]` generates a
+//! `gen_backend_document()` next to `gen_schema()`, returning the document of
+//! the trait converted by the backend, e.g. into the structs of another
+//! OpenRPC crate or vendored ones.
+//!
+//! The backend only converts the finished document: the generated code
+//! builds an [`OpenrpcDocument`] whatever the backend.
+use crate::document::OpenrpcDocument;
+use serde::de::DeserializeOwned;
+use std::convert::Infallible;
+use std::marker::PhantomData;
+
+pub trait SchemaBackend {
+    type Document;
+    /// Why the model rejects a document.
+    type Error;
+
+    fn convert(document: OpenrpcDocument) -> Result<Self::Document, Self::Error>;
+}
+
+/// The backend of any model that deserializes from the OpenRPC JSON.
+pub struct Json<T>(PhantomData<T>);
+
+impl<T: DeserializeOwned> SchemaBackend for Json<T> {
+    type Document = T;
+    type Error = serde_json::Error;
+
+    fn convert(document: OpenrpcDocument) -> Result<T, serde_json::Error> {
+        serde_json::to_value(document).and_then(serde_json::from_value)
+    }
+}
+
+/// The document itself, for code generic over the backend.
+pub struct Native;
+
+impl SchemaBackend for Native {
+    type Document = OpenrpcDocument;
+    type Error = Infallible;
+
+    fn convert(document: OpenrpcDocument) -> Result<OpenrpcDocument, Infallible> {
+        Ok(document)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    struct Vendored {
+        openrpc: String,
+        methods: Vec<serde_json::Value>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Versioned {
+        version: u32,
+    }
+
+    #[test]
+    fn convert_through_json() {
+        let document = Json::<Vendored>::convert(OpenrpcDocument::default()).unwrap();
+        assert_eq!(document.openrpc, "1.2.6");
+        assert!(document.methods.is_empty());
+
+        let err = Json::<Versioned>::convert(OpenrpcDocument::default()).unwrap_err();
+        assert!(err.to_string().contains("missing field `version`"));
+    }
+}
@@ -0,0 +1,267 @@
+//! Coercing the params of a request to what the documented schemas expect.
+//!
+//! Clients written in languages without 64-bit integers send a `u64` as a
+//! string, and callers of a method taking a list often pass a lone value.
+//! Instead of every handler parsing both shapes, a server opting in coerces
+//! the params before validating and deserializing them:
+//!
+//! ```ignore
+//! let validator = openrpc_schema_Rpc::validator();
+//! let params = validator.accept_params("getBlocks", params)?;
+//! ```
+//!
+//! An integer or number schema takes a string holding one, and an array
+//! schema wraps a value that is not an array. Values already valid are left
+//! alone, and anything else is left to the validation to reject. A value is
+//! coerced by every schema of an `allOf`, and by the first schema of an
+//! `anyOf` or `oneOf` it then satisfies, unless it satisfies one already.
+use crate::validate::{escape, ValidationError, Validator, MAX_DEPTH};
+use serde_json::{Number, Value};
+
+impl Validator {
+    /// Coerces the params of a call to `method` in place. Returns how many
+    /// values were changed.
+    pub fn coerce_params(&self, method: &str, params: &mut Value) -> usize {
+        let index = match self.method_index(method) {
+            Some(index) => index,
+            None => return 0,
+        };
+        let descriptors = match self.document["methods"][index]["params"].as_array() {
+            Some(descriptors) => descriptors,
+            None => return 0,
+        };
+        let mut coerced = 0;
+        for (position, descriptor) in descriptors.iter().enumerate() {
+            let descriptor_pointer = match descriptor["$ref"].as_str() {
+                Some(reference) => reference.trim_start_matches('#').to_string(),
+                None => format!("/methods/{}/params/{}", index, position),
+            };
+            let name = self.resolve(&descriptor_pointer)["name"]
+                .as_str()
+                .unwrap_or_default();
+            let value = match params {
+                Value::Array(values) => values.get_mut(position),
+                Value::Object(values) => values.get_mut(name),
+                _ => None,
+            };
+            if let Some(value) = value {
+                let schema = format!("{}/schema", descriptor_pointer);
+                coerced += self.coerce(&schema, &schema, value, 0);
+            }
+        }
+        coerced
+    }
+
+    /// Coerces the params of a call to `method` and validates the result,
+    /// the params to deserialize.
+    pub fn accept_params(&self, method: &str, mut params: Value) -> Result<Value, ValidationError> {
+        self.coerce_params(method, &mut params);
+        self.validate_params(method, &params)?;
+        Ok(params)
+    }
+
+    fn coerce(&self, root: &str, pointer: &str, value: &mut Value, depth: usize) -> usize {
+        let schema = match self.resolve(pointer) {
+            Value::Object(schema) if depth <= MAX_DEPTH => schema,
+            _ => return 0,
+        };
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            let reference = reference.trim_start_matches('#');
+            if reference.starts_with("/definitions/") {
+                let target = format!("{}{}", root, reference);
+                return self.coerce(root, &target, value, depth + 1);
+            }
+            if reference.starts_with("/components/") {
+                return self.coerce(reference, reference, value, depth + 1);
+            }
+            return 0;
+        }
+        let mut coerced = 0;
+        let subschemas = |key: &str| -> Vec<String> {
+            let count = schema
+                .get(key)
+                .and_then(Value::as_array)
+                .map_or(0, Vec::len);
+            (0..count)
+                .map(|index| format!("{}/{}/{}", pointer, key, index))
+                .collect()
+        };
+        for subschema in subschemas("allOf") {
+            coerced += self.coerce(root, &subschema, value, depth + 1);
+        }
+        for key in ["anyOf", "oneOf"] {
+            let branches = subschemas(key);
+            let satisfies = |branch: &String, value: &Value| {
+                self.check(root, branch, "", value, depth + 1).is_ok()
+            };
+            if branches.is_empty() || branches.iter().any(|branch| satisfies(branch, value)) {
+                continue;
+            }
+            for branch in &branches {
+                let mut candidate = value.clone();
+                let changed = self.coerce(root, branch, &mut candidate, depth + 1);
+                if changed > 0 && satisfies(branch, &candidate) {
+                    *value = candidate;
+                    coerced += changed;
+                    break;
+                }
+            }
+        }
+        let types: Vec<&str> = match schema.get("type") {
+            Some(Value::String(ty)) => vec![ty.as_str()],
+            Some(Value::Array(types)) => types.iter().filter_map(Value::as_str).collect(),
+            _ => vec![],
+        };
+        let accepts = |ty: &str| types.contains(&ty);
+        match value {
+            Value::Null if accepts("null") => return 0,
+            _ if accepts("array") && !value.is_array() => {
+                *value = Value::Array(vec![value.take()]);
+                coerced += 1;
+            }
+            Value::String(string) if !accepts("string") => {
+                let number = if accepts("integer") {
+                    integer(string)
+                } else if accepts("number") {
+                    string.parse().ok().and_then(Number::from_f64)
+                } else {
+                    None
+                };
+                if let Some(number) = number {
+                    *value = Value::Number(number);
+                    coerced += 1;
+                }
+            }
+            _ => {}
+        }
+        match value {
+            Value::Array(items) => {
+                for (index, item) in items.iter_mut().enumerate() {
+                    let item_schema = match schema.get("items") {
+                        Some(Value::Array(tuple)) if index < tuple.len() => {
+                            format!("{}/items/{}", pointer, index)
+                        }
+                        Some(Value::Array(_)) | None => continue,
+                        Some(_) => format!("{}/items", pointer),
+                    };
+                    coerced += self.coerce(root, &item_schema, item, depth + 1);
+                }
+            }
+            Value::Object(object) => {
+                let properties = schema.get("properties").and_then(Value::as_object);
+                for (name, property) in object.iter_mut() {
+                    if properties.is_some_and(|properties| properties.contains_key(name)) {
+                        let property_schema = format!("{}/properties/{}", pointer, escape(name));
+                        coerced += self.coerce(root, &property_schema, property, depth + 1);
+                    }
+                }
+            }
+            _ => {}
+        }
+        coerced
+    }
+}
+
+fn integer(string: &str) -> Option<Number> {
+    match string.parse::<u64>() {
+        Ok(unsigned) => Some(unsigned.into()),
+        Err(_) => string.parse::<i64>().ok().map(Number::from),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::{ContentDescriptorOrReference, MethodObject, OpenrpcDocument};
+    use schemars::JsonSchema;
+    use serde_json::json;
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Config {
+        min_slot: Option<u64>,
+        commitment: String,
+    }
+
+    fn validator() -> Validator {
+        let mut method = MethodObject::new("getBlocks".to_string(), None);
+        method.params = vec![
+            ContentDescriptorOrReference::new_content_descriptor::<Vec<u64>>(
+                "slots".to_string(),
+                None,
+            ),
+            ContentDescriptorOrReference::new_content_descriptor::<Config>(
+                "config".to_string(),
+                None,
+            ),
+        ];
+        let mut document = OpenrpcDocument::default();
+        document.add_object_method(method);
+        Validator::new(&document)
+    }
+
+    #[test]
+    fn strings_and_lone_values_are_coerced() {
+        let validator = validator();
+        let mut params = json!(["18446744073709551615", { "min_slot": "7", "commitment": "7" }]);
+        assert_eq!(validator.coerce_params("getBlocks", &mut params), 3);
+        assert_eq!(
+            params,
+            json!([[18446744073709551615u64], { "min_slot": 7, "commitment": "7" }])
+        );
+
+        let params = json!({ "slots": ["1", 2], "config": { "min_slot": "3", "commitment": "x" } });
+        let accepted = validator.accept_params("getBlocks", params).unwrap();
+        assert_eq!(accepted["slots"], json!([1, 2]));
+        assert_eq!(accepted["config"]["min_slot"], json!(3));
+        assert_eq!(accepted["config"]["commitment"], json!("x"));
+
+        let err = validator
+            .accept_params("getBlocks", json!(["one"]))
+            .unwrap_err();
+        assert_eq!(err.path, "/0/0");
+        assert_eq!(validator.coerce_params("unknown", &mut json!(["1"])), 0);
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    enum Target {
+        Slot(u64),
+        Signature { signature: String, limit: Vec<u32> },
+    }
+
+    #[test]
+    fn combined_schemas_are_coerced() {
+        let mut method = MethodObject::new("getRange".to_string(), None);
+        method.params = vec![
+            ContentDescriptorOrReference::new_content_descriptor::<Target>(
+                "target".to_string(),
+                None,
+            ),
+            ContentDescriptorOrReference::new_content_descriptor::<Option<Target>>(
+                "until".to_string(),
+                None,
+            ),
+        ];
+        let mut document = OpenrpcDocument::default();
+        document.add_object_method(method);
+        let validator = Validator::new(&document);
+
+        let mut params = json!([
+            { "Signature": { "signature": "5", "limit": "10" } },
+            { "Slot": "7" }
+        ]);
+        assert_eq!(validator.coerce_params("getRange", &mut params), 3);
+        assert_eq!(
+            params,
+            json!([
+                { "Signature": { "signature": "5", "limit": [10] } },
+                { "Slot": 7 }
+            ])
+        );
+
+        let mut params = json!([{ "Slot": 1 }, null]);
+        assert_eq!(validator.coerce_params("getRange", &mut params), 0);
+        assert!(validator.accept_params("getRange", params).is_ok());
+    }
+}
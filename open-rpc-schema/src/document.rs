@@ -1,8 +1,8 @@
 use schemars::gen::SchemaSettings;
-use schemars::schema::RootSchema;
+use schemars::schema::{InstanceType, RootSchema, SchemaObject, SingleOrVec};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 extern crate serde_json;
 
@@ -64,6 +64,9 @@ pub type ContactObjectName = String;
 pub type ContactObjectEmail = String;
 pub type ContactObjectUrl = String;
 pub type SpecificationExtension = serde_json::Value;
+/// `x-` prefixed extension fields, kept sorted so the serialized document is
+/// stable across builds.
+pub type SpecificationExtensions = BTreeMap<String, SpecificationExtension>;
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct ContactObject {
@@ -224,6 +227,7 @@ pub type NonNegativeIntegerDefaultZero = i64;
 pub type Pattern = String;
 pub type SchemaArray = Vec<JSONSchema>;
 
+#[allow(clippy::large_enum_variant)]
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(untagged)]
 pub enum Items {
@@ -262,6 +266,7 @@ pub type Properties = HashMap<String, Option<serde_json::Value>>;
 ///
 pub type PatternProperties = HashMap<String, Option<serde_json::Value>>;
 
+#[allow(clippy::large_enum_variant)]
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(untagged)]
 pub enum DependenciesSet {
@@ -289,6 +294,7 @@ pub type ContentEncoding = String;
 ///
 /// Always valid if true. Never valid if false. Is constant.
 ///
+#[allow(clippy::large_enum_variant)]
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(untagged)]
 pub enum JSONSchema {
@@ -313,6 +319,7 @@ pub struct ContentDescriptorObject {
     pub deprecated: Option<ContentDescriptorObjectDeprecated>,
 }
 
+#[allow(clippy::large_enum_variant)]
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(untagged)]
 pub enum ContentDescriptorOrReference {
@@ -397,6 +404,7 @@ pub struct LinkObject {
     pub server: Option<LinkObjectServer>,
 }
 
+#[allow(clippy::large_enum_variant)]
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(untagged)]
 pub enum LinkOrReference {
@@ -450,7 +458,8 @@ pub struct ExamplePairingObject {
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(untagged)]
 pub enum ExamplePairingOrReference {
-    ExampleObject(ExampleObject),
+    /// Named after the generated model, though it holds an example pairing.
+    ExampleObject(ExamplePairingObject),
     ReferenceObject(ReferenceObject),
 }
 
@@ -484,6 +493,8 @@ pub struct MethodObject {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "externalDocs")]
     pub external_docs: Option<ExternalDocumentationObject>,
+    #[serde(flatten)]
+    pub extensions: SpecificationExtensions,
 }
 
 pub type Methods = Vec<MethodObject>;
@@ -495,7 +506,7 @@ pub type ExamplePairingComponents = HashMap<String, Option<serde_json::Value>>;
 pub type ContentDescriptorComponents = HashMap<String, Option<serde_json::Value>>;
 pub type TagComponents = HashMap<String, Option<serde_json::Value>>;
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, Default)]
 pub struct Components {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub schemas: Option<SchemaComponents>,
@@ -526,8 +537,17 @@ pub struct OpenrpcDocument {
     pub methods: Methods,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub components: Option<Components>,
+    #[serde(flatten)]
+    pub extensions: SpecificationExtensions,
 }
 
+// the generated `cached_schema()` hands out a `&'static OpenrpcDocument` to
+// every thread of the server
+const _: () = {
+    fn assert_send_sync<T: Send + Sync>() {}
+    let _ = assert_send_sync::<OpenrpcDocument>;
+};
+
 impl Default for OpenrpcDocument {
     fn default() -> Self {
         OpenrpcDocument {
@@ -544,6 +564,7 @@ impl Default for OpenrpcDocument {
             servers: None,
             methods: vec![],
             components: None,
+            extensions: SpecificationExtensions::new(),
         }
     }
 }
@@ -556,6 +577,93 @@ impl OpenrpcDocument {
     pub fn add_object_method(&mut self, method: MethodObject) {
         self.methods.push(method)
     }
+
+    /// Registers a named example under `components.examples` for reuse.
+    pub fn add_example(&mut self, name: &str, value: serde_json::Value) {
+        let example = ExampleObject {
+            summary: None,
+            value,
+            description: None,
+            name: name.to_string(),
+        };
+        self.components
+            .get_or_insert_with(Components::default)
+            .examples
+            .get_or_insert_with(ExampleComponents::new)
+            .insert(name.to_string(), serde_json::to_value(example).ok());
+    }
+
+    /// Registers a schema under `components.schemas` for `$ref`s to point at.
+    pub fn add_schema(&mut self, name: &str, schema: JSONSchema) {
+        self.components
+            .get_or_insert_with(Components::default)
+            .schemas
+            .get_or_insert_with(SchemaComponents::new)
+            .insert(name.to_string(), serde_json::to_value(schema).ok());
+    }
+}
+
+/// Generates the JSON schema used for params and results, with every
+/// subschema inlined.
+pub fn schema_for<T: ?Sized + JsonSchema>() -> JSONSchema {
+    let mut setting = SchemaSettings::draft07();
+    setting.inline_subschemas = true;
+    let schema = schemars::gen::SchemaGenerator::new(setting).into_root_schema_for::<T>();
+    JSONSchema::JsonSchemaObject(schema)
+}
+
+/// Reads an example given as an attribute string, as JSON when it parses and
+/// as a plain string otherwise, so both `"12345"` and `"finalized"` work.
+/// For a `schema` only taking strings the text is always the string, so the
+/// example of a string param holding `"123"` is not the number 123.
+pub fn example_value(text: &str, schema: Option<&SchemaObject>) -> serde_json::Value {
+    let string_only = schema
+        .and_then(|schema| schema.instance_type.as_ref())
+        .is_some_and(|types| match types {
+            SingleOrVec::Single(ty) => **ty == InstanceType::String,
+            SingleOrVec::Vec(types) => {
+                types.contains(&InstanceType::String)
+                    && types
+                        .iter()
+                        .all(|ty| matches!(ty, InstanceType::String | InstanceType::Null))
+            }
+        });
+    if string_only {
+        return serde_json::Value::String(text.to_string());
+    }
+    serde_json::from_str(text).unwrap_or_else(|_| serde_json::Value::String(text.to_string()))
+}
+
+/// The id a subscribe method returns and its unsubscribe method takes.
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum SubscriptionId {
+    Number(u64),
+    String(String),
+}
+
+/// Implemented by the `<Trait>Schema` marker type `#[document_rpc]`
+/// generates next to every documented trait.
+pub trait SchemaProvider {
+    fn gen_schema() -> OpenrpcDocument;
+}
+
+/// Sets the numeric bound `keyword` of a schema, e.g. `minimum`. schemars
+/// stores bounds as `f64`, which would turn an integer bound into `1.0` and
+/// round the ones above 2^53, so the bound is kept as the given JSON number.
+pub fn set_numeric_bound(schema: &mut SchemaObject, keyword: &str, bound: serde_json::Number) {
+    let number = schema.number();
+    let stored = match keyword {
+        "minimum" => &mut number.minimum,
+        "maximum" => &mut number.maximum,
+        "exclusiveMinimum" => &mut number.exclusive_minimum,
+        "exclusiveMaximum" => &mut number.exclusive_maximum,
+        _ => return,
+    };
+    *stored = None;
+    schema
+        .extensions
+        .insert(keyword.to_string(), serde_json::Value::Number(bound));
 }
 
 impl ContentDescriptorOrReference {
@@ -563,15 +671,29 @@ impl ContentDescriptorOrReference {
         name: ContactObjectName,
         description: Option<Description>,
     ) -> Self {
-        let mut setting = SchemaSettings::draft07();
-        setting.inline_subschemas = true;
-        let schema = schemars::gen::SchemaGenerator::new(setting).into_root_schema_for::<T>();
-        let json_schema = JSONSchema::JsonSchemaObject(schema);
+        Self::from_schema(name, description, schema_for::<T>())
+    }
+
+    /// The schema of a content descriptor, `None` for a reference.
+    pub fn schema(&self) -> Option<&JSONSchema> {
+        match self {
+            ContentDescriptorOrReference::ContentDescriptorObject(descriptor) => {
+                Some(&descriptor.schema)
+            }
+            ContentDescriptorOrReference::ReferenceObject(_) => None,
+        }
+    }
+
+    pub fn from_schema(
+        name: ContentDescriptorObjectName,
+        description: Option<Description>,
+        schema: JSONSchema,
+    ) -> Self {
         ContentDescriptorOrReference::ContentDescriptorObject(ContentDescriptorObject {
             name,
             description,
             summary: None,
-            schema: json_schema,
+            schema,
             required: None,
             deprecated: None,
         })
@@ -596,6 +718,59 @@ impl MethodObject {
             examples: None,
             deprecated: None,
             external_docs: None,
+            extensions: SpecificationExtensions::new(),
+        }
+    }
+
+    /// Documents an error the method may return.
+    pub fn add_error(
+        &mut self,
+        code: ErrorObjectCode,
+        message: &str,
+        data: Option<ErrorObjectData>,
+    ) {
+        self.errors
+            .get_or_insert_with(Vec::new)
+            .push(ErrorOrReference::ErrorObject(ErrorObject {
+                code,
+                message: message.to_string(),
+                data,
+            }));
+    }
+
+    /// Documents the errors harvested from the error type of the method.
+    pub fn add_errors(&mut self, errors: Vec<ErrorObject>) {
+        if !errors.is_empty() {
+            let documented = self.errors.get_or_insert_with(Vec::new);
+            documented.extend(errors.into_iter().map(ErrorOrReference::ErrorObject));
+        }
+    }
+
+    /// The root schema object of the param `name`, for attaching constraints
+    /// the type alone cannot express.
+    pub fn param_schema_mut(&mut self, name: &str) -> Option<&mut SchemaObject> {
+        self.params.iter_mut().find_map(|param| match param {
+            ContentDescriptorOrReference::ContentDescriptorObject(descriptor)
+                if descriptor.name == name =>
+            {
+                match &mut descriptor.schema {
+                    JSONSchema::JsonSchemaObject(root) => Some(&mut root.schema),
+                    JSONSchema::JSONSchemaBoolean(_) => None,
+                }
+            }
+            _ => None,
+        })
+    }
+
+    pub fn result_schema_mut(&mut self) -> Option<&mut SchemaObject> {
+        match &mut self.result {
+            ContentDescriptorOrReference::ContentDescriptorObject(descriptor) => {
+                match &mut descriptor.schema {
+                    JSONSchema::JsonSchemaObject(root) => Some(&mut root.schema),
+                    JSONSchema::JSONSchemaBoolean(_) => None,
+                }
+            }
+            ContentDescriptorOrReference::ReferenceObject(_) => None,
         }
     }
 }
@@ -603,9 +778,11 @@ impl MethodObject {
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[allow(dead_code)]
     #[derive(JsonSchema)]
     pub struct MyType([u8; 8]);
 
+    #[allow(dead_code)]
     #[derive(JsonSchema)]
     pub struct MyParam {
         pub my_int: i32,
@@ -613,6 +790,7 @@ mod tests {
         pub my_type: Box<MyType>,
     }
 
+    #[allow(dead_code)]
     #[derive(JsonSchema)]
     pub struct MyRet {
         pub success: Box<bool>,
@@ -0,0 +1,26 @@
+//! Support for a dedicated schema dump binary.
+//!
+//! ```ignore
+//! // src/bin/openrpc-dump.rs
+//! fn main() -> std::io::Result<()> {
+//!     open_rpc_schema::dump::run::<my_crate::MyRpcSchema>()
+//! }
+//! ```
+//!
+//! The binary prints the document to stdout, or writes it to the path given as
+//! its first argument.
+use crate::document::{OpenrpcDocument, SchemaProvider};
+use std::io::{self, Write};
+
+pub fn run<S: SchemaProvider>() -> io::Result<()> {
+    let path = std::env::args_os().nth(1);
+    match path {
+        Some(path) => write(&S::gen_schema(), std::fs::File::create(path)?),
+        None => write(&S::gen_schema(), io::stdout().lock()),
+    }
+}
+
+pub fn write<W: Write>(document: &OpenrpcDocument, mut writer: W) -> io::Result<()> {
+    serde_json::to_writer_pretty(&mut writer, document)?;
+    writeln!(writer)
+}
@@ -0,0 +1,98 @@
+//! Errors documented by the error type of a method.
+//!
+//! An error enum deriving `OpenRpcError` lists its variants as error objects,
+//! and every method returning it documents them without repeating them in
+//! `errors(..)` attributes.
+use crate::document::{ErrorObject, OpenrpcDocument};
+use std::marker::PhantomData;
+
+/// An error type knowing the JSON-RPC errors it turns into.
+pub trait OpenRpcErrors {
+    fn errors() -> Vec<ErrorObject>;
+}
+
+/// The errors of `E` if it implements [`OpenRpcErrors`], none otherwise.
+///
+/// The generated code calls `(&ErrorsOf::<E>::new()).harvest()`: method
+/// resolution picks [`HarvestErrors`] when its bound holds and falls back to
+/// [`NoErrors`] through one more autoref otherwise.
+#[doc(hidden)]
+pub struct ErrorsOf<E: ?Sized>(PhantomData<E>);
+
+impl<E: ?Sized> ErrorsOf<E> {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        ErrorsOf(PhantomData)
+    }
+}
+
+#[doc(hidden)]
+pub trait HarvestErrors {
+    fn harvest(&self) -> Vec<ErrorObject>;
+}
+
+impl<E: OpenRpcErrors + ?Sized> HarvestErrors for ErrorsOf<E> {
+    fn harvest(&self) -> Vec<ErrorObject> {
+        E::errors()
+    }
+}
+
+#[doc(hidden)]
+pub trait NoErrors {
+    fn harvest(&self) -> Vec<ErrorObject> {
+        Vec::new()
+    }
+}
+
+impl<E: ?Sized> NoErrors for &ErrorsOf<E> {}
+
+/// The errors of the JSON-RPC 2.0 specification any method may return.
+pub const STANDARD_ERRORS: &[(i64, &str)] = &[
+    (-32700, "Parse error"),
+    (-32600, "Invalid Request"),
+    (-32601, "Method not found"),
+    (-32602, "Invalid params"),
+];
+
+pub fn standard_errors() -> Vec<ErrorObject> {
+    STANDARD_ERRORS
+        .iter()
+        .map(|(code, message)| ErrorObject {
+            code: *code,
+            message: message.to_string(),
+            data: None,
+        })
+        .collect()
+}
+
+impl OpenrpcDocument {
+    /// Documents the [`STANDARD_ERRORS`] on every method, after its own.
+    pub fn add_standard_errors(&mut self) {
+        for method in &mut self.methods {
+            method.add_errors(standard_errors());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Documented;
+
+    impl OpenRpcErrors for Documented {
+        fn errors() -> Vec<ErrorObject> {
+            vec![ErrorObject {
+                code: -32010,
+                message: "Tx failed".to_string(),
+                data: None,
+            }]
+        }
+    }
+
+    #[test]
+    fn harvest_falls_back_to_no_errors() {
+        assert_eq!(ErrorsOf::<Documented>::new().harvest()[0].code, -32010);
+        assert!((&ErrorsOf::<String>::new()).harvest().is_empty());
+    }
+}
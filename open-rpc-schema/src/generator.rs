@@ -0,0 +1,330 @@
+//! Choosing what generates the schemas of the params and results.
+//!
+//! `#[document_rpc(schema_generator = "path::to::Generator")]` builds every
+//! schema through `<Generator as GenerateSchema<T>>::generate`, so the params
+//! and results only need to implement the schema trait of that generator,
+//! e.g. the one a workspace already standardizes on, instead of `JsonSchema`.
+use crate::document::{
+    schema_for, Components, ContentDescriptorOrReference, JSONSchema, OpenrpcDocument,
+    SchemaComponents,
+};
+use crate::registry::{rewrite_method, rewrite_refs};
+use schemars::gen::{SchemaGenerator, SchemaSettings};
+use schemars::schema::{Schema, SchemaObject};
+use schemars::visit::{visit_root_schema, visit_schema, Visitor};
+use schemars::JsonSchema;
+use serde_json::Value;
+use std::collections::HashMap;
+
+const COMPONENT_SCHEMAS: &str = "#/components/schemas/";
+/// The names of the component schemas merged into the one listing them.
+const ALIASES_EXTENSION: &str = "x-aliases";
+
+pub trait GenerateSchema<T: ?Sized> {
+    fn generate() -> JSONSchema;
+}
+
+/// The default generator, `schemars` with every subschema inlined.
+pub struct Schemars;
+
+impl<T: ?Sized + JsonSchema> GenerateSchema<T> for Schemars {
+    fn generate() -> JSONSchema {
+        schema_for::<T>()
+    }
+}
+
+/// `schemars` with the settings of `#[document_rpc(schemars(...))]`, so the
+/// schemas follow the conventions of the organization.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SchemarsSettings {
+    pub inline_subschemas: bool,
+    /// Where the `$ref`s of the subschemas not inlined point, e.g.
+    /// `#/components/schemas/` to refer to the components of the document.
+    pub definitions_path: &'static str,
+    pub option_nullable: bool,
+    pub option_add_null_type: bool,
+    /// Write the schemas accepting any value as `true` instead of `{}`.
+    pub bool_schemas: bool,
+}
+
+impl SchemarsSettings {
+    /// The settings of [`schema_for`].
+    pub const DEFAULT: SchemarsSettings = SchemarsSettings {
+        inline_subschemas: true,
+        definitions_path: "#/definitions/",
+        option_nullable: false,
+        option_add_null_type: true,
+        bool_schemas: false,
+    };
+
+    pub fn schema_for<T: ?Sized + JsonSchema>(&self) -> JSONSchema {
+        let mut settings = SchemaSettings::draft07();
+        settings.inline_subschemas = self.inline_subschemas;
+        settings.definitions_path = self.definitions_path.to_string();
+        settings.option_nullable = self.option_nullable;
+        settings.option_add_null_type = self.option_add_null_type;
+        let mut schema = SchemaGenerator::new(settings).into_root_schema_for::<T>();
+        if self.bool_schemas {
+            visit_root_schema(&mut BoolSchemas, &mut schema);
+        }
+        JSONSchema::JsonSchemaObject(schema)
+    }
+}
+
+impl Default for SchemarsSettings {
+    fn default() -> Self {
+        SchemarsSettings::DEFAULT
+    }
+}
+
+/// Turns the empty subschemas into `true`.
+struct BoolSchemas;
+
+impl Visitor for BoolSchemas {
+    fn visit_schema(&mut self, schema: &mut Schema) {
+        if matches!(schema, Schema::Object(object) if *object == SchemaObject::default()) {
+            *schema = Schema::Bool(true);
+        }
+        visit_schema(self, schema)
+    }
+}
+
+impl OpenrpcDocument {
+    /// Moves the definitions of the param and result schemas into
+    /// `components.schemas`, where `definitions_path = "#/components/schemas/"`
+    /// points their `$ref`s.
+    ///
+    /// A definition named like a different schema already in the components,
+    /// e.g. of a namesake type in another module, is hoisted under its name
+    /// suffixed with `_2`, `_3` and so on, and the `$ref`s of its param or
+    /// result follow it.
+    pub fn hoist_definitions(&mut self) {
+        let has_definitions = self.methods.iter().any(|method| {
+            method
+                .params
+                .iter()
+                .chain([&method.result])
+                .any(|descriptor| {
+                    matches!(
+                        descriptor,
+                        ContentDescriptorOrReference::ContentDescriptorObject(descriptor)
+                            if matches!(&descriptor.schema, JSONSchema::JsonSchemaObject(root)
+                                if !root.definitions.is_empty())
+                    )
+                })
+        });
+        if !has_definitions {
+            return;
+        }
+        let schemas = self
+            .components
+            .get_or_insert_with(Components::default)
+            .schemas
+            .get_or_insert_with(SchemaComponents::new);
+        for method in &mut self.methods {
+            for descriptor in method.params.iter_mut().chain([&mut method.result]) {
+                let root = match descriptor {
+                    ContentDescriptorOrReference::ContentDescriptorObject(descriptor) => {
+                        match &mut descriptor.schema {
+                            JSONSchema::JsonSchemaObject(root) => root,
+                            JSONSchema::JSONSchemaBoolean(_) => continue,
+                        }
+                    }
+                    ContentDescriptorOrReference::ReferenceObject(_) => continue,
+                };
+                let mut hoisted: Vec<(String, Value)> = Vec::new();
+                let mut references = HashMap::new();
+                for (name, definition) in std::mem::take(&mut root.definitions) {
+                    let definition = match serde_json::to_value(definition) {
+                        Ok(definition) => definition,
+                        Err(_) => continue,
+                    };
+                    let mut hoisted_name = name.clone();
+                    for suffix in 2.. {
+                        let taken = hoisted.iter().any(|(other, _)| *other == hoisted_name);
+                        match schemas.get(&hoisted_name) {
+                            Some(Some(existing))
+                                if !taken && same_schema(existing, &definition) =>
+                            {
+                                break
+                            }
+                            None if !taken => break,
+                            _ => hoisted_name = format!("{}_{}", name, suffix),
+                        }
+                    }
+                    if hoisted_name != name {
+                        references.insert(
+                            format!("{}{}", COMPONENT_SCHEMAS, name),
+                            format!("{}{}", COMPONENT_SCHEMAS, hoisted_name),
+                        );
+                    }
+                    hoisted.push((hoisted_name, definition));
+                }
+                if !references.is_empty() {
+                    let schema = serde_json::to_value(&root.schema)
+                        .ok()
+                        .and_then(|mut schema| {
+                            rewrite_refs(&mut schema, &references);
+                            serde_json::from_value(schema).ok()
+                        });
+                    if let Some(schema) = schema {
+                        root.schema = schema;
+                    }
+                }
+                for (name, mut definition) in hoisted {
+                    rewrite_refs(&mut definition, &references);
+                    schemas.entry(name).or_insert(Some(definition));
+                }
+            }
+        }
+    }
+}
+
+impl OpenrpcDocument {
+    /// Renames the component schemas, and the definitions of the param and
+    /// result schemas, following every `$ref` to them.
+    pub fn rename_schemas(&mut self, renames: &[(&str, &str)]) {
+        let mut references = HashMap::new();
+        for (from, to) in renames {
+            for prefix in [COMPONENT_SCHEMAS, "#/definitions/"] {
+                references.insert(format!("{}{}", prefix, from), format!("{}{}", prefix, to));
+            }
+        }
+        for method in &mut self.methods {
+            rewrite_method(method, &references);
+            for descriptor in method.params.iter_mut().chain([&mut method.result]) {
+                if let ContentDescriptorOrReference::ContentDescriptorObject(descriptor) =
+                    descriptor
+                {
+                    if let JSONSchema::JsonSchemaObject(root) = &mut descriptor.schema {
+                        for (from, to) in renames {
+                            if let Some(definition) = root.definitions.remove(*from) {
+                                root.definitions.insert(to.to_string(), definition);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        let schemas = self.components.as_mut().and_then(|c| c.schemas.as_mut());
+        if let Some(schemas) = schemas {
+            for (from, to) in renames {
+                if let Some(schema) = schemas.remove(*from) {
+                    schemas.insert(to.to_string(), schema);
+                }
+            }
+            for schema in schemas.values_mut().flatten() {
+                rewrite_refs(schema, &references);
+            }
+        }
+    }
+}
+
+impl OpenrpcDocument {
+    /// Merges the component schemas equal to one another but for their
+    /// title and `$schema` into the first of them by name, which lists the others under
+    /// `x-aliases`, and points the `$ref`s to the others at it. Returns how
+    /// many schemas were merged.
+    pub fn dedupe_schemas(&mut self) -> usize {
+        let mut merged = 0;
+        loop {
+            let schemas = match self.components.as_mut().and_then(|c| c.schemas.as_mut()) {
+                Some(schemas) => schemas,
+                None => return merged,
+            };
+            let mut names: Vec<_> = schemas.keys().cloned().collect();
+            names.sort();
+            let unaliased = |name: &str| {
+                let mut schema = schemas.get(name).cloned().flatten()?;
+                // the schemas of the error data are root schemas, titled after
+                // their type
+                if let Value::Object(schema) = &mut schema {
+                    for key in [ALIASES_EXTENSION, "title", "$schema"] {
+                        schema.remove(key);
+                    }
+                }
+                Some(schema)
+            };
+            let mut aliases: Vec<(String, Vec<String>)> = Vec::new();
+            for (index, name) in names.iter().enumerate() {
+                if aliases.iter().any(|(_, same)| same.contains(name)) {
+                    continue;
+                }
+                let schema = match unaliased(name) {
+                    Some(schema) => schema,
+                    None => continue,
+                };
+                let same: Vec<_> = names[index + 1..]
+                    .iter()
+                    .filter(|other| unaliased(other).as_ref() == Some(&schema))
+                    .cloned()
+                    .collect();
+                if !same.is_empty() {
+                    aliases.push((name.clone(), same));
+                }
+            }
+            if aliases.is_empty() {
+                return merged;
+            }
+
+            let mut references = HashMap::new();
+            for (name, same) in aliases {
+                let mut listed = Vec::new();
+                for alias in same {
+                    references.insert(
+                        format!("{}{}", COMPONENT_SCHEMAS, alias),
+                        format!("{}{}", COMPONENT_SCHEMAS, name),
+                    );
+                    // the aliases of the merged schema become aliases too
+                    let aliased = schemas.remove(&alias).flatten();
+                    if let Some(Value::Array(earlier)) = aliased
+                        .as_ref()
+                        .and_then(|schema| schema.get(ALIASES_EXTENSION))
+                    {
+                        listed.extend(earlier.iter().cloned());
+                    }
+                    listed.push(Value::String(alias));
+                    merged += 1;
+                }
+                if let Some(Some(Value::Object(schema))) = schemas.get_mut(&name) {
+                    let aliases = schema
+                        .entry(ALIASES_EXTENSION)
+                        .or_insert_with(|| Value::Array(Vec::new()));
+                    if let Value::Array(aliases) = aliases {
+                        aliases.extend(listed);
+                        aliases.sort_by(|a, b| a.as_str().cmp(&b.as_str()));
+                    }
+                }
+            }
+            // the schemas referring to the merged ones may now be equal too
+            for schema in schemas.values_mut().flatten() {
+                rewrite_refs(schema, &references);
+            }
+            for method in &mut self.methods {
+                rewrite_method(method, &references);
+            }
+        }
+    }
+}
+
+/// Whether two component schemas describe the same values, the schemas of
+/// the error data being root schemas titled after their type.
+fn same_schema(schema: &Value, other: &Value) -> bool {
+    let comparable = |schema: &Value| {
+        let mut schema = schema.clone();
+        if let Value::Object(schema) = &mut schema {
+            for key in [ALIASES_EXTENSION, "title", "$schema", "definitions"] {
+                schema.remove(key);
+            }
+        }
+        schema
+    };
+    comparable(schema) == comparable(other)
+}
+
+impl JSONSchema {
+    /// Reads a schema another generator produced as JSON.
+    pub fn from_json(value: serde_json::Value) -> serde_json::Result<Self> {
+        serde_json::from_value(value)
+    }
+}
@@ -0,0 +1,200 @@
+//! Contract tests turning the example pairings of a method into requests.
+//!
+//! Each example request is sent to a handler standing in for the server, and
+//! the result it answers with has to validate against the documented result
+//! schema. `#[document_rpc(golden_tests = "path::to::handler")]` generates one
+//! such `#[test]` per method.
+use crate::document::{
+    ExampleOrReference, ExamplePairingOrReference, MethodObject, MethodObjectParamStructure,
+    OpenrpcDocument,
+};
+use crate::validate::Validator;
+use serde_json::{json, Value};
+use std::fmt;
+
+/// An example the server did not answer as documented, or a method without
+/// examples to check.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContractFailure {
+    pub method: String,
+    /// The name of the example pairing, `None` when there was nothing to
+    /// send.
+    pub example: Option<String>,
+    pub response: Value,
+    pub reason: String,
+}
+
+impl fmt::Display for ContractFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.example {
+            Some(example) => write!(
+                f,
+                "example `{}` of `{}`: {}, the response was {}",
+                example, self.method, self.reason, self.response
+            ),
+            None => write!(f, "`{}`: {}", self.method, self.reason),
+        }
+    }
+}
+
+impl std::error::Error for ContractFailure {}
+
+impl MethodObject {
+    /// The request bodies of the example pairings of the method, with the
+    /// name of each pairing. Pairings referring to shared examples are
+    /// skipped.
+    pub fn example_requests(&self) -> Vec<(String, Value)> {
+        let by_name = matches!(
+            self.param_structure,
+            Some(MethodObjectParamStructure::ByName)
+        );
+        let mut requests = Vec::new();
+        for pairing in self.examples.iter().flatten() {
+            let pairing = match pairing {
+                ExamplePairingOrReference::ExampleObject(pairing) => pairing,
+                ExamplePairingOrReference::ReferenceObject(_) => continue,
+            };
+            let params: Option<Vec<_>> = pairing
+                .params
+                .iter()
+                .map(|param| match param {
+                    ExampleOrReference::ExampleObject(example) => {
+                        Some((example.name.clone(), example.value.clone()))
+                    }
+                    ExampleOrReference::ReferenceObject(_) => None,
+                })
+                .collect();
+            let params = match params {
+                Some(params) if by_name => Value::Object(params.into_iter().collect()),
+                Some(params) => Value::Array(params.into_iter().map(|(_, value)| value).collect()),
+                None => continue,
+            };
+            let request = json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": self.name,
+                "params": params,
+            });
+            requests.push((pairing.name.clone(), request));
+        }
+        requests
+    }
+}
+
+/// Sends the example requests of `method` to `handler` and checks that every
+/// response carries a result valid against the document. Returns how many
+/// examples were checked, and fails for a method the document does not
+/// declare or one without example requests, which would pass vacuously.
+pub fn check_examples<H>(
+    document: &OpenrpcDocument,
+    method: &str,
+    mut handler: H,
+) -> Result<usize, ContractFailure>
+where
+    H: FnMut(&Value) -> Value,
+{
+    let unchecked = |reason: &str| ContractFailure {
+        method: method.to_string(),
+        example: None,
+        response: Value::Null,
+        reason: reason.to_string(),
+    };
+    let requests = match document.methods.iter().find(|m| m.name == method) {
+        Some(method) => method.example_requests(),
+        None => return Err(unchecked("the document declares no such method")),
+    };
+    if requests.is_empty() {
+        return Err(unchecked("the method has no example requests to check"));
+    }
+    let validator = Validator::new(document);
+    for (example, request) in &requests {
+        let response = handler(request);
+        let failure = |reason: String| ContractFailure {
+            method: method.to_string(),
+            example: Some(example.clone()),
+            response: response.clone(),
+            reason,
+        };
+        let result = match response.get("result") {
+            Some(result) => result,
+            None => return Err(failure("no result in the response".to_string())),
+        };
+        if let Err(err) = validator.validate_result(method, result) {
+            return Err(failure(err.to_string()));
+        }
+    }
+    Ok(requests.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::ContentDescriptorOrReference;
+
+    fn document() -> OpenrpcDocument {
+        let mut method = MethodObject::new("getSlot".to_string(), None);
+        method.params = vec![
+            ContentDescriptorOrReference::new_content_descriptor::<bool>(
+                "finalized".to_string(),
+                None,
+            ),
+        ];
+        method.result =
+            ContentDescriptorOrReference::new_content_descriptor::<u64>("slot".to_string(), None);
+        let mut document = OpenrpcDocument::default();
+        document.add_object_method(method);
+        document.synthesize_examples();
+        document
+    }
+
+    #[test]
+    fn examples_are_checked_against_the_result_schema() {
+        let document = document();
+        let (_, request) = &document.methods[0].example_requests()[0];
+        assert_eq!(request["method"], "getSlot");
+        assert!(request["params"][0].is_boolean());
+
+        let answered = check_examples(
+            &document,
+            "getSlot",
+            |request| json!({ "jsonrpc": "2.0", "id": request["id"], "result": 42 }),
+        );
+        assert_eq!(answered, Ok(1));
+
+        let failure = check_examples(
+            &document,
+            "getSlot",
+            |request| json!({ "jsonrpc": "2.0", "id": request["id"], "result": "42" }),
+        )
+        .unwrap_err();
+        assert_eq!(failure.example.as_deref(), Some("synthesized"));
+        assert!(failure.reason.contains("expected integer"));
+
+        let failure = check_examples(
+            &document,
+            "getSlot",
+            |request| json!({ "jsonrpc": "2.0", "id": request["id"], "error": { "code": -1 } }),
+        )
+        .unwrap_err();
+        assert_eq!(failure.reason, "no result in the response");
+    }
+
+    #[test]
+    fn nothing_to_check_is_a_failure() {
+        let answer = |_: &Value| json!({ "jsonrpc": "2.0", "id": 1, "result": 42 });
+        let failure = check_examples(&document(), "getBlock", answer).unwrap_err();
+        assert_eq!(failure.example, None);
+        assert_eq!(
+            failure.to_string(),
+            "`getBlock`: the document declares no such method"
+        );
+
+        let mut document = document();
+        document.methods[0].examples = None;
+        let failure = check_examples(&document, "getSlot", answer).unwrap_err();
+        assert_eq!(
+            failure.reason,
+            "the method has no example requests to check"
+        );
+    }
+}
@@ -0,0 +1,134 @@
+//! Documents for handlers registered outside `#[document_rpc]`.
+//!
+//! Methods added to a `jsonrpc_core::IoHandler` by hand carry no type
+//! information, so their document is assembled at runtime from the registered
+//! names and whatever the application knows about their params and results:
+//!
+//! ```ignore
+//! let hints = TypeHints::default()
+//!     .method("health", MethodHint::default().result::<bool>())
+//!     .method("setLogLevel", MethodHint::default().param::<String>("level"));
+//! let legacy = document_from_methods(io.iter().map(|(name, _)| name.as_str()), &hints);
+//! document.merge(legacy)?;
+//! ```
+//!
+//! The registered names come in through a plain iterator, so this crate does
+//! not depend on `jsonrpc-core`. A method without a hint is still documented,
+//! accepting any params and returning any value, and is marked as `x-untyped`.
+use crate::document::{
+    schema_for, ContentDescriptorOrReference, JSONSchema, MethodObject, OpenrpcDocument,
+};
+use schemars::JsonSchema;
+use std::collections::HashMap;
+
+/// The extension marking a method documented without any type hint.
+pub const UNTYPED_EXTENSION: &str = "x-untyped";
+
+/// What is known about the params and result of a method.
+#[derive(Clone, Default)]
+pub struct MethodHint {
+    description: Option<String>,
+    params: Vec<(String, JSONSchema)>,
+    result: Option<JSONSchema>,
+}
+
+impl MethodHint {
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    /// Appends a positional param named `name`.
+    pub fn param<T: ?Sized + JsonSchema>(mut self, name: &str) -> Self {
+        self.params.push((name.to_string(), schema_for::<T>()));
+        self
+    }
+
+    pub fn result<T: ?Sized + JsonSchema>(mut self) -> Self {
+        self.result = Some(schema_for::<T>());
+        self
+    }
+
+    fn to_method_object(&self, name: &str) -> MethodObject {
+        let mut method = MethodObject::new(name.to_string(), self.description.clone());
+        method.params = self
+            .params
+            .iter()
+            .map(|(name, schema)| {
+                ContentDescriptorOrReference::from_schema(name.clone(), None, schema.clone())
+            })
+            .collect();
+        method.result = ContentDescriptorOrReference::from_schema(
+            "result".to_string(),
+            None,
+            self.result
+                .clone()
+                .unwrap_or(JSONSchema::JSONSchemaBoolean(true)),
+        );
+        method
+    }
+}
+
+/// The hints of the methods, keyed by method name.
+#[derive(Clone, Default)]
+pub struct TypeHints {
+    methods: HashMap<String, MethodHint>,
+}
+
+impl TypeHints {
+    pub fn method(mut self, name: &str, hint: MethodHint) -> Self {
+        self.methods.insert(name.to_string(), hint);
+        self
+    }
+}
+
+/// A document with one method per name in `methods`, in the given order.
+pub fn document_from_methods<'a, I>(methods: I, hints: &TypeHints) -> OpenrpcDocument
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let mut document = OpenrpcDocument::default();
+    for name in methods {
+        let method = match hints.methods.get(name) {
+            Some(hint) => hint.to_method_object(name),
+            None => {
+                let mut method = MethodHint::default().to_method_object(name);
+                method
+                    .extensions
+                    .insert(UNTYPED_EXTENSION.to_string(), serde_json::Value::Bool(true));
+                method
+            }
+        };
+        document.add_object_method(method);
+    }
+    document
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hinted_and_untyped_methods() {
+        let hints = TypeHints::default().method(
+            "setLogLevel",
+            MethodHint::default()
+                .param::<String>("level")
+                .result::<bool>(),
+        );
+        let document = document_from_methods(["setLogLevel", "health"], &hints);
+        let document = serde_json::to_value(document).unwrap();
+
+        let hinted = &document["methods"][0];
+        assert_eq!(hinted["name"], "setLogLevel");
+        assert_eq!(hinted["params"][0]["name"], "level");
+        assert_eq!(hinted["params"][0]["schema"]["type"], "string");
+        assert_eq!(hinted["result"]["schema"]["type"], "boolean");
+        assert!(hinted.get(UNTYPED_EXTENSION).is_none());
+
+        let untyped = &document["methods"][1];
+        assert_eq!(untyped["params"], serde_json::json!([]));
+        assert_eq!(untyped["result"]["schema"], true);
+        assert_eq!(untyped[UNTYPED_EXTENSION], true);
+    }
+}
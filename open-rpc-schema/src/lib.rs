@@ -1,2 +1,43 @@
+extern crate alloc;
+
+pub mod backend;
+pub mod coerce;
 pub mod document;
+#[cfg(feature = "std")]
+pub mod dump;
+pub mod errors;
+pub mod generator;
+pub mod golden;
+pub mod introspect;
+pub mod merge;
+pub mod negotiate;
+pub mod notification;
+pub mod params;
+pub mod pointer;
+pub mod policy;
+#[cfg(feature = "record")]
+pub mod record;
+pub mod registration;
+pub mod registry;
+pub mod samples;
+pub mod synthesize;
+pub mod table;
+pub mod validate;
+pub mod view;
 pub use schemars;
+
+/// Items the generated code refers to, so it does not rely on the prelude of
+/// the crate it expands in.
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::string::{String, ToString};
+    pub use alloc::vec;
+    pub use alloc::vec::Vec;
+    pub use core::default::Default;
+    pub use core::option::Option::{None, Some};
+    pub use serde_json;
+    #[cfg(feature = "std")]
+    pub use std::collections::HashMap;
+    #[cfg(feature = "std")]
+    pub use std::sync::OnceLock;
+}
@@ -0,0 +1,328 @@
+//! Merging documents generated by several documented traits.
+//!
+//! A binary whose api surface is assembled from several crates collects the
+//! `gen_schema()` output of each documented trait and merges them under a
+//! single info and servers block:
+//!
+//! ```ignore
+//! let document = OpenrpcDocument::aggregate(
+//!     info,
+//!     [accounts::gen_schema(), blocks::gen_schema()],
+//! )?
+//! .set_servers(servers);
+//! ```
+//!
+//! Traits whose method names may collide are merged with a namespace each.
+//! A method whose name an earlier document declares is then published as
+//! `<namespace>_<method>` instead of failing the merge, and a component schema
+//! named like a different one of an earlier document as `<namespace>_<name>`,
+//! the way `SchemaRegistry` publishes plugins:
+//!
+//! ```ignore
+//! let document = OpenrpcDocument::aggregate_namespaced(
+//!     info,
+//!     [("accounts", accounts::gen_schema()), ("blocks", blocks::gen_schema())],
+//!     Collisions::Prefix,
+//! )?;
+//! ```
+use crate::document::{
+    Components, InfoObject, MethodObject, MethodObjectName, OpenrpcDocument, Servers,
+};
+use crate::registry;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+
+/// The extension keeping the name a method was declared with when merging
+/// published it under its namespace.
+pub const ORIGINAL_NAME_EXTENSION: &str = "x-original-name";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MergeError {
+    /// Two merged documents declare a method with the same name.
+    DuplicateMethod(MethodObjectName),
+}
+
+impl fmt::Display for MergeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MergeError::DuplicateMethod(name) => {
+                write!(f, "method `{}` is declared by more than one document", name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MergeError {}
+
+/// What merging does with a method whose name is already taken.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Collisions {
+    /// Publish it as `<namespace>_<method>`, keeping its name under
+    /// `x-original-name`, and a differing component schema of the same name
+    /// as `<namespace>_<name>`.
+    #[default]
+    Prefix,
+    /// Fail with `MergeError::DuplicateMethod`, like `merge`.
+    Strict,
+}
+
+impl OpenrpcDocument {
+    pub fn set_servers(mut self, servers: Servers) -> Self {
+        self.servers = Some(servers);
+        self
+    }
+
+    /// Appends the methods and components of `other` to this document.
+    ///
+    /// The info, servers and external docs of `other` are discarded. A
+    /// component already present in this document is kept as is.
+    pub fn merge(&mut self, other: OpenrpcDocument) -> Result<(), MergeError> {
+        self.merge_namespaced("", other, Collisions::Strict)
+    }
+
+    /// Appends the methods and components of `other`, the document of the
+    /// trait published under `namespace`, to this document.
+    ///
+    /// With `Collisions::Prefix` a method whose name is taken is renamed to
+    /// `<namespace>_<method>`, which only fails when that name is taken too,
+    /// and a component that differs from the one of this document under its
+    /// name is renamed to `<namespace>_<name>`, the references of `other`
+    /// following it. A failed merge leaves this document unchanged.
+    pub fn merge_namespaced(
+        &mut self,
+        namespace: &str,
+        other: OpenrpcDocument,
+        collisions: Collisions,
+    ) -> Result<(), MergeError> {
+        let mut methods: Vec<MethodObject> = Vec::with_capacity(other.methods.len());
+        for mut method in other.methods {
+            let taken = |name: &str| self.methods.iter().chain(&methods).any(|m| m.name == name);
+            if taken(&method.name) {
+                if collisions == Collisions::Strict {
+                    return Err(MergeError::DuplicateMethod(method.name));
+                }
+                let name = format!("{}_{}", namespace, method.name);
+                if taken(&name) {
+                    return Err(MergeError::DuplicateMethod(name));
+                }
+                let original = std::mem::replace(&mut method.name, name);
+                method
+                    .extensions
+                    .insert(ORIGINAL_NAME_EXTENSION.to_string(), Value::String(original));
+            }
+            methods.push(method);
+        }
+        match collisions {
+            Collisions::Prefix => {
+                self.components = registry::merge_components(
+                    namespace,
+                    &mut methods,
+                    other.components,
+                    self.components.take(),
+                );
+            }
+            Collisions::Strict => {
+                if let Some(components) = other.components {
+                    merge_components(
+                        self.components.get_or_insert_with(Components::default),
+                        components,
+                    );
+                }
+            }
+        }
+        self.methods.extend(methods);
+        Ok(())
+    }
+
+    /// Merges `documents` into a single document described by `info`.
+    pub fn aggregate<I>(info: InfoObject, documents: I) -> Result<Self, MergeError>
+    where
+        I: IntoIterator<Item = OpenrpcDocument>,
+    {
+        let mut aggregate = OpenrpcDocument::default().set_info(info);
+        for document in documents {
+            aggregate.merge(document)?;
+        }
+        Ok(aggregate)
+    }
+
+    /// Merges the documents of the traits, each with its namespace, into a
+    /// single document described by `info`.
+    pub fn aggregate_namespaced<'a, I>(
+        info: InfoObject,
+        documents: I,
+        collisions: Collisions,
+    ) -> Result<Self, MergeError>
+    where
+        I: IntoIterator<Item = (&'a str, OpenrpcDocument)>,
+    {
+        let mut aggregate = OpenrpcDocument::default().set_info(info);
+        for (namespace, document) in documents {
+            aggregate.merge_namespaced(namespace, document, collisions)?;
+        }
+        Ok(aggregate)
+    }
+}
+
+fn merge_components(into: &mut Components, other: Components) {
+    fn extend<V>(into: &mut Option<HashMap<String, V>>, other: Option<HashMap<String, V>>) {
+        if let Some(other) = other {
+            let into = into.get_or_insert_with(HashMap::new);
+            for (name, value) in other {
+                into.entry(name).or_insert(value);
+            }
+        }
+    }
+    extend(&mut into.schemas, other.schemas);
+    extend(&mut into.links, other.links);
+    extend(&mut into.errors, other.errors);
+    extend(&mut into.examples, other.examples);
+    extend(&mut into.example_pairings, other.example_pairings);
+    extend(&mut into.content_descriptors, other.content_descriptors);
+    extend(&mut into.tags, other.tags);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::{ContentDescriptorOrReference, JSONSchema};
+    use serde_json::json;
+
+    fn document_with(methods: &[&str]) -> OpenrpcDocument {
+        let mut document = OpenrpcDocument::default();
+        for name in methods {
+            document.add_object_method(MethodObject::new(name.to_string(), None));
+        }
+        document
+    }
+
+    fn info() -> InfoObject {
+        InfoObject {
+            title: "node".to_string(),
+            description: None,
+            terms_of_service: None,
+            version: "1.0.0".to_string(),
+            contact: None,
+            license: None,
+        }
+    }
+
+    #[test]
+    fn test_aggregate_documents() {
+        let info = info();
+        let document = OpenrpcDocument::aggregate(
+            info.clone(),
+            [document_with(&["getBalance"]), document_with(&["getBlock"])],
+        )
+        .unwrap();
+        assert_eq!(document.info.title, "node");
+        let names: Vec<_> = document.methods.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, ["getBalance", "getBlock"]);
+
+        let err = OpenrpcDocument::aggregate(
+            info,
+            [
+                document_with(&["getBalance"]),
+                document_with(&["getBalance"]),
+            ],
+        )
+        .err();
+        assert_eq!(
+            err,
+            Some(MergeError::DuplicateMethod("getBalance".to_string()))
+        );
+    }
+
+    #[test]
+    fn colliding_methods_are_namespaced() {
+        let documents = || {
+            [
+                ("accounts", document_with(&["getBalance", "getSlot"])),
+                ("blocks", document_with(&["getSlot"])),
+            ]
+        };
+        let document =
+            OpenrpcDocument::aggregate_namespaced(info(), documents(), Collisions::Prefix).unwrap();
+        let names: Vec<_> = document.methods.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, ["getBalance", "getSlot", "blocks_getSlot"]);
+        assert!(!document.methods[1]
+            .extensions
+            .contains_key(ORIGINAL_NAME_EXTENSION));
+        assert_eq!(
+            document.methods[2].extensions[ORIGINAL_NAME_EXTENSION],
+            "getSlot"
+        );
+
+        let err =
+            OpenrpcDocument::aggregate_namespaced(info(), documents(), Collisions::Strict).err();
+        assert_eq!(
+            err,
+            Some(MergeError::DuplicateMethod("getSlot".to_string()))
+        );
+
+        let mut document = document;
+        let err =
+            document.merge_namespaced("blocks", document_with(&["getSlot"]), Collisions::Prefix);
+        assert_eq!(
+            err,
+            Err(MergeError::DuplicateMethod("blocks_getSlot".to_string()))
+        );
+    }
+
+    fn document_returning_item(method: &str, item: Value) -> OpenrpcDocument {
+        let mut document = OpenrpcDocument::default();
+        let mut method = MethodObject::new(method.to_string(), None);
+        let schema = json!({ "$ref": "#/components/schemas/Item" });
+        method.result = ContentDescriptorOrReference::from_schema(
+            "item".to_string(),
+            None,
+            JSONSchema::from_json(schema).unwrap(),
+        );
+        document.add_object_method(method);
+        document.add_schema("Item", JSONSchema::from_json(item).unwrap());
+        document
+    }
+
+    #[test]
+    fn differing_components_are_namespaced() {
+        let account =
+            json!({ "type": "object", "properties": { "lamports": { "type": "integer" } } });
+        let block = json!({ "type": "object", "properties": { "slot": { "type": "integer" } } });
+        let document = OpenrpcDocument::aggregate_namespaced(
+            info(),
+            [
+                (
+                    "accounts",
+                    document_returning_item("getAccount", account.clone()),
+                ),
+                ("blocks", document_returning_item("getBlock", block.clone())),
+                (
+                    "cache",
+                    document_returning_item("getCached", account.clone()),
+                ),
+            ],
+            Collisions::Prefix,
+        )
+        .unwrap();
+        let document = serde_json::to_value(document).unwrap();
+        let schemas = &document["components"]["schemas"];
+        assert_eq!(schemas.as_object().unwrap().len(), 2);
+        assert_eq!(schemas["Item"], account);
+        assert_eq!(schemas["blocks_Item"], block);
+        let references: Vec<_> = document["methods"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|method| method["result"]["schema"]["$ref"].clone())
+            .collect();
+        assert_eq!(
+            references,
+            [
+                "#/components/schemas/Item",
+                "#/components/schemas/blocks_Item",
+                "#/components/schemas/Item"
+            ]
+        );
+    }
+}
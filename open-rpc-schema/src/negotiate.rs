@@ -0,0 +1,112 @@
+//! Serving several versions of the API from one discover method.
+//!
+//! A server keeping the `v1` document around next to `v2` answers a client
+//! advertising the version, or the hash of the document, it was built against
+//! with that document, and tells it which of its methods are gone from the
+//! latest one:
+//!
+//! ```ignore
+//! let versions = Versions::default()
+//!     .version(v1::openrpc_schema_Rpc::gen_schema())
+//!     .version(v2::openrpc_schema_Rpc::gen_schema());
+//! match versions.negotiate(advertised) {
+//!     Some(negotiation) => warn_about(&negotiation.removed),
+//!     None => serve(versions.latest()),
+//! }
+//! ```
+use crate::document::OpenrpcDocument;
+
+/// The documents of the versions of an API, from the oldest to the latest.
+#[derive(Clone, Default)]
+pub struct Versions {
+    documents: Vec<(String, OpenrpcDocument)>,
+}
+
+/// The version a client advertised and how it differs from the latest one.
+#[derive(Clone)]
+pub struct Negotiation<'a> {
+    pub document: &'a OpenrpcDocument,
+    /// The methods of `document` the latest version no longer has.
+    pub removed: Vec<String>,
+}
+
+impl OpenrpcDocument {
+    /// A hash of the serialized document, fixed across builds and platforms
+    /// so clients can advertise the document they were generated from.
+    pub fn schema_hash(&self) -> String {
+        // going through `Value` sorts the keys of the maps
+        let json = serde_json::to_value(self)
+            .map(|value| value.to_string())
+            .unwrap_or_default();
+        // FNV-1a, like the seeds of the synthesized examples
+        let hash = json.bytes().fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+        format!("{:016x}", hash)
+    }
+}
+
+impl Versions {
+    /// Adds the document of the next version.
+    pub fn version(mut self, document: OpenrpcDocument) -> Self {
+        self.documents.push((document.schema_hash(), document));
+        self
+    }
+
+    pub fn latest(&self) -> Option<&OpenrpcDocument> {
+        self.documents.last().map(|(_, document)| document)
+    }
+
+    /// The document whose `info.version` or `schema_hash()` is `advertised`,
+    /// `None` if no version matches.
+    pub fn negotiate(&self, advertised: &str) -> Option<Negotiation<'_>> {
+        let (_, document) =
+            self.documents.iter().rev().find(|(hash, document)| {
+                hash == advertised || document.info.version == advertised
+            })?;
+        let latest = self.latest()?;
+        let removed = document
+            .methods
+            .iter()
+            .filter(|method| latest.methods.iter().all(|m| m.name != method.name))
+            .map(|method| method.name.clone())
+            .collect();
+        Some(Negotiation { document, removed })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::MethodObject;
+
+    fn document(version: &str, methods: &[&str]) -> OpenrpcDocument {
+        let mut document = OpenrpcDocument::default();
+        document.info.version = version.to_string();
+        for name in methods {
+            document.add_object_method(MethodObject::new(name.to_string(), None));
+        }
+        document
+    }
+
+    #[test]
+    fn negotiates_by_version_or_hash() {
+        let v1 = document("1.0.0", &["getBlock", "getConfirmedBlock"]);
+        let v2 = document("2.0.0", &["getBlock", "getBlocks"]);
+        let hash = v1.schema_hash();
+        assert_eq!(hash, v1.clone().schema_hash());
+        assert_ne!(hash, v2.schema_hash());
+
+        let versions = Versions::default().version(v1).version(v2);
+        assert_eq!(versions.latest().unwrap().info.version, "2.0.0");
+
+        let negotiation = versions.negotiate("1.0.0").unwrap();
+        assert_eq!(negotiation.document.info.version, "1.0.0");
+        assert_eq!(negotiation.removed, ["getConfirmedBlock"]);
+        let negotiation = versions.negotiate(&hash).unwrap();
+        assert_eq!(negotiation.document.schema_hash(), hash);
+
+        assert!(versions.negotiate("2.0.0").unwrap().removed.is_empty());
+        assert!(versions.negotiate("3.0.0").is_none());
+    }
+}
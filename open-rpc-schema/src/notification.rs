@@ -0,0 +1,103 @@
+//! The frames a server pushes to the subscribers of a subscription.
+//!
+//! Every notification of a subscription is sent as
+//! `{"jsonrpc":"2.0","method":"<subscription>","params":{"subscription":id,"result":T}}`.
+//! The document describes that envelope in `components.schemas` and refers to
+//! it from the subscribe method through `x-notification`, and clients
+//! deserialize the frames into [`Notification`].
+use crate::document::{schema_for, JSONSchema, SubscriptionId};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+/// The extension of a subscribe method referring to its notification schema.
+pub const NOTIFICATION_EXTENSION: &str = "x-notification";
+const JSONRPC_VERSION: &str = "2.0";
+
+/// A notification frame of a subscription.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Notification<T> {
+    pub jsonrpc: String,
+    pub method: String,
+    pub params: NotificationParams<T>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct NotificationParams<T> {
+    pub subscription: SubscriptionId,
+    pub result: T,
+}
+
+impl<T> Notification<T> {
+    pub fn new(method: &str, subscription: SubscriptionId, result: T) -> Self {
+        Notification {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            method: method.to_string(),
+            params: NotificationParams {
+                subscription,
+                result,
+            },
+        }
+    }
+}
+
+/// The schema of the notifications of the subscription `method` whose
+/// results follow `result`.
+pub fn envelope_schema(method: &str, result: JSONSchema) -> JSONSchema {
+    let mut definitions = Map::new();
+    let mut inline = |schema: JSONSchema| {
+        let mut schema = serde_json::to_value(schema).unwrap_or(Value::Bool(true));
+        if let Value::Object(schema) = &mut schema {
+            schema.remove("$schema");
+            if let Some(Value::Object(nested)) = schema.remove("definitions") {
+                definitions.extend(nested);
+            }
+        }
+        schema
+    };
+    let subscription = inline(schema_for::<SubscriptionId>());
+    let result = inline(result);
+    let envelope = json!({
+        "title": method,
+        "type": "object",
+        "required": ["jsonrpc", "method", "params"],
+        "properties": {
+            "jsonrpc": { "const": JSONRPC_VERSION },
+            "method": { "const": method },
+            "params": {
+                "type": "object",
+                "required": ["subscription", "result"],
+                "properties": {
+                    "subscription": subscription,
+                    "result": result,
+                },
+            },
+        },
+        "definitions": definitions,
+    });
+    JSONSchema::from_json(envelope).unwrap_or(JSONSchema::JSONSchemaBoolean(true))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_match_the_envelope_schema() {
+        let frame = Notification::new("slotNotification", SubscriptionId::Number(3), 42u64);
+        assert_eq!(
+            serde_json::to_value(&frame).unwrap(),
+            json!({
+                "jsonrpc": "2.0",
+                "method": "slotNotification",
+                "params": { "subscription": 3, "result": 42 },
+            })
+        );
+
+        let schema =
+            serde_json::to_value(envelope_schema("slotNotification", schema_for::<u64>())).unwrap();
+        assert_eq!(schema["properties"]["method"]["const"], "slotNotification");
+        let params = &schema["properties"]["params"]["properties"];
+        assert_eq!(params["result"]["format"], "uint64");
+        assert_eq!(params["subscription"]["anyOf"].as_array().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,223 @@
+//! Reshaping the documented params of a method to how the server reads them.
+use crate::document::{
+    ContentDescriptorObject, ContentDescriptorOrReference, JSONSchema, MethodObject,
+    MethodObjectParamStructure,
+};
+use schemars::schema::{InstanceType, ObjectValidation, RootSchema, Schema, SchemaObject};
+
+/// The extension marking a method whose by-name params must all be declared.
+pub const DENY_UNKNOWN_PARAMS_EXTENSION: &str = "x-deny-unknown-params";
+/// The name of the single param [`MethodObject::params_as_object`] documents.
+const PARAMS_OBJECT_NAME: &str = "params";
+
+impl MethodObject {
+    /// Sets `required` on the params, by position.
+    pub fn mark_required(&mut self, required: &[bool]) {
+        for (param, required) in self.params.iter_mut().zip(required) {
+            if let ContentDescriptorOrReference::ContentDescriptorObject(param) = param {
+                param.required = Some(*required);
+            }
+        }
+    }
+
+    /// Documents every property of the single object param as a param of its
+    /// own, taken by name and required unless its field is optional. Returns
+    /// whether the param was an object, the params are left as they are
+    /// otherwise.
+    pub fn expand_params(&mut self) -> bool {
+        let root = match self.params.as_slice() {
+            [ContentDescriptorOrReference::ContentDescriptorObject(ContentDescriptorObject {
+                schema: JSONSchema::JsonSchemaObject(root),
+                ..
+            })] => root,
+            _ => return false,
+        };
+        // a recursive type is a reference to its definition
+        let schema = match &root.schema.reference {
+            Some(reference) => match reference
+                .strip_prefix("#/definitions/")
+                .and_then(|name| root.definitions.get(name))
+            {
+                Some(Schema::Object(definition)) => definition,
+                _ => return false,
+            },
+            None => &root.schema,
+        };
+        let object = match &schema.object {
+            Some(object) => object,
+            None => return false,
+        };
+        let params = object
+            .properties
+            .iter()
+            .map(|(name, schema)| {
+                let (schema, description) = match schema {
+                    Schema::Object(schema) => (
+                        JSONSchema::JsonSchemaObject(RootSchema {
+                            meta_schema: None,
+                            schema: schema.clone(),
+                            definitions: root.definitions.clone(),
+                        }),
+                        schema
+                            .metadata
+                            .as_ref()
+                            .and_then(|metadata| metadata.description.clone()),
+                    ),
+                    Schema::Bool(schema) => (JSONSchema::JSONSchemaBoolean(*schema), None),
+                };
+                ContentDescriptorOrReference::ContentDescriptorObject(ContentDescriptorObject {
+                    name: name.clone(),
+                    description,
+                    summary: None,
+                    schema,
+                    required: Some(object.required.contains(name)),
+                    deprecated: None,
+                })
+            })
+            .collect();
+        self.params = params;
+        self.param_structure = Some(MethodObjectParamStructure::ByName);
+        true
+    }
+
+    /// Documents all params as the properties of a single object param
+    /// named `params`, taken by position, so the params of a call are
+    /// `[{ .. }]`. The params named in `optional` may be left out. Returns whether the params were reshaped, which a reference
+    /// to a param whose schema is unknown here prevents.
+    pub fn params_as_object(&mut self, optional: &[&str]) -> bool {
+        let mut object = ObjectValidation::default();
+        let mut definitions = schemars::Map::new();
+        for param in &self.params {
+            let param = match param {
+                ContentDescriptorOrReference::ContentDescriptorObject(param) => param,
+                ContentDescriptorOrReference::ReferenceObject(_) => return false,
+            };
+            let schema = match &param.schema {
+                JSONSchema::JsonSchemaObject(root) => {
+                    definitions.extend(root.definitions.clone());
+                    Schema::Object(root.schema.clone())
+                }
+                JSONSchema::JSONSchemaBoolean(schema) => Schema::Bool(*schema),
+            };
+            object.properties.insert(param.name.clone(), schema);
+            if !optional.contains(&param.name.as_str()) {
+                object.required.insert(param.name.clone());
+            }
+        }
+        let schema = RootSchema {
+            meta_schema: None,
+            schema: SchemaObject {
+                instance_type: Some(InstanceType::Object.into()),
+                object: Some(Box::new(object)),
+                ..Default::default()
+            },
+            definitions,
+        };
+        self.params = vec![ContentDescriptorOrReference::from_schema(
+            PARAMS_OBJECT_NAME.to_string(),
+            None,
+            JSONSchema::JsonSchemaObject(schema),
+        )];
+        self.param_structure = Some(MethodObjectParamStructure::ByPosition);
+        true
+    }
+
+    /// Rejects the by-name params the method does not declare, marking it
+    /// with `x-deny-unknown-params` for the validator. The object of
+    /// [`MethodObject::params_as_object`] also gets
+    /// `additionalProperties: false`.
+    pub fn deny_unknown_params(&mut self) {
+        self.extensions.insert(
+            DENY_UNKNOWN_PARAMS_EXTENSION.to_string(),
+            serde_json::Value::Bool(true),
+        );
+        if let [ContentDescriptorOrReference::ContentDescriptorObject(param)] =
+            self.params.as_mut_slice()
+        {
+            if param.name != PARAMS_OBJECT_NAME {
+                return;
+            }
+            if let JSONSchema::JsonSchemaObject(root) = &mut param.schema {
+                root.schema.object().additional_properties = Some(Box::new(Schema::Bool(false)));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::{schema_for, ReferenceObject};
+    use schemars::JsonSchema;
+
+    #[allow(dead_code)]
+    #[derive(JsonSchema)]
+    struct ListOptions {
+        /// Where the previous page ended.
+        cursor: Option<String>,
+        limit: u32,
+    }
+
+    #[test]
+    fn expand_object_param() {
+        let mut method = MethodObject::new("list".to_string(), None);
+        method.params = vec![ContentDescriptorOrReference::from_schema(
+            "options".to_string(),
+            None,
+            schema_for::<ListOptions>(),
+        )];
+        assert!(method.expand_params());
+        let params = serde_json::to_value(&method.params).unwrap();
+        assert_eq!(params[0]["name"], "cursor");
+        assert_eq!(params[0]["required"], false);
+        assert_eq!(params[0]["description"], "Where the previous page ended.");
+        assert_eq!(params[1]["name"], "limit");
+        assert_eq!(params[1]["required"], true);
+        assert_eq!(params[1]["schema"]["format"], "uint32");
+
+        method.params = vec![ContentDescriptorOrReference::new_content_descriptor::<u64>(
+            "slot".to_string(),
+            None,
+        )];
+        assert!(!method.expand_params());
+    }
+
+    #[test]
+    fn params_as_object() {
+        let mut method = MethodObject::new("transfer".to_string(), None);
+        method.params = vec![
+            ContentDescriptorOrReference::new_content_descriptor::<String>("to".to_string(), None),
+            ContentDescriptorOrReference::new_content_descriptor::<Option<String>>(
+                "memo".to_string(),
+                None,
+            ),
+        ];
+        assert!(method.params_as_object(&["memo"]));
+        let params = serde_json::to_value(&method.params).unwrap();
+        assert_eq!(params.as_array().unwrap().len(), 1);
+        assert_eq!(params[0]["name"], "params");
+        assert_eq!(params[0]["schema"]["type"], "object");
+        assert_eq!(params[0]["schema"]["properties"]["to"]["type"], "string");
+        assert_eq!(params[0]["schema"]["required"], serde_json::json!(["to"]));
+
+        method.deny_unknown_params();
+        let method = serde_json::to_value(&method).unwrap();
+        assert_eq!(method["paramStructure"], "by-position");
+        assert_eq!(method[DENY_UNKNOWN_PARAMS_EXTENSION], true);
+        assert_eq!(method["params"][0]["schema"]["additionalProperties"], false);
+    }
+
+    #[test]
+    fn referenced_params_are_not_reshaped() {
+        let mut method = MethodObject::new("transfer".to_string(), None);
+        method.params = vec![
+            ContentDescriptorOrReference::new_content_descriptor::<String>("to".to_string(), None),
+            ContentDescriptorOrReference::ReferenceObject(ReferenceObject {
+                reference: "#/components/contentDescriptors/memo".to_string(),
+            }),
+        ];
+        assert!(!method.params_as_object(&[]));
+        assert_eq!(method.params.len(), 2);
+        assert!(method.param_structure.is_none());
+    }
+}
@@ -0,0 +1,121 @@
+//! JSON Pointers into the serialized document.
+//!
+//! Middleware validating requests attaches the location of the violated
+//! schema to the errors it returns, e.g. `/methods/3/params/1` for the second
+//! param of the fourth method, so clients can look it up in the document they
+//! discovered.
+use crate::document::{ContentDescriptorOrReference, OpenrpcDocument};
+use std::collections::HashMap;
+
+/// The pointers to the methods of a document and to their params and result.
+#[derive(Clone, Debug, Default)]
+pub struct PointerIndex {
+    methods: HashMap<String, MethodPointers>,
+}
+
+#[derive(Clone, Debug)]
+struct MethodPointers {
+    method: String,
+    params: Vec<(String, String)>,
+    result: String,
+}
+
+impl PointerIndex {
+    /// The pointer to the method object of `method`.
+    pub fn method(&self, method: &str) -> Option<&str> {
+        Some(self.methods.get(method)?.method.as_str())
+    }
+
+    /// The pointer to the content descriptor of the param `param` of `method`.
+    pub fn param(&self, method: &str, param: &str) -> Option<&str> {
+        let (_, pointer) = self
+            .methods
+            .get(method)?
+            .params
+            .iter()
+            .find(|(name, _)| name == param)?;
+        Some(pointer.as_str())
+    }
+
+    /// The pointer to the result content descriptor of `method`.
+    pub fn result(&self, method: &str) -> Option<&str> {
+        Some(self.methods.get(method)?.result.as_str())
+    }
+}
+
+impl OpenrpcDocument {
+    /// Indexes the location of every method, param and result.
+    ///
+    /// A param documented as a reference has no name to index it by, it is
+    /// indexed under its position instead, e.g. `"0"`.
+    pub fn pointer_index(&self) -> PointerIndex {
+        let methods = self
+            .methods
+            .iter()
+            .enumerate()
+            .map(|(index, method)| {
+                let pointer = format!("/methods/{}", index);
+                let params = method
+                    .params
+                    .iter()
+                    .enumerate()
+                    .map(|(position, param)| {
+                        let name = match param {
+                            ContentDescriptorOrReference::ContentDescriptorObject(param) => {
+                                param.name.clone()
+                            }
+                            ContentDescriptorOrReference::ReferenceObject(_) => {
+                                position.to_string()
+                            }
+                        };
+                        (name, format!("{}/params/{}", pointer, position))
+                    })
+                    .collect();
+                let pointers = MethodPointers {
+                    result: format!("{}/result", pointer),
+                    method: pointer,
+                    params,
+                };
+                (method.name.clone(), pointers)
+            })
+            .collect();
+        PointerIndex { methods }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::document::{ContentDescriptorOrReference, MethodObject, OpenrpcDocument};
+
+    #[test]
+    fn pointers_resolve_in_the_serialized_document() {
+        let mut document = OpenrpcDocument::default();
+        document.add_object_method(MethodObject::new("ping".to_string(), None));
+        let mut method = MethodObject::new("getBlock".to_string(), None);
+        method.params = vec![
+            ContentDescriptorOrReference::new_content_descriptor::<u64>("slot".to_string(), None),
+            ContentDescriptorOrReference::new_content_descriptor::<bool>("full".to_string(), None),
+        ];
+        method.result = ContentDescriptorOrReference::new_content_descriptor::<String>(
+            "block".to_string(),
+            None,
+        );
+        document.add_object_method(method);
+
+        let index = document.pointer_index();
+        let json = serde_json::to_value(&document).unwrap();
+        assert_eq!(index.method("getBlock"), Some("/methods/1"));
+        assert_eq!(index.param("getBlock", "full"), Some("/methods/1/params/1"));
+        assert_eq!(
+            json.pointer(index.param("getBlock", "full").unwrap())
+                .unwrap()["name"],
+            "full"
+        );
+        assert_eq!(
+            json.pointer(index.result("getBlock").unwrap()).unwrap()["name"],
+            "block"
+        );
+        assert_eq!(index.param("getBlock", "missing"), None);
+        assert_eq!(index.method("missing"), None);
+    }
+}
@@ -0,0 +1,293 @@
+//! The operational policy of a method, read from the document.
+//!
+//! `#[rpc(idempotent)]`, `#[rpc(rate_limit(requests = 10, per_ms = 1000))]`
+//! and `#[rpc(timeout_ms = ..)]` land in the document as `x-idempotent`,
+//! `x-rate-limit` and `x-timeout`. Clients built on the document, e.g. on the
+//! generated request builders, look the [`CallPolicy`] of a method up instead
+//! of configuring retries and limits by hand:
+//!
+//! ```ignore
+//! let policy = &openrpc_schema_Rpc::call_policies()["getBlock"];
+//! let block = policy
+//!     .call_async(tokio::time::sleep, || transport.send(requests::get_block(slot, None)))
+//!     .await?;
+//! ```
+//!
+//! Waiting is left to the caller, which passes the sleep of its runtime, so
+//! neither async clients nor `wasm32` targets are blocked by a thread sleep.
+use crate::document::{MethodObject, OpenrpcDocument};
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+const IDEMPOTENT_EXTENSION: &str = "x-idempotent";
+const RATE_LIMIT_EXTENSION: &str = "x-rate-limit";
+const TIMEOUT_EXTENSION: &str = "x-timeout";
+
+/// How often a method may be called.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RateLimit {
+    pub requests: u32,
+    pub per: Duration,
+}
+
+/// Exponential backoff between the retries of an idempotent call.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Backoff {
+    pub max_retries: u32,
+    pub initial: Duration,
+    pub max: Duration,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Backoff {
+            max_retries: 3,
+            initial: Duration::from_millis(100),
+            max: Duration::from_secs(2),
+        }
+    }
+}
+
+impl Backoff {
+    /// The delay before the retry `attempt`, counting from 0.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 1u32.checked_shl(attempt).unwrap_or(u32::MAX);
+        self.initial.saturating_mul(factor).min(self.max)
+    }
+}
+
+/// What a client honors when calling a method.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CallPolicy {
+    /// Only idempotent methods are retried.
+    pub retry: Option<Backoff>,
+    pub rate_limit: Option<RateLimit>,
+    pub timeout: Option<Duration>,
+}
+
+impl MethodObject {
+    pub fn call_policy(&self) -> CallPolicy {
+        let extension = |name: &str| self.extensions.get(name);
+        let idempotent = extension(IDEMPOTENT_EXTENSION).and_then(|v| v.as_bool());
+        let rate_limit = extension(RATE_LIMIT_EXTENSION).and_then(|limit| {
+            Some(RateLimit {
+                requests: u32::try_from(limit["requests"].as_u64()?).ok()?,
+                per: Duration::from_millis(limit["perMs"].as_u64()?),
+            })
+        });
+        // a limit never refilling its tokens is no limit to keep
+        let rate_limit = rate_limit.filter(|limit| limit.requests > 0 && !limit.per.is_zero());
+        CallPolicy {
+            retry: (idempotent == Some(true)).then(Backoff::default),
+            rate_limit,
+            timeout: extension(TIMEOUT_EXTENSION)
+                .and_then(|v| v.as_u64())
+                .map(Duration::from_millis),
+        }
+    }
+}
+
+impl OpenrpcDocument {
+    /// The policy of every method, keyed by method name.
+    pub fn call_policies(&self) -> HashMap<String, CallPolicy> {
+        self.methods
+            .iter()
+            .map(|method| (method.name.clone(), method.call_policy()))
+            .collect()
+    }
+}
+
+impl CallPolicy {
+    /// How long to wait before retrying the failed `attempt`, counting from
+    /// 0, `None` when the call is not retried again.
+    pub fn retry_delay(&self, attempt: u32) -> Option<Duration> {
+        self.retry
+            .filter(|retry| attempt < retry.max_retries)
+            .map(|retry| retry.delay(attempt))
+    }
+
+    /// Runs `call`, retrying the failures of an idempotent method after
+    /// `sleep` waited out the backoff delay, e.g. `std::thread::sleep`.
+    pub fn call<T, E>(
+        &self,
+        mut sleep: impl FnMut(Duration),
+        mut call: impl FnMut() -> Result<T, E>,
+    ) -> Result<T, E> {
+        let mut attempt = 0;
+        loop {
+            match call() {
+                Err(err) => match self.retry_delay(attempt) {
+                    Some(delay) => sleep(delay),
+                    None => return Err(err),
+                },
+                result => return result,
+            }
+            attempt += 1;
+        }
+    }
+
+    /// [`CallPolicy::call`] for async clients, awaiting the future `sleep`
+    /// returns for the backoff delay, e.g. `tokio::time::sleep`.
+    pub async fn call_async<T, E, F, S>(
+        &self,
+        mut sleep: impl FnMut(Duration) -> S,
+        mut call: impl FnMut() -> F,
+    ) -> Result<T, E>
+    where
+        F: Future<Output = Result<T, E>>,
+        S: Future<Output = ()>,
+    {
+        let mut attempt = 0;
+        loop {
+            match call().await {
+                Err(err) => match self.retry_delay(attempt) {
+                    Some(delay) => sleep(delay).await,
+                    None => return Err(err),
+                },
+                result => return result,
+            }
+            attempt += 1;
+        }
+    }
+
+    /// A limiter keeping the calls within the rate limit of the method,
+    /// `None` for a method without one.
+    pub fn limiter(&self) -> Option<RateLimiter> {
+        self.rate_limit.map(RateLimiter::new)
+    }
+}
+
+/// A token bucket refilling at the rate of a [`RateLimit`].
+#[derive(Clone, Debug)]
+pub struct RateLimiter {
+    limit: RateLimit,
+    tokens: f64,
+    refilled: Option<Instant>,
+}
+
+impl RateLimiter {
+    pub fn new(limit: RateLimit) -> Self {
+        RateLimiter {
+            limit,
+            tokens: f64::from(limit.requests),
+            refilled: None,
+        }
+    }
+
+    /// Takes a token at `now`, or tells how long to wait for the next one.
+    pub fn acquire(&mut self, now: Instant) -> Result<(), Duration> {
+        let capacity = f64::from(self.limit.requests);
+        let per_token = self.limit.per.as_secs_f64() / capacity;
+        if let Some(refilled) = self.refilled {
+            let elapsed = now.saturating_duration_since(refilled).as_secs_f64();
+            self.tokens = (self.tokens + elapsed / per_token).min(capacity);
+        }
+        self.refilled = Some(now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            let wait = (1.0 - self.tokens) * per_token;
+            Err(Duration::try_from_secs_f64(wait).unwrap_or(Duration::MAX))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn policy_follows_the_annotations() {
+        let mut method = MethodObject::new("getBlock".to_string(), None);
+        method
+            .extensions
+            .insert(IDEMPOTENT_EXTENSION.to_string(), json!(true));
+        method.extensions.insert(
+            RATE_LIMIT_EXTENSION.to_string(),
+            json!({ "requests": 2, "perMs": 1000 }),
+        );
+        let policy = method.call_policy();
+        let backoff = policy.retry.unwrap();
+        assert_eq!(backoff.delay(0), Duration::from_millis(100));
+        assert_eq!(backoff.delay(1), Duration::from_millis(200));
+        assert_eq!(backoff.delay(40), backoff.max);
+
+        let mut calls = 0;
+        let mut waited = Vec::new();
+        let result: Result<(), ()> = policy.call(
+            |delay| waited.push(delay),
+            || {
+                calls += 1;
+                Err(())
+            },
+        );
+        assert!(result.is_err());
+        assert_eq!(calls, 4);
+        assert_eq!(waited, [100, 200, 400].map(Duration::from_millis));
+        assert_eq!(policy.retry_delay(3), None);
+
+        let mut limiter = policy.limiter().unwrap();
+        let now = Instant::now();
+        assert!(limiter.acquire(now).is_ok());
+        assert!(limiter.acquire(now).is_ok());
+        assert_eq!(limiter.acquire(now), Err(Duration::from_millis(500)));
+        assert!(limiter.acquire(now + Duration::from_millis(500)).is_ok());
+
+        let plain = MethodObject::new("sendTransaction".to_string(), None).call_policy();
+        assert_eq!(plain, CallPolicy::default());
+        let mut calls = 0;
+        let _: Result<(), ()> = plain.call(
+            |_| panic!("not retried"),
+            || {
+                calls += 1;
+                Err(())
+            },
+        );
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn async_calls_await_the_backoff() {
+        let policy = CallPolicy {
+            retry: Some(Backoff::default()),
+            ..CallPolicy::default()
+        };
+        let mut calls = 0;
+        let mut waited = Vec::new();
+        let result = {
+            let call = policy.call_async(
+                |delay| {
+                    waited.push(delay);
+                    std::future::ready(())
+                },
+                || {
+                    calls += 1;
+                    std::future::ready(if calls < 3 { Err(()) } else { Ok(calls) })
+                },
+            );
+            let mut context = std::task::Context::from_waker(std::task::Waker::noop());
+            std::pin::pin!(call).poll(&mut context)
+        };
+        assert_eq!(result, std::task::Poll::Ready(Ok(3)));
+        assert_eq!(waited, [100, 200].map(Duration::from_millis));
+    }
+
+    #[test]
+    fn zero_rate_limits_do_not_panic() {
+        let mut method = MethodObject::new("getBlock".to_string(), None);
+        method.extensions.insert(
+            RATE_LIMIT_EXTENSION.to_string(),
+            json!({ "requests": 0, "perMs": 1000 }),
+        );
+        assert_eq!(method.call_policy().rate_limit, None);
+
+        let mut limiter = RateLimiter::new(RateLimit {
+            requests: 0,
+            per: Duration::from_secs(1),
+        });
+        assert_eq!(limiter.acquire(Instant::now()), Err(Duration::MAX));
+    }
+}
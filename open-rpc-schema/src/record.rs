@@ -0,0 +1,222 @@
+//! Example pairings recorded from the calls integration tests make.
+//!
+//! ```ignore
+//! let result = client.get_block(slot)?;
+//! open_rpc_schema::record::record_example("getBlock", (slot,), &result)?;
+//! ```
+//!
+//! Every call appends a line to the file named by `OPENRPC_RECORD_FILE`, or
+//! `target/openrpc-examples.jsonl`. The dump binary or a build step then
+//! merges the recorded pairs with [`OpenrpcDocument::merge_recorded_examples`],
+//! so the examples are whatever the server really answered.
+use crate::document::{
+    ContentDescriptorOrReference, ExampleObject, ExampleOrReference, ExamplePairingObject,
+    ExamplePairingObjectResult, ExamplePairingOrReference, MethodObject, OpenrpcDocument,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::OpenOptions;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+const RECORD_FILE_VAR: &str = "OPENRPC_RECORD_FILE";
+const DEFAULT_RECORD_FILE: &str = "target/openrpc-examples.jsonl";
+const RECORDED_EXAMPLE_NAME: &str = "recorded";
+
+#[derive(Serialize, Deserialize)]
+struct Recorded {
+    method: String,
+    params: Value,
+    result: Value,
+}
+
+/// The file calls are recorded to.
+pub fn record_file() -> PathBuf {
+    std::env::var_os(RECORD_FILE_VAR)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_RECORD_FILE))
+}
+
+/// Records a call of `method` to the [`record_file`]. `params` serializes to
+/// an array of the params by position or an object of them by name.
+pub fn record_example<P: Serialize, R: Serialize>(
+    method: &str,
+    params: P,
+    result: R,
+) -> io::Result<()> {
+    record_example_to(record_file(), method, params, result)
+}
+
+/// [`record_example`] to another file.
+pub fn record_example_to<P: Serialize, R: Serialize>(
+    path: impl AsRef<Path>,
+    method: &str,
+    params: P,
+    result: R,
+) -> io::Result<()> {
+    let recorded = Recorded {
+        method: method.to_string(),
+        params: serde_json::to_value(params)?,
+        result: serde_json::to_value(result)?,
+    };
+    let mut line = serde_json::to_vec(&recorded)?;
+    line.push(b'\n');
+    if let Some(dir) = path
+        .as_ref()
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+    {
+        std::fs::create_dir_all(dir)?;
+    }
+    // one write per line, so tests recording in parallel do not interleave
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(&line)
+}
+
+impl OpenrpcDocument {
+    /// Adds the calls recorded to `path` as example pairings of their
+    /// methods, skipping calls of unknown methods and pairs the method
+    /// already has. Returns how many pairings were added.
+    pub fn merge_recorded_examples(&mut self, path: impl AsRef<Path>) -> io::Result<usize> {
+        let mut merged = 0;
+        for line in BufReader::new(std::fs::File::open(path)?).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let recorded: Recorded = serde_json::from_str(&line)?;
+            let method = match self.methods.iter_mut().find(|m| m.name == recorded.method) {
+                Some(method) => method,
+                None => continue,
+            };
+            let count = method.examples.as_ref().map_or(0, Vec::len);
+            let name = format!("{}-{}", RECORDED_EXAMPLE_NAME, count + 1);
+            let pairing = recorded_pairing(method, name, recorded);
+            let examples = method.examples.get_or_insert_with(Vec::new);
+            if examples.iter().any(|example| same_pair(example, &pairing)) {
+                continue;
+            }
+            examples.push(ExamplePairingOrReference::ExampleObject(pairing));
+            merged += 1;
+        }
+        Ok(merged)
+    }
+}
+
+fn recorded_pairing(
+    method: &MethodObject,
+    name: String,
+    recorded: Recorded,
+) -> ExamplePairingObject {
+    let names: Vec<_> = method.params.iter().map(content_name).collect();
+    let params = match recorded.params {
+        Value::Array(values) => values
+            .into_iter()
+            .enumerate()
+            .map(|(i, value)| {
+                let name = names.get(i).cloned().unwrap_or_else(|| i.to_string());
+                (name, value)
+            })
+            .collect(),
+        Value::Object(values) => values.into_iter().collect(),
+        Value::Null => vec![],
+        value => vec![(names.first().cloned().unwrap_or_default(), value)],
+    };
+    ExamplePairingObject {
+        name,
+        description: None,
+        params: params
+            .into_iter()
+            .map(|(name, value)| ExampleOrReference::ExampleObject(example(name, value)))
+            .collect(),
+        result: ExamplePairingObjectResult::ExampleObject(example(
+            content_name(&method.result),
+            recorded.result,
+        )),
+    }
+}
+
+fn content_name(content: &ContentDescriptorOrReference) -> String {
+    match content {
+        ContentDescriptorOrReference::ContentDescriptorObject(descriptor) => {
+            descriptor.name.clone()
+        }
+        ContentDescriptorOrReference::ReferenceObject(_) => String::new(),
+    }
+}
+
+fn example(name: String, value: Value) -> ExampleObject {
+    ExampleObject {
+        summary: None,
+        value,
+        description: None,
+        name,
+    }
+}
+
+fn same_pair(example: &ExamplePairingOrReference, pairing: &ExamplePairingObject) -> bool {
+    match example {
+        ExamplePairingOrReference::ExampleObject(example) => {
+            pair_values(example) == pair_values(pairing)
+        }
+        ExamplePairingOrReference::ReferenceObject(_) => false,
+    }
+}
+
+fn pair_values(pairing: &ExamplePairingObject) -> (Vec<Option<&Value>>, Option<&Value>) {
+    let params = pairing
+        .params
+        .iter()
+        .map(|param| match param {
+            ExampleOrReference::ExampleObject(param) => Some(&param.value),
+            ExampleOrReference::ReferenceObject(_) => None,
+        })
+        .collect();
+    let result = match &pairing.result {
+        ExamplePairingObjectResult::ExampleObject(result) => Some(&result.value),
+        ExamplePairingObjectResult::ReferenceObject(_) => None,
+    };
+    (params, result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_recorded_calls() {
+        let path =
+            std::env::temp_dir().join(format!("openrpc-record-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        record_example_to(&path, "getSlot", ("finalized",), 42).unwrap();
+        record_example_to(&path, "getSlot", ("finalized",), 42).unwrap();
+        record_example_to(&path, "getPeers", (), vec!["a"]).unwrap();
+
+        let mut document = OpenrpcDocument::default();
+        let mut method = MethodObject::new("getSlot".to_string(), None);
+        method.params = vec![
+            ContentDescriptorOrReference::new_content_descriptor::<String>(
+                "commitment".to_string(),
+                None,
+            ),
+        ];
+        method.result =
+            ContentDescriptorOrReference::new_content_descriptor::<u64>("slot".to_string(), None);
+        document.add_object_method(method);
+
+        assert_eq!(document.merge_recorded_examples(&path).unwrap(), 1);
+        std::fs::remove_file(&path).unwrap();
+        let examples = serde_json::to_value(&document.methods[0].examples).unwrap();
+        assert_eq!(
+            examples,
+            serde_json::json!([{
+                "name": "recorded-1",
+                "params": [{ "name": "commitment", "value": "finalized" }],
+                "result": { "name": "slot", "value": 42 },
+            }])
+        );
+    }
+}
@@ -0,0 +1,48 @@
+//! The methods of a trait as the attribute macros parsed them.
+//!
+//! A proc-macro crate can only export macros, so instead of a library API the
+//! parsed model is emitted as the `REGISTRATIONS` table of every documented
+//! trait. Downstream generators and build tools read it, or its JSON, instead
+//! of parsing the `#[rpc]` and `#[pubsub]` attributes again:
+//!
+//! ```ignore
+//! let json = serde_json::to_string(openrpc_schema_Rpc::REGISTRATIONS)?;
+//! ```
+use serde::Serialize;
+
+/// What the attribute of a trait method registers it as.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RegistrationKind {
+    Method,
+    Subscribe,
+    Unsubscribe,
+}
+
+/// An argument of a trait method.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisteredParam {
+    pub name: &'static str,
+    /// The type as the trait spells it.
+    pub rust_type: &'static str,
+}
+
+/// A wire method registered by an attribute of a trait method.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MethodRegistration {
+    pub name: &'static str,
+    pub rust_name: &'static str,
+    pub kind: RegistrationKind,
+    /// The subscription of a pubsub method.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscription: Option<&'static str>,
+    pub aliases: &'static [&'static str],
+    /// Whether the method takes the `Metadata` of the trait.
+    pub has_metadata: bool,
+    /// The arguments besides `self`, the metadata and the subscriber.
+    pub params: &'static [RegisteredParam],
+    /// The return type as the trait spells it.
+    pub returns: &'static str,
+}
@@ -0,0 +1,343 @@
+//! A host assembling its api from plugins loaded at startup.
+//!
+//! Every plugin registers the document of its traits under a namespace, and
+//! the host answers `rpc.discover` with the merged document:
+//!
+//! ```ignore
+//! let mut registry = SchemaRegistry::new(info);
+//! registry.register("accounts", accounts::gen_schema())?;
+//! registry.register("blocks", blocks::gen_schema())?;
+//! let document = registry.discover();
+//! ```
+//!
+//! The methods of a plugin are published as `<namespace>_<method>`. Component
+//! schemas the plugins share unchanged are published once, a component whose
+//! name another plugin already uses for something else is renamed to
+//! `<namespace>_<name>` and the references of the plugin follow it.
+use crate::document::{Components, InfoObject, MethodObject, OpenrpcDocument};
+use crate::merge::MergeError;
+use serde_json::Value;
+use std::collections::HashMap;
+
+const NAMESPACE_SEPARATOR: &str = "_";
+
+/// The component maps whose entries are deduplicated across plugins.
+const COMPONENT_KINDS: [&str; 7] = [
+    "schemas",
+    "links",
+    "errors",
+    "examples",
+    "examplePairings",
+    "contentDescriptors",
+    "tags",
+];
+
+type ComponentMap = HashMap<String, Option<Value>>;
+
+/// The merged document of every registered plugin.
+#[derive(Clone)]
+pub struct SchemaRegistry {
+    document: OpenrpcDocument,
+}
+
+impl SchemaRegistry {
+    pub fn new(info: InfoObject) -> Self {
+        SchemaRegistry {
+            document: OpenrpcDocument::default().set_info(info),
+        }
+    }
+
+    /// Adds the methods and components of `document` under `namespace`.
+    ///
+    /// Fails with `MergeError::DuplicateMethod` when a namespaced method is
+    /// already registered, leaving the registry unchanged.
+    pub fn register(
+        &mut self,
+        namespace: &str,
+        document: OpenrpcDocument,
+    ) -> Result<(), MergeError> {
+        let mut methods = Vec::with_capacity(document.methods.len());
+        for method in &document.methods {
+            let name = namespaced(namespace, &method.name);
+            if self.document.methods.iter().any(|m| m.name == name)
+                || methods.iter().any(|m: &MethodObject| m.name == name)
+            {
+                return Err(MergeError::DuplicateMethod(name));
+            }
+            let mut method = method.clone();
+            method.name = name;
+            methods.push(method);
+        }
+
+        self.document.components = merge_components(
+            namespace,
+            &mut methods,
+            document.components,
+            self.document.components.take(),
+        );
+        self.document.methods.extend(methods);
+        Ok(())
+    }
+
+    /// The document served by `rpc.discover`.
+    pub fn discover(&self) -> &OpenrpcDocument {
+        &self.document
+    }
+
+    pub fn into_document(self) -> OpenrpcDocument {
+        self.document
+    }
+}
+
+/// Merges the `components` of the `methods` published under `namespace` into
+/// the `registered` ones. A component conflicting with a registered one is
+/// renamed to `<namespace>_<name>`, and the references of the methods and
+/// components follow it.
+pub(crate) fn merge_components(
+    namespace: &str,
+    methods: &mut [MethodObject],
+    components: Option<Components>,
+    registered: Option<Components>,
+) -> Option<Components> {
+    let mut components = components_by_kind(components);
+    let registered = components_by_kind(registered);
+    let renames = renames(namespace, &components, &registered);
+    for method in methods {
+        rewrite_method(method, &renames);
+    }
+    for (kind, map) in &mut components {
+        *map = map
+            .drain()
+            .map(|(name, mut value)| {
+                if let Some(value) = &mut value {
+                    rewrite_refs(value, &renames);
+                }
+                let name = renames
+                    .get(&reference(kind, &name))
+                    .map(|_| namespaced(namespace, &name))
+                    .unwrap_or(name);
+                (name, value)
+            })
+            .collect();
+    }
+
+    let mut merged = registered;
+    for (kind, map) in components {
+        let into = merged.entry(kind).or_default();
+        for (name, value) in map {
+            into.entry(name).or_insert(value);
+        }
+    }
+    components_from_kinds(merged)
+}
+
+fn namespaced(namespace: &str, name: &str) -> String {
+    format!("{}{}{}", namespace, NAMESPACE_SEPARATOR, name)
+}
+
+fn reference(kind: &str, name: &str) -> String {
+    format!("#/components/{}/{}", kind, name)
+}
+
+/// The references to the components of a plugin that can't be shared, with the
+/// reference replacing them.
+///
+/// A component is shared when the registry holds the same value under its
+/// name. Renaming one component changes the value of those referring to it, so
+/// the renames are grown until no further component conflicts.
+fn renames(
+    namespace: &str,
+    components: &HashMap<&'static str, ComponentMap>,
+    registered: &HashMap<&'static str, ComponentMap>,
+) -> HashMap<String, String> {
+    let mut renames = HashMap::new();
+    loop {
+        let mut conflicts = Vec::new();
+        for (kind, map) in components {
+            let registered = match registered.get(kind) {
+                Some(registered) => registered,
+                None => continue,
+            };
+            for (name, value) in map {
+                let from = reference(kind, name);
+                if renames.contains_key(&from) {
+                    continue;
+                }
+                let mut value = value.clone();
+                if let Some(value) = &mut value {
+                    rewrite_refs(value, &renames);
+                }
+                if matches!(registered.get(name), Some(existing) if *existing != value) {
+                    conflicts.push((from, reference(kind, &namespaced(namespace, name))));
+                }
+            }
+        }
+        if conflicts.is_empty() {
+            return renames;
+        }
+        renames.extend(conflicts);
+    }
+}
+
+pub(crate) fn rewrite_method(method: &mut MethodObject, renames: &HashMap<String, String>) {
+    if renames.is_empty() {
+        return;
+    }
+    let rewritten = serde_json::to_value(&*method).ok().and_then(|mut value| {
+        rewrite_refs(&mut value, renames);
+        serde_json::from_value(value).ok()
+    });
+    if let Some(rewritten) = rewritten {
+        *method = rewritten;
+    }
+}
+
+pub(crate) fn rewrite_refs(value: &mut Value, renames: &HashMap<String, String>) {
+    match value {
+        Value::Object(object) => {
+            for (key, value) in object {
+                match value {
+                    Value::String(reference) if key == "$ref" => {
+                        if let Some(renamed) = renames.get(reference.as_str()) {
+                            *reference = renamed.clone();
+                        }
+                    }
+                    value => rewrite_refs(value, renames),
+                }
+            }
+        }
+        Value::Array(values) => values
+            .iter_mut()
+            .for_each(|value| rewrite_refs(value, renames)),
+        _ => {}
+    }
+}
+
+fn components_by_kind(components: Option<Components>) -> HashMap<&'static str, ComponentMap> {
+    let components = components.unwrap_or_default();
+    COMPONENT_KINDS
+        .into_iter()
+        .zip([
+            components.schemas,
+            components.links,
+            components.errors,
+            components.examples,
+            components.example_pairings,
+            components.content_descriptors,
+            components.tags,
+        ])
+        .filter_map(|(kind, map)| Some((kind, map?)))
+        .collect()
+}
+
+fn components_from_kinds(mut kinds: HashMap<&'static str, ComponentMap>) -> Option<Components> {
+    if kinds.is_empty() {
+        return None;
+    }
+    Some(Components {
+        schemas: kinds.remove("schemas"),
+        links: kinds.remove("links"),
+        errors: kinds.remove("errors"),
+        examples: kinds.remove("examples"),
+        example_pairings: kinds.remove("examplePairings"),
+        content_descriptors: kinds.remove("contentDescriptors"),
+        tags: kinds.remove("tags"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::{ContentDescriptorOrReference, ReferenceObject};
+    use serde_json::json;
+
+    fn plugin(method: &str, schemas: &[(&str, Value)]) -> OpenrpcDocument {
+        let mut document = OpenrpcDocument::default();
+        let mut method = MethodObject::new(method.to_string(), None);
+        method.params = vec![ContentDescriptorOrReference::ReferenceObject(
+            ReferenceObject {
+                reference: "#/components/schemas/Page".to_string(),
+            },
+        )];
+        document.add_object_method(method);
+        document.components = Some(Components {
+            schemas: Some(
+                schemas
+                    .iter()
+                    .map(|(name, schema)| (name.to_string(), Some(schema.clone())))
+                    .collect(),
+            ),
+            ..Components::default()
+        });
+        document
+    }
+
+    #[test]
+    fn shares_identical_and_namespaces_differing_components() {
+        let info = InfoObject {
+            title: "node".to_string(),
+            description: None,
+            terms_of_service: None,
+            version: "1.0.0".to_string(),
+            contact: None,
+            license: None,
+        };
+        let hash = json!({ "type": "string" });
+        let mut registry = SchemaRegistry::new(info);
+        registry
+            .register(
+                "accounts",
+                plugin(
+                    "list",
+                    &[
+                        ("Hash", hash.clone()),
+                        ("Page", json!({ "$ref": "#/components/schemas/Item" })),
+                        ("Item", json!({ "type": "integer" })),
+                    ],
+                ),
+            )
+            .unwrap();
+        registry
+            .register(
+                "blocks",
+                plugin(
+                    "list",
+                    &[
+                        ("Hash", hash),
+                        ("Page", json!({ "$ref": "#/components/schemas/Item" })),
+                        ("Item", json!({ "type": "object" })),
+                    ],
+                ),
+            )
+            .unwrap();
+
+        let document = registry.discover();
+        let names: Vec<_> = document.methods.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, ["accounts_list", "blocks_list"]);
+        let schemas = document
+            .components
+            .as_ref()
+            .unwrap()
+            .schemas
+            .as_ref()
+            .unwrap();
+        let mut keys: Vec<_> = schemas.keys().map(String::as_str).collect();
+        keys.sort_unstable();
+        assert_eq!(keys, ["Hash", "Item", "Page", "blocks_Item", "blocks_Page"]);
+        assert_eq!(
+            schemas["blocks_Page"],
+            Some(json!({ "$ref": "#/components/schemas/blocks_Item" }))
+        );
+        let blocks = serde_json::to_value(&document.methods[1]).unwrap();
+        assert_eq!(
+            blocks["params"][0]["$ref"],
+            "#/components/schemas/blocks_Page"
+        );
+
+        let err = registry.register("blocks", plugin("list", &[])).err();
+        assert_eq!(
+            err,
+            Some(MergeError::DuplicateMethod("blocks_list".to_string()))
+        );
+    }
+}
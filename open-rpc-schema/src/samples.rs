@@ -0,0 +1,217 @@
+//! Copy-pasteable usage of a method, emitted as the `x-code-samples`
+//! extension documentation viewers render next to the method.
+//!
+//! The samples call the method with the values of its first example pairing,
+//! or with values synthesized from its schemas when it has none.
+use crate::document::{
+    ContentDescriptorOrReference, ExampleOrReference, ExamplePairingOrReference, JSONSchema,
+    MethodObject, MethodObjectParamStructure,
+};
+use crate::synthesize::{method_seed, synthesize_pairing, DEFAULT_SEED};
+use schemars::schema::{InstanceType, Schema, SchemaObject, SingleOrVec};
+use serde_json::{json, Map, Value};
+
+/// The extension holding the samples of a method.
+pub const CODE_SAMPLES_EXTENSION: &str = "x-code-samples";
+
+impl MethodObject {
+    /// Adds a Rust, a curl and a TypeScript sample of a call to the method.
+    ///
+    /// `rust_fn` and `rust_args` name the trait method and its arguments, as
+    /// the Rust client calls them.
+    pub fn add_code_samples(&mut self, rust_fn: &str, rust_args: &[&str]) {
+        let params = self.example_params();
+        let by_name = matches!(
+            self.param_structure,
+            Some(MethodObjectParamStructure::ByName)
+        );
+        let wire_params = if by_name {
+            Value::Object(params.iter().cloned().collect())
+        } else {
+            Value::Array(params.iter().map(|(_, value)| value.clone()).collect())
+        };
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": self.name,
+            "params": wire_params,
+        });
+        let samples = [
+            (
+                "Rust",
+                rust_sample(rust_fn, rust_args, &params, |arg| self.optional_arg(arg)),
+            ),
+            ("curl", curl_sample(&request)),
+            ("TypeScript", typescript_sample(&request)),
+        ]
+        .into_iter()
+        .map(|(lang, source)| json!({ "lang": lang, "source": source }))
+        .collect();
+        self.extensions
+            .insert(CODE_SAMPLES_EXTENSION.to_string(), Value::Array(samples));
+    }
+
+    /// The names and values of the params in the first example pairing.
+    fn example_params(&self) -> Vec<(String, Value)> {
+        let pairing = match self.examples.iter().flatten().next() {
+            Some(ExamplePairingOrReference::ExampleObject(pairing)) => pairing.clone(),
+            _ => synthesize_pairing(self, method_seed(DEFAULT_SEED, &self.name)),
+        };
+        pairing
+            .params
+            .into_iter()
+            .filter_map(|param| match param {
+                ExampleOrReference::ExampleObject(example) => Some((example.name, example.value)),
+                ExampleOrReference::ReferenceObject(_) => None,
+            })
+            .collect()
+    }
+
+    /// Whether the Rust argument `arg` is an `Option`, as far as the
+    /// documented params tell: a param left out or taking null.
+    fn optional_arg(&self, arg: &str) -> bool {
+        let params = self.params.iter().filter_map(|param| match param {
+            ContentDescriptorOrReference::ContentDescriptorObject(param) => Some(param),
+            ContentDescriptorOrReference::ReferenceObject(_) => None,
+        });
+        for param in params {
+            let root = match &param.schema {
+                JSONSchema::JsonSchemaObject(root) => root,
+                JSONSchema::JSONSchemaBoolean(_) => continue,
+            };
+            if param.name == arg {
+                return param.required != Some(true) || nullable(&root.schema);
+            }
+            // the arguments documented as the properties of one object param
+            if let Some(object) = &root.schema.object {
+                if let Some(Schema::Object(property)) = object.properties.get(arg) {
+                    return !object.required.contains(arg) || nullable(property);
+                }
+            }
+        }
+        false
+    }
+}
+
+fn nullable(schema: &SchemaObject) -> bool {
+    let takes_null = |schema: &Schema| match schema {
+        Schema::Object(schema) => nullable(schema),
+        Schema::Bool(accepts) => *accepts,
+    };
+    let typed_null = match &schema.instance_type {
+        Some(SingleOrVec::Single(ty)) => **ty == InstanceType::Null,
+        Some(SingleOrVec::Vec(types)) => types.contains(&InstanceType::Null),
+        None => false,
+    };
+    typed_null
+        || schema
+            .subschemas
+            .as_ref()
+            .and_then(|subschemas| subschemas.any_of.as_ref())
+            .is_some_and(|any_of| any_of.iter().any(takes_null))
+}
+
+fn rust_sample(
+    rust_fn: &str,
+    rust_args: &[&str],
+    params: &[(String, Value)],
+    optional: impl Fn(&str) -> bool,
+) -> String {
+    let mut source = String::new();
+    for arg in rust_args {
+        let value = rust_arg_value(arg, rust_args.len(), params);
+        let source_value = match &value {
+            Value::Null => "None".to_string(),
+            Value::Bool(_) | Value::Number(_) => value.to_string(),
+            Value::String(_) => format!("{}.to_string()", value),
+            _ => format!("serde_json::from_value(serde_json::json!({}))?", value),
+        };
+        let value = if !value.is_null() && optional(arg) {
+            format!("Some({})", source_value)
+        } else {
+            source_value
+        };
+        source.push_str(&format!("let {} = {};\n", arg, value));
+    }
+    source.push_str(&format!(
+        "let result = client.{}({}).await?;",
+        rust_fn,
+        rust_args.join(", ")
+    ));
+    source
+}
+
+/// The value of the Rust argument `arg`, which params reshaped from the
+/// arguments no longer name directly.
+fn rust_arg_value(arg: &str, arg_count: usize, params: &[(String, Value)]) -> Value {
+    if let Some((_, value)) = params.iter().find(|(name, _)| name == arg) {
+        return value.clone();
+    }
+    // the arguments documented as the properties of one object param
+    if let [(_, Value::Object(object))] = params {
+        if let Some(value) = object.get(arg) {
+            return value.clone();
+        }
+    }
+    // the fields of a single struct argument documented as params
+    if arg_count == 1 {
+        return Value::Object(params.iter().cloned().collect::<Map<_, _>>());
+    }
+    Value::Null
+}
+
+fn curl_sample(request: &Value) -> String {
+    format!(
+        "curl -X POST -H 'Content-Type: application/json' \\\n  --data '{}' \\\n  \"$RPC_URL\"",
+        request.to_string().replace('\'', "'\\''")
+    )
+}
+
+fn typescript_sample(request: &Value) -> String {
+    format!(
+        "const response = await fetch(RPC_URL, {{\n  method: \"POST\",\n  headers: {{ \"Content-Type\": \"application/json\" }},\n  body: JSON.stringify({}),\n}});\nconst {{ result }} = await response.json();",
+        request
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::ContentDescriptorOrReference;
+
+    #[test]
+    fn samples_call_the_method_with_its_params() {
+        let mut method = MethodObject::new("getBalance".to_string(), None);
+        method.params = vec![
+            ContentDescriptorOrReference::new_content_descriptor::<bool>(
+                "verbose".to_string(),
+                None,
+            ),
+            ContentDescriptorOrReference::new_content_descriptor::<Option<bool>>(
+                "pending".to_string(),
+                None,
+            ),
+        ];
+        method.mark_required(&[true, false]);
+        method.add_code_samples("get_balance", &["verbose", "pending"]);
+
+        let samples = &method.extensions[CODE_SAMPLES_EXTENSION];
+        let langs: Vec<_> = samples
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|sample| sample["lang"].as_str().unwrap())
+            .collect();
+        assert_eq!(langs, ["Rust", "curl", "TypeScript"]);
+        let rust = samples[0]["source"].as_str().unwrap();
+        assert_eq!(
+            rust,
+            "let verbose = false;\n\
+             let pending = Some(false);\n\
+             let result = client.get_balance(verbose, pending).await?;"
+        );
+        let curl = samples[1]["source"].as_str().unwrap();
+        assert!(curl.contains(r#""method":"getBalance""#));
+        assert!(curl.contains(r#""params":["#));
+    }
+}
@@ -0,0 +1,578 @@
+//! Placeholder examples synthesized from the param and result schemas.
+//!
+//! Methods without an explicit example pairing get one whose values follow
+//! their schemas: enum picks, numbers within the declared range, strings
+//! matching the declared pattern or from a small word list. The playground is
+//! then usable before authors write any examples of their own.
+//!
+//! The values are drawn from a generator seeded with the method name, so the
+//! document is the same on every build and adding a method leaves the
+//! examples of the others untouched.
+use crate::document::{
+    ContentDescriptorOrReference, ExampleObject, ExampleOrReference, ExamplePairingObject,
+    ExamplePairingObjectResult, ExamplePairingOrReference, JSONSchema, MethodObject,
+    OpenrpcDocument,
+};
+use regex_syntax::hir::{Class, Hir, HirKind, Literal};
+use schemars::schema::{InstanceType, RootSchema, Schema, SchemaObject, SingleOrVec};
+use serde_json::{Number, Value};
+
+const SYNTHESIZED_EXAMPLE_NAME: &str = "synthesized";
+pub(crate) const DEFAULT_SEED: u64 = 0x9e37_79b9_7f4a_7c15;
+const MAX_DEPTH: usize = 8;
+/// How many more times than required an unbounded repetition of a pattern
+/// repeats at most.
+const MAX_REPETITION: u32 = 3;
+const WORDS: &[&str] = &[
+    "alpha", "bravo", "charlie", "delta", "echo", "foxtrot", "golf", "hotel",
+];
+
+impl OpenrpcDocument {
+    /// Adds a synthesized example pairing to every method without examples.
+    pub fn synthesize_examples(&mut self) {
+        self.synthesize_examples_with_seed(DEFAULT_SEED)
+    }
+
+    /// [`OpenrpcDocument::synthesize_examples`] with another seed, for a
+    /// different but still stable set of values.
+    pub fn synthesize_examples_with_seed(&mut self, seed: u64) {
+        for method in &mut self.methods {
+            if method
+                .examples
+                .as_ref()
+                .is_some_and(|examples| !examples.is_empty())
+            {
+                continue;
+            }
+            let pairing = synthesize_pairing(method, method_seed(seed, &method.name));
+            method.examples = Some(vec![ExamplePairingOrReference::ExampleObject(pairing)]);
+        }
+    }
+}
+
+pub(crate) fn synthesize_pairing(method: &MethodObject, seed: u64) -> ExamplePairingObject {
+    let mut rng = Rng(seed);
+    let params = method
+        .params
+        .iter()
+        .filter_map(|param| synthesize_content(param, &mut rng))
+        .map(ExampleOrReference::ExampleObject)
+        .collect();
+    let result = synthesize_content(&method.result, &mut rng).unwrap_or(ExampleObject {
+        summary: None,
+        value: Value::Null,
+        description: None,
+        name: "result".to_string(),
+    });
+    ExamplePairingObject {
+        name: SYNTHESIZED_EXAMPLE_NAME.to_string(),
+        description: None,
+        params,
+        result: ExamplePairingObjectResult::ExampleObject(result),
+    }
+}
+
+fn synthesize_content(
+    content: &ContentDescriptorOrReference,
+    rng: &mut Rng,
+) -> Option<ExampleObject> {
+    let descriptor = match content {
+        ContentDescriptorOrReference::ContentDescriptorObject(descriptor) => descriptor,
+        ContentDescriptorOrReference::ReferenceObject(_) => return None,
+    };
+    let value = match &descriptor.schema {
+        JSONSchema::JsonSchemaObject(root) => Synthesizer { root, rng }.object(&root.schema, 0),
+        JSONSchema::JSONSchemaBoolean(_) => Value::Null,
+    };
+    Some(ExampleObject {
+        summary: None,
+        value,
+        description: None,
+        name: descriptor.name.clone(),
+    })
+}
+
+struct Synthesizer<'a> {
+    root: &'a RootSchema,
+    rng: &'a mut Rng,
+}
+
+impl Synthesizer<'_> {
+    fn schema(&mut self, schema: &Schema, depth: usize) -> Value {
+        match schema {
+            Schema::Object(object) => self.object(object, depth),
+            Schema::Bool(_) => Value::Null,
+        }
+    }
+
+    fn object(&mut self, schema: &SchemaObject, depth: usize) -> Value {
+        if depth > MAX_DEPTH {
+            return Value::Null;
+        }
+        // values the author wrote win over anything synthesized
+        if let Some(example) = schema.metadata.as_ref().and_then(|m| m.examples.first()) {
+            return example.clone();
+        }
+        if let Some(value) = &schema.const_value {
+            return value.clone();
+        }
+        if let Some(values) = schema
+            .enum_values
+            .as_ref()
+            .filter(|values| !values.is_empty())
+        {
+            return values[self.rng.below(values.len() as u64) as usize].clone();
+        }
+        if let Some(reference) = &schema.reference {
+            let name = reference.rsplit('/').next().unwrap_or_default();
+            return match self.root.definitions.get(name) {
+                Some(definition) => self.schema(definition, depth + 1),
+                None => Value::Null,
+            };
+        }
+        if let Some(subschemas) = &schema.subschemas {
+            let alternatives = subschemas
+                .any_of
+                .iter()
+                .chain(&subschemas.one_of)
+                .chain(&subschemas.all_of)
+                .flatten();
+            // prefer a value over the `null` of an optional
+            let mut fallback = None;
+            for alternative in alternatives {
+                match self.schema(alternative, depth + 1) {
+                    Value::Null => fallback = Some(Value::Null),
+                    value => return value,
+                }
+            }
+            if let Some(value) = fallback {
+                return value;
+            }
+        }
+        match instance_type(schema) {
+            Some(InstanceType::Boolean) => Value::Bool(self.rng.below(2) == 1),
+            Some(InstanceType::Integer) => self.integer(schema).into(),
+            Some(InstanceType::Number) => self.number(schema).into(),
+            Some(InstanceType::String) => Value::String(self.string(schema)),
+            Some(InstanceType::Array) => self.array(schema, depth),
+            Some(InstanceType::Object) => self.properties(schema, depth),
+            Some(InstanceType::Null) | None => Value::Null,
+        }
+    }
+
+    fn integer(&mut self, schema: &SchemaObject) -> Number {
+        let (format_minimum, format_maximum) = format_bounds(schema.format.as_deref());
+        let minimum = lower_bound(schema)
+            .or(format_minimum)
+            .unwrap_or(0)
+            .clamp(i64::MIN.into(), u64::MAX.into());
+        let maximum = upper_bound(schema)
+            .or(format_maximum)
+            .unwrap_or(i128::MAX)
+            .min(minimum + 1000)
+            .min(u64::MAX.into());
+        let value = if maximum <= minimum {
+            minimum
+        } else {
+            let span = (maximum - minimum) as u64;
+            minimum + i128::from(self.rng.below(span + 1))
+        };
+        match u64::try_from(value) {
+            Ok(value) => value.into(),
+            Err(_) => (value as i64).into(),
+        }
+    }
+
+    /// A whole number when one is in range, a fraction within the bounds
+    /// otherwise, e.g. for `0.1..=0.9`.
+    fn number(&mut self, schema: &SchemaObject) -> f64 {
+        let number = schema.number.as_deref();
+        let float = |keyword, stored| {
+            bound(schema, keyword, stored).map(|bound| bound.map_or_else(|f| f, |i| i as f64))
+        };
+        let minimum = float("minimum", number.and_then(|n| n.minimum));
+        let exclusive_minimum = float("exclusiveMinimum", number.and_then(|n| n.exclusive_minimum));
+        let maximum = float("maximum", number.and_then(|n| n.maximum));
+        let exclusive_maximum = float("exclusiveMaximum", number.and_then(|n| n.exclusive_maximum));
+        let (low, high) = match (minimum.or(exclusive_minimum), maximum.or(exclusive_maximum)) {
+            (Some(low), Some(high)) => (low, high.min(low + 1000.0)),
+            (Some(low), None) => (low, low + 1000.0),
+            (None, Some(high)) if high > 0.0 => (0.0, high),
+            (None, Some(high)) => (high - 1000.0, high),
+            (None, None) => (0.0, 1000.0),
+        };
+        let mut first = low.ceil();
+        if minimum.is_none() && exclusive_minimum.is_some() && first == low {
+            first += 1.0;
+        }
+        let mut last = high.floor();
+        if maximum.is_none() && exclusive_maximum.is_some() && last == high {
+            last -= 1.0;
+        }
+        if first <= last {
+            let span = (last - first).min(u64::MAX as f64) as u64;
+            return first + self.rng.below(span + 1) as f64;
+        }
+        // strictly between the bounds, which also satisfies exclusive ones
+        let fraction = (self.rng.below(999) + 1) as f64 / 1000.0;
+        low + (high - low) * fraction
+    }
+
+    fn string(&mut self, schema: &SchemaObject) -> String {
+        let string = schema.string.as_deref();
+        if let Some(pattern) = string.and_then(|string| string.pattern.as_deref()) {
+            if let Ok(hir) = regex_syntax::Parser::new().parse(pattern) {
+                let mut value = String::new();
+                if self.matching(&hir, &mut value) {
+                    return value;
+                }
+            }
+        }
+        let mut value = match schema.format.as_deref() {
+            Some("date-time") => "1970-01-01T00:00:00Z".to_string(),
+            Some("date") => "1970-01-01".to_string(),
+            Some("uuid") => "00000000-0000-0000-0000-000000000000".to_string(),
+            _ => WORDS[self.rng.below(WORDS.len() as u64) as usize].to_string(),
+        };
+        if let Some(string) = string {
+            let min_length = string.min_length.unwrap_or(0) as usize;
+            while value.chars().count() < min_length {
+                value.push('x');
+            }
+            if let Some(max_length) = string.max_length {
+                value = value.chars().take(max_length as usize).collect();
+            }
+        }
+        value
+    }
+
+    /// Appends a string matching `hir` to `value`, false when there is none
+    /// to write down, e.g. for a class of raw bytes.
+    fn matching(&mut self, hir: &Hir, value: &mut String) -> bool {
+        match hir.kind() {
+            HirKind::Empty | HirKind::Look(_) => true,
+            HirKind::Literal(Literal(bytes)) => match std::str::from_utf8(bytes) {
+                Ok(literal) => {
+                    value.push_str(literal);
+                    true
+                }
+                Err(_) => false,
+            },
+            HirKind::Class(Class::Unicode(class)) => {
+                // readable characters first, the start of the class otherwise
+                let readable: Vec<char> = ('a'..='z')
+                    .chain('0'..='9')
+                    .chain('A'..='Z')
+                    .filter(|c| {
+                        class
+                            .ranges()
+                            .iter()
+                            .any(|r| (r.start()..=r.end()).contains(c))
+                    })
+                    .collect();
+                let c = match readable.len() {
+                    0 => match class.ranges().first() {
+                        Some(range) => range.start(),
+                        None => return false,
+                    },
+                    len => readable[self.rng.below(len as u64) as usize],
+                };
+                value.push(c);
+                true
+            }
+            HirKind::Class(Class::Bytes(class)) => match class.ranges().first() {
+                Some(range) if range.start().is_ascii() => {
+                    value.push(char::from(range.start()));
+                    true
+                }
+                _ => false,
+            },
+            HirKind::Repetition(repetition) => {
+                let max = repetition
+                    .max
+                    .unwrap_or(u32::MAX)
+                    .min(repetition.min.saturating_add(MAX_REPETITION));
+                let count =
+                    repetition.min + self.rng.below(u64::from(max - repetition.min) + 1) as u32;
+                (0..count).all(|_| self.matching(&repetition.sub, value))
+            }
+            HirKind::Capture(capture) => self.matching(&capture.sub, value),
+            HirKind::Concat(hirs) => hirs.iter().all(|hir| self.matching(hir, value)),
+            HirKind::Alternation(hirs) => {
+                let hir = &hirs[self.rng.below(hirs.len() as u64) as usize];
+                self.matching(hir, value)
+            }
+        }
+    }
+
+    fn array(&mut self, schema: &SchemaObject, depth: usize) -> Value {
+        let array = match schema.array.as_deref() {
+            Some(array) => array,
+            None => return Value::Array(vec![]),
+        };
+        let items = match &array.items {
+            Some(SingleOrVec::Single(item)) => {
+                let count = array.min_items.unwrap_or(1).max(1);
+                (0..count).map(|_| self.schema(item, depth + 1)).collect()
+            }
+            Some(SingleOrVec::Vec(items)) => items
+                .iter()
+                .map(|item| self.schema(item, depth + 1))
+                .collect(),
+            None => vec![],
+        };
+        Value::Array(items)
+    }
+
+    fn properties(&mut self, schema: &SchemaObject, depth: usize) -> Value {
+        let properties = schema
+            .object
+            .as_deref()
+            .map(|object| {
+                object
+                    .properties
+                    .iter()
+                    .map(|(name, property)| (name.clone(), self.schema(property, depth + 1)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        Value::Object(properties)
+    }
+}
+
+/// The bounds implied by the integer formats schemars emits.
+fn format_bounds(format: Option<&str>) -> (Option<i128>, Option<i128>) {
+    match format {
+        Some("int8") => (Some(i8::MIN.into()), Some(i8::MAX.into())),
+        Some("uint8") => (None, Some(u8::MAX.into())),
+        Some("int16") => (Some(i16::MIN.into()), Some(i16::MAX.into())),
+        Some("uint16") => (None, Some(u16::MAX.into())),
+        _ => (None, None),
+    }
+}
+
+/// The smallest integer the bounds of the schema allow. Integer bounds set
+/// by `set_numeric_bound` are exact, the `f64` ones of schemars rounded in.
+fn lower_bound(schema: &SchemaObject) -> Option<i128> {
+    let number = schema.number.as_deref();
+    let inclusive = bound(schema, "minimum", number.and_then(|n| n.minimum))
+        .map(|minimum| minimum.unwrap_or_else(|minimum| minimum.ceil() as i128));
+    let exclusive = || {
+        bound(
+            schema,
+            "exclusiveMinimum",
+            number.and_then(|n| n.exclusive_minimum),
+        )
+        .map(|minimum| minimum.unwrap_or_else(|minimum| minimum.floor() as i128) + 1)
+    };
+    inclusive.or_else(exclusive)
+}
+
+/// The largest integer the bounds of the schema allow.
+fn upper_bound(schema: &SchemaObject) -> Option<i128> {
+    let number = schema.number.as_deref();
+    let inclusive = bound(schema, "maximum", number.and_then(|n| n.maximum))
+        .map(|maximum| maximum.unwrap_or_else(|maximum| maximum.floor() as i128));
+    let exclusive = || {
+        bound(
+            schema,
+            "exclusiveMaximum",
+            number.and_then(|n| n.exclusive_maximum),
+        )
+        .map(|maximum| maximum.unwrap_or_else(|maximum| maximum.ceil() as i128) - 1)
+    };
+    inclusive.or_else(exclusive)
+}
+
+/// The bound `keyword` as an integer, or as the float it is.
+fn bound(schema: &SchemaObject, keyword: &str, stored: Option<f64>) -> Option<Result<i128, f64>> {
+    match schema.extensions.get(keyword) {
+        Some(Value::Number(bound)) => match (bound.as_i64(), bound.as_u64()) {
+            (Some(bound), _) => Some(Ok(bound.into())),
+            (_, Some(bound)) => Some(Ok(bound.into())),
+            _ => bound.as_f64().map(Err),
+        },
+        _ => stored.map(Err),
+    }
+}
+
+/// The single type of a schema, ignoring the `null` of an optional.
+fn instance_type(schema: &SchemaObject) -> Option<InstanceType> {
+    match schema.instance_type.as_ref()? {
+        SingleOrVec::Single(ty) => Some(**ty),
+        SingleOrVec::Vec(types) => types
+            .iter()
+            .find(|ty| **ty != InstanceType::Null)
+            .or(types.first())
+            .copied(),
+    }
+}
+
+/// Mixes the method name into the seed with FNV-1a, which unlike the std
+/// hashers is fixed across builds and platforms.
+pub(crate) fn method_seed(seed: u64, name: &str) -> u64 {
+    let hash = name.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    match seed ^ hash {
+        // the xorshift generator never leaves zero
+        0 => DEFAULT_SEED,
+        seed => seed,
+    }
+}
+
+/// A xorshift generator, enough to vary the placeholder values.
+struct Rng(u64);
+
+impl Rng {
+    fn below(&mut self, bound: u64) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        if bound == 0 {
+            0
+        } else {
+            self.0 % bound
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::{schema_for, set_numeric_bound};
+
+    #[test]
+    fn synthesized_values_follow_the_schema() {
+        let mut method = MethodObject::new("transfer".to_string(), None);
+        method.params = vec![
+            ContentDescriptorOrReference::new_content_descriptor::<u8>("amount".to_string(), None),
+            ContentDescriptorOrReference::new_content_descriptor::<Option<bool>>(
+                "confirm".to_string(),
+                None,
+            ),
+        ];
+        method.result = ContentDescriptorOrReference::from_schema(
+            "signatures".to_string(),
+            None,
+            schema_for::<Vec<String>>(),
+        );
+        let mut document = OpenrpcDocument::default();
+        document.add_object_method(method);
+        document.synthesize_examples();
+
+        let examples = serde_json::to_value(&document.methods[0].examples).unwrap();
+        let pairing = &examples[0];
+        assert_eq!(pairing["name"], SYNTHESIZED_EXAMPLE_NAME);
+        assert!(pairing["params"][0]["value"].as_u64().unwrap() <= 255);
+        assert!(pairing["params"][1]["value"].is_boolean());
+        assert!(pairing["result"]["value"][0].is_string());
+    }
+
+    #[test]
+    fn synthesized_strings_match_the_pattern() {
+        let mut method = MethodObject::new("getBalance".to_string(), None);
+        method.params = vec![
+            ContentDescriptorOrReference::new_content_descriptor::<String>(
+                "address".to_string(),
+                None,
+            ),
+        ];
+        let schema = method.param_schema_mut("address").unwrap();
+        schema.string().pattern =
+            Some("^(0x[0-9a-f]{40}|[1-9A-HJ-NP-Za-km-z]{32,44})$".to_string());
+        let mut document = OpenrpcDocument::default();
+        document.add_object_method(method);
+        document.synthesize_examples();
+
+        let examples = serde_json::to_value(&document.methods[0].examples).unwrap();
+        let address = examples[0]["params"][0]["value"].as_str().unwrap();
+        let base58 = |c: char| c.is_ascii_alphanumeric() && !"0OIl".contains(c);
+        let hex = address.strip_prefix("0x").is_some_and(|hex| {
+            hex.len() == 40
+                && hex
+                    .chars()
+                    .all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c))
+        });
+        assert!(
+            hex || ((32..=44).contains(&address.len()) && address.chars().all(base58)),
+            "{}",
+            address
+        );
+    }
+
+    #[test]
+    fn integer_bounds_are_exact() {
+        let mut method = MethodObject::new("transfer".to_string(), None);
+        method.params = vec![ContentDescriptorOrReference::new_content_descriptor::<u64>(
+            "lamports".to_string(),
+            None,
+        )];
+        let schema = method.param_schema_mut("lamports").unwrap();
+        let bound = Number::from(9_007_199_254_740_993u64);
+        set_numeric_bound(schema, "minimum", bound.clone());
+        set_numeric_bound(schema, "maximum", bound.clone());
+        let mut document = OpenrpcDocument::default();
+        document.add_object_method(method);
+        document.synthesize_examples();
+
+        let examples = serde_json::to_value(&document.methods[0].examples).unwrap();
+        assert_eq!(examples[0]["params"][0]["value"], Value::Number(bound));
+    }
+
+    #[test]
+    fn fractional_ranges_give_fractions() {
+        let mut method = MethodObject::new("setRatio".to_string(), None);
+        method.params = vec![
+            ContentDescriptorOrReference::new_content_descriptor::<f64>("ratio".to_string(), None),
+            ContentDescriptorOrReference::new_content_descriptor::<f64>("share".to_string(), None),
+            ContentDescriptorOrReference::new_content_descriptor::<f64>("fee".to_string(), None),
+        ];
+        let ratio = method.param_schema_mut("ratio").unwrap().number();
+        ratio.minimum = Some(0.1);
+        ratio.maximum = Some(0.9);
+        let share = method.param_schema_mut("share").unwrap().number();
+        share.exclusive_minimum = Some(0.0);
+        share.exclusive_maximum = Some(1.0);
+        let fee = method.param_schema_mut("fee").unwrap().number();
+        fee.minimum = Some(1.5);
+        fee.maximum = Some(4.5);
+        let mut document = OpenrpcDocument::default();
+        document.add_object_method(method);
+        document.synthesize_examples();
+
+        let examples = serde_json::to_value(&document.methods[0].examples).unwrap();
+        let value = |index: usize| examples[0]["params"][index]["value"].as_f64().unwrap();
+        assert!((0.1..=0.9).contains(&value(0)), "{}", value(0));
+        assert!(value(1) > 0.0 && value(1) < 1.0, "{}", value(1));
+        assert!([2.0, 3.0, 4.0].contains(&value(2)), "{}", value(2));
+    }
+
+    #[test]
+    fn synthesized_values_are_stable() {
+        let document =
+            |names: &[&str], seed| {
+                let mut document = OpenrpcDocument::default();
+                for name in names {
+                    let mut method = MethodObject::new(name.to_string(), None);
+                    method.params = vec![ContentDescriptorOrReference::new_content_descriptor::<
+                        String,
+                    >("value".to_string(), None)];
+                    document.add_object_method(method);
+                }
+                document.synthesize_examples_with_seed(seed);
+                let mut examples = document
+                    .methods
+                    .iter()
+                    .map(|m| (m.name.clone(), serde_json::to_value(&m.examples).unwrap()))
+                    .collect::<Vec<_>>();
+                examples.sort_by(|a, b| a.0.cmp(&b.0));
+                examples
+            };
+        let before = document(&["a", "b", "c", "d"], 7);
+        assert_eq!(before, document(&["d", "c", "b", "a"], 7));
+        let mut after = document(&["a", "b", "c", "d", "e"], 7);
+        after.pop();
+        assert_eq!(before, after);
+    }
+}
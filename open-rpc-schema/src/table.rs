@@ -0,0 +1,291 @@
+//! Compact metadata tables emitted by `#[document_rpc(compact)]`.
+//!
+//! Instead of expanding the construction of every `MethodObject` inline, the
+//! macro emits one static table per trait and the shared [`build_document`]
+//! turns it into an [`OpenrpcDocument`] at runtime.
+use crate::document::{ContentDescriptorOrReference, JSONSchema, MethodObject, OpenrpcDocument};
+
+/// A param or result of a documented method.
+pub struct ContentEntry {
+    pub name: &'static str,
+    pub schema: fn() -> JSONSchema,
+}
+
+/// A documented method.
+pub struct MethodEntry {
+    pub name: &'static str,
+    pub summary: &'static str,
+    pub description: Option<&'static str>,
+    pub params: &'static [ContentEntry],
+    pub result: ContentEntry,
+    /// Applies everything else the method attributes document.
+    pub extend: Option<fn(&mut MethodObject)>,
+}
+
+impl ContentEntry {
+    pub fn to_content_descriptor(&self) -> ContentDescriptorOrReference {
+        ContentDescriptorOrReference::from_schema(self.name.to_string(), None, (self.schema)())
+    }
+}
+
+impl MethodEntry {
+    pub fn to_method_object(&self) -> MethodObject {
+        let mut method_object =
+            MethodObject::new(self.name.to_string(), self.description.map(str::to_string));
+        method_object.result = self.result.to_content_descriptor();
+        method_object.params = self
+            .params
+            .iter()
+            .map(ContentEntry::to_content_descriptor)
+            .collect();
+        method_object.summary = Some(self.summary.to_string());
+        if let Some(extend) = self.extend {
+            extend(&mut method_object);
+        }
+        method_object
+    }
+}
+
+pub fn build_document(methods: &[MethodEntry]) -> OpenrpcDocument {
+    let mut document = OpenrpcDocument::default();
+    for method in methods {
+        document.add_object_method(method.to_method_object());
+    }
+    document
+}
+
+/// Types whose schema the macro knows without looking at a `JsonSchema` impl.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Primitive {
+    Unit,
+    Bool,
+    Char,
+    String,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    Usize,
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+    Isize,
+    F32,
+    F64,
+}
+
+impl Primitive {
+    pub fn schema(self) -> JSONSchema {
+        use crate::document::schema_for;
+        match self {
+            Primitive::Unit => schema_for::<()>(),
+            Primitive::Bool => schema_for::<bool>(),
+            Primitive::Char => schema_for::<char>(),
+            Primitive::String => schema_for::<String>(),
+            Primitive::U8 => schema_for::<u8>(),
+            Primitive::U16 => schema_for::<u16>(),
+            Primitive::U32 => schema_for::<u32>(),
+            Primitive::U64 => schema_for::<u64>(),
+            Primitive::U128 => schema_for::<u128>(),
+            Primitive::Usize => schema_for::<usize>(),
+            Primitive::I8 => schema_for::<i8>(),
+            Primitive::I16 => schema_for::<i16>(),
+            Primitive::I32 => schema_for::<i32>(),
+            Primitive::I64 => schema_for::<i64>(),
+            Primitive::I128 => schema_for::<i128>(),
+            Primitive::Isize => schema_for::<isize>(),
+            Primitive::F32 => schema_for::<f32>(),
+            Primitive::F64 => schema_for::<f64>(),
+        }
+    }
+}
+
+/// A param or result of a method that only deals in primitives.
+#[derive(Clone, Copy, Debug)]
+pub struct ConstContent {
+    pub name: &'static str,
+    pub ty: Primitive,
+}
+
+/// A documented method whose params and result are all primitives. The
+/// generated `CONST_METHODS` table of these is usable in const contexts.
+#[derive(Clone, Copy, Debug)]
+pub struct ConstMethod {
+    pub name: &'static str,
+    pub summary: &'static str,
+    pub description: Option<&'static str>,
+    pub params: &'static [ConstContent],
+    pub result: ConstContent,
+}
+
+impl ConstContent {
+    pub fn to_content_descriptor(&self) -> ContentDescriptorOrReference {
+        ContentDescriptorOrReference::from_schema(self.name.to_string(), None, self.ty.schema())
+    }
+}
+
+impl ConstMethod {
+    pub fn to_method_object(&self) -> MethodObject {
+        let mut method_object =
+            MethodObject::new(self.name.to_string(), self.description.map(str::to_string));
+        method_object.result = self.result.to_content_descriptor();
+        method_object.params = self
+            .params
+            .iter()
+            .map(ConstContent::to_content_descriptor)
+            .collect();
+        method_object.summary = Some(self.summary.to_string());
+        method_object
+    }
+}
+
+/// Looks a method up by name, in const contexts too.
+pub const fn find_const_method<'a>(
+    methods: &'a [ConstMethod],
+    name: &str,
+) -> Option<&'a ConstMethod> {
+    let mut i = 0;
+    while i < methods.len() {
+        if str_eq(methods[i].name, name) {
+            return Some(&methods[i]);
+        }
+        i += 1;
+    }
+    None
+}
+
+/// A param or result of a [`StaticMethod`].
+#[derive(Clone, Copy, Debug)]
+pub struct StaticContent {
+    pub name: &'static str,
+    /// The type as the trait spells it.
+    pub rust_type: &'static str,
+    pub required: bool,
+    pub primitive: Option<Primitive>,
+}
+
+/// A documented method as plain static data. The generated `STATIC_METHODS`
+/// table of these lists every method, so servers answer introspection
+/// queries without building the document.
+#[derive(Clone, Copy, Debug)]
+pub struct StaticMethod {
+    pub name: &'static str,
+    pub summary: &'static str,
+    pub description: Option<&'static str>,
+    pub tags: &'static [&'static str],
+    /// The params as the trait declares them.
+    pub params: &'static [StaticContent],
+    pub result: StaticContent,
+}
+
+impl StaticMethod {
+    pub const fn param(&self, name: &str) -> Option<&StaticContent> {
+        let mut i = 0;
+        while i < self.params.len() {
+            if str_eq(self.params[i].name, name) {
+                return Some(&self.params[i]);
+            }
+            i += 1;
+        }
+        None
+    }
+}
+
+/// Looks a method up by name, in const contexts too.
+pub const fn find_static_method<'a>(
+    methods: &'a [StaticMethod],
+    name: &str,
+) -> Option<&'a StaticMethod> {
+    let mut i = 0;
+    while i < methods.len() {
+        if str_eq(methods[i].name, name) {
+            return Some(&methods[i]);
+        }
+        i += 1;
+    }
+    None
+}
+
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const METHODS: &[ConstMethod] = &[ConstMethod {
+        name: "getSlot",
+        summary: "",
+        description: None,
+        params: &[ConstContent {
+            name: "commitment",
+            ty: Primitive::String,
+        }],
+        result: ConstContent {
+            name: "u64",
+            ty: Primitive::U64,
+        },
+    }];
+    const GET_SLOT: Option<&ConstMethod> = find_const_method(METHODS, "getSlot");
+
+    #[test]
+    fn test_const_method_lookup() {
+        assert_eq!(GET_SLOT.map(|m| m.params.len()), Some(1));
+        assert!(find_const_method(METHODS, "getSlots").is_none());
+        let method = GET_SLOT.unwrap().to_method_object();
+        assert_eq!(method.params.len(), 1);
+    }
+
+    const STATIC_METHODS: &[StaticMethod] = &[StaticMethod {
+        name: "getBlock",
+        summary: "",
+        description: None,
+        tags: &["blocks"],
+        params: &[
+            StaticContent {
+                name: "slot",
+                rust_type: "u64",
+                required: true,
+                primitive: Some(Primitive::U64),
+            },
+            StaticContent {
+                name: "config",
+                rust_type: "Option < BlockConfig >",
+                required: false,
+                primitive: None,
+            },
+        ],
+        result: StaticContent {
+            name: "Block",
+            rust_type: "Block",
+            required: true,
+            primitive: None,
+        },
+    }];
+
+    #[test]
+    fn test_static_method_lookup() {
+        const GET_BLOCK: Option<&StaticMethod> = find_static_method(STATIC_METHODS, "getBlock");
+        const CONFIG: Option<&StaticContent> = match GET_BLOCK {
+            Some(method) => method.param("config"),
+            None => None,
+        };
+        assert!(!CONFIG.unwrap().required);
+        assert!(find_static_method(STATIC_METHODS, "getBlocks").is_none());
+    }
+}
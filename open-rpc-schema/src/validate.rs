@@ -0,0 +1,661 @@
+//! Validation of request params against the documented schemas.
+//!
+//! A server checks incoming params before deserializing them and answers a
+//! violation with an `Invalid params` error whose `data` tells the client
+//! what went wrong:
+//!
+//! ```json
+//! {
+//!   "code": -32602,
+//!   "message": "Invalid params",
+//!   "data": {
+//!     "pointer": "/methods/2/params/0/schema",
+//!     "path": "/0",
+//!     "expected": { "type": "integer", "format": "uint64", "minimum": 0.0 },
+//!     "received": "42",
+//!     "message": "expected integer"
+//!   }
+//! }
+//! ```
+//!
+//! `pointer` locates the violated schema in the document served by
+//! `rpc.discover`, `path` the rejected value in the params of the request.
+//!
+//! The common keywords of the schemas `schemars` generates are checked:
+//! `type`, `enum`, `const`, the numeric and length bounds, `required`,
+//! `properties`, `additionalProperties`, `items` and the `allOf`, `anyOf` and
+//! `oneOf` combinators. String lengths count characters, while the
+//! `x-max-bytes` limit of `#[rpc(max_bytes(..))]` counts the UTF-8 bytes.
+//! `pattern` and `format` are not checked. Params passed by name
+//! that the method does not declare are only rejected for methods marked
+//! `x-deny-unknown-params`.
+use crate::document::{ErrorObject, OpenrpcDocument};
+use crate::params::DENY_UNKNOWN_PARAMS_EXTENSION;
+use serde_json::{json, Map, Value};
+use std::fmt;
+
+/// The JSON-RPC error code of a request whose params do not validate.
+pub const INVALID_PARAMS: i64 = -32602;
+pub(crate) const MAX_DEPTH: usize = 32;
+const MAX_BYTES_EXTENSION: &str = "x-max-bytes";
+
+/// Why the params of a request were rejected, boxed to keep the `Result`s
+/// of the validation small. Derefs to its [`Violation`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValidationError(Box<Violation>);
+
+/// What a rejected value violates.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Violation {
+    /// The JSON Pointer to the violated schema in the document.
+    pub pointer: String,
+    /// The JSON Pointer to the rejected value in the params.
+    pub path: String,
+    /// The violated schema.
+    pub expected: Value,
+    /// The rejected value, `null` when a required value is missing.
+    pub received: Value,
+    pub message: String,
+}
+
+impl From<Violation> for ValidationError {
+    fn from(violation: Violation) -> Self {
+        ValidationError(Box::new(violation))
+    }
+}
+
+impl std::ops::Deref for ValidationError {
+    type Target = Violation;
+
+    fn deref(&self) -> &Violation {
+        &self.0
+    }
+}
+
+impl ValidationError {
+    /// The `Invalid params` error answering the request.
+    pub fn to_error_object(&self) -> ErrorObject {
+        ErrorObject {
+            code: INVALID_PARAMS,
+            message: "Invalid params".to_string(),
+            data: Some(json!({
+                "pointer": self.pointer,
+                "path": self.path,
+                "expected": self.expected,
+                "received": self.received,
+                "message": self.message,
+            })),
+        }
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid params at `{}`: {}", self.path, self.message)
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// Validates params against the serialized document, which is kept so the
+/// pointers of the errors point into exactly what clients discover.
+#[derive(Clone, Debug)]
+pub struct Validator {
+    pub(crate) document: Value,
+}
+
+impl Validator {
+    pub fn new(document: &OpenrpcDocument) -> Self {
+        Validator {
+            document: serde_json::to_value(document).unwrap_or(Value::Null),
+        }
+    }
+
+    /// Checks the params of a call to `method`.
+    ///
+    /// Methods the document does not declare are left to the server to
+    /// reject.
+    pub fn validate_params(&self, method: &str, params: &Value) -> Result<(), ValidationError> {
+        let index = match self.method_index(method) {
+            Some(index) => index,
+            None => return Ok(()),
+        };
+        let method = &self.document["methods"][index];
+        let pointer = format!("/methods/{}", index);
+        let structure = method["paramStructure"].as_str();
+        let descriptors = method["params"].as_array().map_or(&[][..], Vec::as_slice);
+        let no_params = Value::Array(Vec::new());
+        let params = if params.is_null() { &no_params } else { params };
+        let error = |message: &str| {
+            ValidationError::from(Violation {
+                pointer: format!("{}/params", pointer),
+                path: String::new(),
+                expected: method["params"].clone(),
+                received: params.clone(),
+                message: message.to_string(),
+            })
+        };
+        let by_name = match params {
+            Value::Array(_) if structure == Some("by-name") => {
+                return Err(error("params must be passed by name"))
+            }
+            Value::Object(_) if structure == Some("by-position") => {
+                return Err(error("params must be passed by position"))
+            }
+            Value::Array(values) if values.len() > descriptors.len() => {
+                return Err(error(&format!(
+                    "expected at most {} params",
+                    descriptors.len()
+                )))
+            }
+            Value::Array(_) => false,
+            Value::Object(_) => true,
+            _ => return Err(error("params must be an array or an object")),
+        };
+        let descriptors: Vec<_> = descriptors
+            .iter()
+            .enumerate()
+            .map(|(position, descriptor)| match descriptor["$ref"].as_str() {
+                Some(reference) => reference.trim_start_matches('#').to_string(),
+                None => format!("{}/params/{}", pointer, position),
+            })
+            .collect();
+        if let (Value::Object(params), true) =
+            (params, method[DENY_UNKNOWN_PARAMS_EXTENSION] == true)
+        {
+            let declared = |name: &String| {
+                descriptors
+                    .iter()
+                    .any(|descriptor| self.resolve(descriptor)["name"] == name.as_str())
+            };
+            if let Some(name) = params.keys().find(|name| !declared(name)) {
+                return Err(error(&format!("unknown param `{}`", name)));
+            }
+        }
+        for (position, descriptor_pointer) in descriptors.iter().enumerate() {
+            let descriptor = self.resolve(descriptor_pointer);
+            let name = descriptor["name"].as_str().unwrap_or_default();
+            let (value, path) = if by_name {
+                (params.get(name), format!("/{}", escape(name)))
+            } else {
+                (params.get(position), format!("/{}", position))
+            };
+            let schema_pointer = format!("{}/schema", descriptor_pointer);
+            match value {
+                Some(value) => self.check(&schema_pointer, &schema_pointer, &path, value, 0)?,
+                None if descriptor["required"] == true => {
+                    return Err(Violation {
+                        expected: self.resolve(&schema_pointer).clone(),
+                        pointer: schema_pointer,
+                        path,
+                        received: Value::Null,
+                        message: format!("missing required param `{}`", name),
+                    }
+                    .into())
+                }
+                None => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks the result of a call to `method`, e.g. in contract tests of
+    /// the server.
+    pub fn validate_result(&self, method: &str, result: &Value) -> Result<(), ValidationError> {
+        let index = match self.method_index(method) {
+            Some(index) => index,
+            None => return Ok(()),
+        };
+        let mut pointer = format!("/methods/{}/result", index);
+        if let Some(reference) = self.resolve(&pointer)["$ref"].as_str() {
+            pointer = reference.trim_start_matches('#').to_string();
+        }
+        pointer.push_str("/schema");
+        self.check(&pointer, &pointer, "", result, 0)
+    }
+
+    /// Whether the result schema of `method` declares the property `field`,
+    /// itself or through one of the schemas it refers to or combines.
+    pub fn result_declares(&self, method: &str, field: &str) -> bool {
+        let index = match self.method_index(method) {
+            Some(index) => index,
+            None => return false,
+        };
+        let mut pointer = format!("/methods/{}/result", index);
+        if let Some(reference) = self.resolve(&pointer)["$ref"].as_str() {
+            pointer = reference.trim_start_matches('#').to_string();
+        }
+        pointer.push_str("/schema");
+        self.declares(&pointer, &pointer, field, 0)
+    }
+
+    fn declares(&self, root: &str, pointer: &str, field: &str, depth: usize) -> bool {
+        let schema = match self.resolve(pointer) {
+            Value::Object(schema) if depth <= MAX_DEPTH => schema,
+            _ => return false,
+        };
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            let reference = reference.trim_start_matches('#');
+            if reference.starts_with("/definitions/") {
+                let target = format!("{}{}", root, reference);
+                return self.declares(root, &target, field, depth + 1);
+            }
+            return self.declares(reference, reference, field, depth + 1);
+        }
+        let properties = schema.get("properties").and_then(Value::as_object);
+        if properties.is_some_and(|properties| properties.contains_key(field)) {
+            return true;
+        }
+        ["allOf", "anyOf", "oneOf"].iter().any(|key| {
+            let count = schema
+                .get(*key)
+                .and_then(Value::as_array)
+                .map_or(0, Vec::len);
+            (0..count).any(|index| {
+                let subschema = format!("{}/{}/{}", pointer, key, index);
+                self.declares(root, &subschema, field, depth + 1)
+            })
+        })
+    }
+
+    pub(crate) fn method_index(&self, method: &str) -> Option<usize> {
+        let methods = self.document["methods"].as_array().into_iter().flatten();
+        methods.into_iter().position(|m| m["name"] == method)
+    }
+
+    pub(crate) fn resolve(&self, pointer: &str) -> &Value {
+        self.document.pointer(pointer).unwrap_or(&Value::Bool(true))
+    }
+
+    /// Checks `value` at `path` against the schema at `pointer`, resolving
+    /// `#/definitions/..` references against the schema at `root`.
+    pub(crate) fn check(
+        &self,
+        root: &str,
+        pointer: &str,
+        path: &str,
+        value: &Value,
+        depth: usize,
+    ) -> Result<(), ValidationError> {
+        let schema = self.resolve(pointer);
+        let fail = |message: String| {
+            ValidationError::from(Violation {
+                pointer: pointer.to_string(),
+                path: path.to_string(),
+                expected: schema.clone(),
+                received: value.clone(),
+                message,
+            })
+        };
+        let schema = match schema {
+            Value::Bool(true) => return Ok(()),
+            Value::Object(schema) => schema,
+            _ => return Err(fail("no value is valid here".to_string())),
+        };
+        if depth > MAX_DEPTH {
+            return Ok(());
+        }
+
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            let reference = reference.trim_start_matches('#');
+            if reference.starts_with("/definitions/") {
+                let target = format!("{}{}", root, reference);
+                return self.check(root, &target, path, value, depth + 1);
+            }
+            if reference.starts_with("/components/") {
+                return self.check(reference, reference, path, value, depth + 1);
+            }
+            // references out of the document are not resolved
+            return Ok(());
+        }
+
+        for (keyword, (min, max)) in [
+            ("allOf", (None, None)),
+            ("anyOf", (Some(1), None)),
+            ("oneOf", (Some(1), Some(1))),
+        ] {
+            let subschemas = match schema.get(keyword).and_then(Value::as_array) {
+                Some(subschemas) => subschemas,
+                None => continue,
+            };
+            let mut matched = 0;
+            for index in 0..subschemas.len() {
+                let subschema = format!("{}/{}/{}", pointer, keyword, index);
+                match self.check(root, &subschema, path, value, depth + 1) {
+                    Ok(()) => matched += 1,
+                    // every subschema of `allOf` must hold
+                    Err(err) if min.is_none() => return Err(err),
+                    Err(_) => {}
+                }
+            }
+            let too_few = min.is_some_and(|min| matched < min);
+            let too_many = max.is_some_and(|max| matched > max);
+            if too_few || too_many {
+                return Err(fail(format!(
+                    "does not match exactly the schemas `{}` requires",
+                    keyword
+                )));
+            }
+        }
+
+        if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+            if !values.contains(value) {
+                return Err(fail("not one of the allowed values".to_string()));
+            }
+        }
+        if let Some(constant) = schema.get("const") {
+            if constant != value {
+                return Err(fail(format!("expected {}", constant)));
+            }
+        }
+        if let Some(types) = schema.get("type") {
+            let types: Vec<&str> = match types {
+                Value::String(ty) => vec![ty.as_str()],
+                Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+                _ => vec![],
+            };
+            if !types.is_empty() && !types.iter().any(|ty| is_instance_of(value, ty)) {
+                return Err(fail(format!("expected {}", types.join(" or "))));
+            }
+        }
+
+        match value {
+            Value::Number(number) => {
+                let number = number.as_f64().unwrap_or_default();
+                let bound = |keyword| schema.get(keyword).and_then(Value::as_f64);
+                if bound("minimum").is_some_and(|minimum| number < minimum)
+                    || bound("exclusiveMinimum").is_some_and(|minimum| number <= minimum)
+                {
+                    return Err(fail("below the minimum".to_string()));
+                }
+                if bound("maximum").is_some_and(|maximum| number > maximum)
+                    || bound("exclusiveMaximum").is_some_and(|maximum| number >= maximum)
+                {
+                    return Err(fail("above the maximum".to_string()));
+                }
+            }
+            Value::String(string) => {
+                let length = string.chars().count() as u64;
+                if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+                    if length < min {
+                        return Err(fail(format!("shorter than {} characters", min)));
+                    }
+                }
+                if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+                    if length > max {
+                        return Err(fail(format!("longer than {} characters", max)));
+                    }
+                }
+                if let Some(max) = schema.get(MAX_BYTES_EXTENSION).and_then(Value::as_u64) {
+                    if string.len() as u64 > max {
+                        return Err(fail(format!("longer than {} bytes", max)));
+                    }
+                }
+            }
+            Value::Array(items) => {
+                let length = items.len() as u64;
+                if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+                    if length < min {
+                        return Err(fail(format!("fewer than {} items", min)));
+                    }
+                }
+                if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+                    if length > max {
+                        return Err(fail(format!("more than {} items", max)));
+                    }
+                }
+                for (index, item) in items.iter().enumerate() {
+                    let item_schema = match schema.get("items") {
+                        Some(Value::Array(tuple)) if index < tuple.len() => {
+                            format!("{}/items/{}", pointer, index)
+                        }
+                        Some(Value::Array(_)) => match schema.get("additionalItems") {
+                            Some(_) => format!("{}/additionalItems", pointer),
+                            None => continue,
+                        },
+                        Some(_) => format!("{}/items", pointer),
+                        None => continue,
+                    };
+                    let item_path = format!("{}/{}", path, index);
+                    self.check(root, &item_schema, &item_path, item, depth + 1)?;
+                }
+            }
+            Value::Object(object) => {
+                self.check_object(root, pointer, path, schema, object, depth, &fail)?
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn check_object(
+        &self,
+        root: &str,
+        pointer: &str,
+        path: &str,
+        schema: &Map<String, Value>,
+        object: &Map<String, Value>,
+        depth: usize,
+        fail: &dyn Fn(String) -> ValidationError,
+    ) -> Result<(), ValidationError> {
+        for required in schema
+            .get("required")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            let required = required.as_str().unwrap_or_default();
+            if !object.contains_key(required) {
+                return Err(fail(format!("missing required property `{}`", required)));
+            }
+        }
+        let properties = schema.get("properties").and_then(Value::as_object);
+        for (name, property) in object {
+            let property_path = format!("{}/{}", path, escape(name));
+            let property_schema =
+                if properties.is_some_and(|properties| properties.contains_key(name)) {
+                    format!("{}/properties/{}", pointer, escape(name))
+                } else {
+                    match schema.get("additionalProperties") {
+                        Some(Value::Bool(false)) => {
+                            return Err(fail(format!("unknown property `{}`", name)))
+                        }
+                        Some(_) => format!("{}/additionalProperties", pointer),
+                        None => continue,
+                    }
+                };
+            self.check(root, &property_schema, &property_path, property, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl OpenrpcDocument {
+    /// Checks the params of a call to `method` against the document.
+    ///
+    /// Servers validating every request keep a [`Validator`] instead of
+    /// serializing the document on each call.
+    pub fn validate_params(&self, method: &str, params: &Value) -> Result<(), ValidationError> {
+        Validator::new(self).validate_params(method, params)
+    }
+
+    /// The methods whose `x-pagination` names a `resultField` their result
+    /// schema does not declare, with that field.
+    pub fn undeclared_result_fields(&self) -> Vec<(String, String)> {
+        let validator = Validator::new(self);
+        self.methods
+            .iter()
+            .filter_map(|method| {
+                let field = method.extensions.get("x-pagination")?["resultField"].as_str()?;
+                let declared = validator.result_declares(&method.name, field);
+                (!declared).then(|| (method.name.clone(), field.to_string()))
+            })
+            .collect()
+    }
+}
+
+fn is_instance_of(value: &Value, ty: &str) -> bool {
+    match ty {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "string" => value.is_string(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        "number" => value.is_number(),
+        "integer" => {
+            value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|n| n.fract() == 0.0)
+        }
+        _ => true,
+    }
+}
+
+/// Escapes `~` and `/` in a JSON Pointer reference token.
+pub(crate) fn escape(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::{ContentDescriptorOrReference, MethodObject};
+    use schemars::JsonSchema;
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Config {
+        commitment: Option<String>,
+        limit: u8,
+    }
+
+    fn document() -> OpenrpcDocument {
+        let mut method = MethodObject::new("getBlocks".to_string(), None);
+        method.params = vec![
+            ContentDescriptorOrReference::new_content_descriptor::<u64>("slot".to_string(), None),
+            ContentDescriptorOrReference::new_content_descriptor::<Config>(
+                "config".to_string(),
+                None,
+            ),
+        ];
+        method.mark_required(&[true, false]);
+        let mut document = OpenrpcDocument::default();
+        document.add_object_method(method);
+        document
+    }
+
+    #[test]
+    fn result_fields_are_looked_up_in_the_result_schema() {
+        let mut method = MethodObject::new("getConfigs".to_string(), None);
+        method.result = ContentDescriptorOrReference::new_content_descriptor::<Option<Vec<Config>>>(
+            "configs".to_string(),
+            None,
+        );
+        let mut page = MethodObject::new("getPage".to_string(), None);
+        page.result = ContentDescriptorOrReference::new_content_descriptor::<Option<Config>>(
+            "page".to_string(),
+            None,
+        );
+        page.extensions.insert(
+            "x-pagination".to_string(),
+            serde_json::json!({ "resultField": "limt" }),
+        );
+        let mut document = OpenrpcDocument::default();
+        document.add_object_method(method);
+        document.add_object_method(page);
+        let validator = Validator::new(&document);
+        assert!(validator.result_declares("getPage", "limit"));
+        assert!(!validator.result_declares("getPage", "limt"));
+        assert!(!validator.result_declares("getConfigs", "limit"));
+        assert_eq!(
+            document.undeclared_result_fields(),
+            [("getPage".to_string(), "limt".to_string())]
+        );
+    }
+
+    #[test]
+    fn valid_params_pass() {
+        let validator = Validator::new(&document());
+        for params in [
+            json!([1]),
+            json!([1, { "limit": 10 }]),
+            json!({ "slot": 1, "config": { "commitment": null, "limit": 10 } }),
+        ] {
+            assert_eq!(validator.validate_params("getBlocks", &params), Ok(()));
+        }
+        assert_eq!(validator.validate_params("unknown", &json!(1)), Ok(()));
+        assert_eq!(
+            validator.validate_params("getBlocks", &json!({ "slot": 1, "slto": 2 })),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn unknown_params_are_rejected_when_denied() {
+        let mut document = document();
+        document.methods[0].deny_unknown_params();
+        let validator = Validator::new(&document);
+        let err = validator
+            .validate_params("getBlocks", &json!({ "slot": 1, "slto": 2 }))
+            .unwrap_err();
+        assert_eq!(err.message, "unknown param `slto`");
+        assert_eq!(err.pointer, "/methods/0/params");
+    }
+
+    #[test]
+    fn errors_point_at_the_violated_schema() {
+        let validator = Validator::new(&document());
+
+        let err = validator
+            .validate_params("getBlocks", &json!(["1"]))
+            .unwrap_err();
+        assert_eq!(err.pointer, "/methods/0/params/0/schema");
+        assert_eq!(err.path, "/0");
+        assert_eq!(err.received, json!("1"));
+        assert_eq!(err.expected["type"], "integer");
+
+        let err = validator
+            .validate_params("getBlocks", &json!([1, { "limit": -1 }]))
+            .unwrap_err();
+        assert_eq!(err.pointer, "/methods/0/params/1/schema/properties/limit");
+        assert_eq!(err.path, "/1/limit");
+        assert_eq!(err.message, "below the minimum");
+
+        let err = validator
+            .validate_params("getBlocks", &json!({ "config": { "limit": 1 } }))
+            .unwrap_err();
+        assert_eq!(err.message, "missing required param `slot`");
+
+        let error = err.to_error_object();
+        assert_eq!(error.code, INVALID_PARAMS);
+        assert_eq!(error.data.unwrap()["pointer"], "/methods/0/params/0/schema");
+    }
+
+    #[test]
+    fn lengths_count_characters_and_limits_bytes() {
+        let mut method = MethodObject::new("send".to_string(), None);
+        method.params = vec![
+            ContentDescriptorOrReference::new_content_descriptor::<String>(
+                "data".to_string(),
+                None,
+            ),
+        ];
+        let schema = method.param_schema_mut("data").unwrap();
+        schema.string().max_length = Some(4);
+        schema
+            .extensions
+            .insert(MAX_BYTES_EXTENSION.to_string(), json!(4));
+        let mut document = OpenrpcDocument::default();
+        document.add_object_method(method);
+        let validator = Validator::new(&document);
+
+        assert_eq!(validator.validate_params("send", &json!(["éé"])), Ok(()));
+        let err = validator
+            .validate_params("send", &json!(["ééé"]))
+            .unwrap_err();
+        assert_eq!(err.message, "longer than 4 bytes");
+        let err = validator
+            .validate_params("send", &json!(["abcde"]))
+            .unwrap_err();
+        assert_eq!(err.message, "longer than 4 characters");
+    }
+}
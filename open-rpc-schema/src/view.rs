@@ -0,0 +1,274 @@
+//! Subsets of a document for one audience.
+//!
+//! Methods carry tags like `public` or `internal`, and a document generated
+//! from one annotated trait can be narrowed to the methods of a tag group
+//! before it is published. Methods declaring roles are only part of the
+//! [`DocumentView`] of those roles.
+use crate::document::{MethodObject, OpenrpcDocument, TagObject, TagOrReference};
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// The extension listing the roles allowed to call a method.
+pub const ROLES_EXTENSION: &str = "x-roles";
+const COMPONENTS_REF_PREFIX: &str = "#/components/";
+
+impl MethodObject {
+    pub fn add_tag(&mut self, name: &str) {
+        self.tags
+            .get_or_insert_with(Vec::new)
+            .push(TagOrReference::TagObject(TagObject {
+                name: name.to_string(),
+                description: None,
+                external_docs: None,
+            }));
+    }
+
+    /// The names of the inline tags of the method.
+    pub fn tag_names(&self) -> impl Iterator<Item = &str> {
+        self.tags.iter().flatten().filter_map(|tag| match tag {
+            TagOrReference::TagObject(tag) => Some(tag.name.as_str()),
+            TagOrReference::ReferenceObject(_) => None,
+        })
+    }
+
+    pub fn has_any_tag(&self, tags: &[&str]) -> bool {
+        self.tag_names().any(|name| tags.contains(&name))
+    }
+
+    /// The roles allowed to call the method, empty when anyone may.
+    pub fn roles(&self) -> impl Iterator<Item = &str> {
+        self.extensions
+            .get(ROLES_EXTENSION)
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+    }
+}
+
+/// Which methods of a document one audience sees.
+#[derive(Clone, Debug, Default)]
+pub struct DocumentView {
+    role: Option<String>,
+    tags: Option<Vec<String>>,
+}
+
+impl DocumentView {
+    /// The methods `role` may call: those allowed to it and those declaring
+    /// no roles at all.
+    pub fn for_role(role: &str) -> Self {
+        DocumentView {
+            role: Some(role.to_string()),
+            tags: None,
+        }
+    }
+
+    /// Further narrows the view to the methods tagged with one of `tags`.
+    pub fn with_tags(mut self, tags: &[&str]) -> Self {
+        self.tags = Some(tags.iter().map(|tag| tag.to_string()).collect());
+        self
+    }
+
+    pub fn includes(&self, method: &MethodObject) -> bool {
+        let role_allowed = match &self.role {
+            Some(role) => {
+                let mut roles = method.roles().peekable();
+                roles.peek().is_none() || roles.any(|r| r == role)
+            }
+            None => true,
+        };
+        let tagged = match &self.tags {
+            Some(tags) => method
+                .tag_names()
+                .any(|name| tags.iter().any(|t| t == name)),
+            None => true,
+        };
+        role_allowed && tagged
+    }
+
+    /// A copy of `document` with only the methods of the view, and only the
+    /// components those methods still refer to.
+    pub fn apply(&self, document: &OpenrpcDocument) -> OpenrpcDocument {
+        let mut document = document.clone();
+        document.methods.retain(|method| self.includes(method));
+        document.prune_components();
+        document
+    }
+}
+
+impl OpenrpcDocument {
+    /// A copy of the document with only the methods tagged with one of `tags`,
+    /// and only the components those methods still refer to.
+    pub fn filter_tags(&self, tags: &[&str]) -> OpenrpcDocument {
+        let mut document = self.clone();
+        document.methods.retain(|method| method.has_any_tag(tags));
+        document.prune_components();
+        document
+    }
+
+    /// Removes the schemas, content descriptors and errors of the components
+    /// no method refers to, directly or through another component.
+    pub fn prune_components(&mut self) {
+        let components = match &mut self.components {
+            Some(components) => components,
+            None => return,
+        };
+        let mut referenced = HashSet::new();
+        let mut pending: Vec<String> = Vec::new();
+        for method in &self.methods {
+            if let Ok(method) = serde_json::to_value(method) {
+                collect_refs(&method, &mut pending);
+            }
+        }
+        while let Some(reference) = pending.pop() {
+            if !referenced.insert(reference.clone()) {
+                continue;
+            }
+            let component = reference
+                .strip_prefix(COMPONENTS_REF_PREFIX)
+                .and_then(|path| path.split_once('/'))
+                .and_then(|(kind, name)| {
+                    let map = match kind {
+                        "schemas" => components.schemas.as_ref(),
+                        "contentDescriptors" => components.content_descriptors.as_ref(),
+                        "errors" => components.errors.as_ref(),
+                        _ => None,
+                    };
+                    map?.get(name)?.as_ref()
+                });
+            if let Some(component) = component {
+                collect_refs(component, &mut pending);
+            }
+        }
+        let retain = |kind: &str, map: &mut Option<crate::document::SchemaComponents>| {
+            if let Some(map) = map {
+                map.retain(|name, _| {
+                    referenced.contains(&format!("{}{}/{}", COMPONENTS_REF_PREFIX, kind, name))
+                });
+            }
+        };
+        retain("schemas", &mut components.schemas);
+        retain("contentDescriptors", &mut components.content_descriptors);
+        retain("errors", &mut components.errors);
+    }
+}
+
+fn collect_refs(value: &Value, refs: &mut Vec<String>) {
+    match value {
+        Value::Object(object) => {
+            for (key, value) in object {
+                match value {
+                    Value::String(reference) if key == "$ref" => refs.push(reference.clone()),
+                    value => collect_refs(value, refs),
+                }
+            }
+        }
+        Value::Array(values) => values.iter().for_each(|value| collect_refs(value, refs)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filter_keeps_methods_with_any_tag() {
+        let mut document = OpenrpcDocument::default();
+        for (name, tags) in [
+            ("getBalance", &["public"][..]),
+            ("getPeers", &["internal"]),
+            ("ping", &[]),
+        ] {
+            let mut method = MethodObject::new(name.to_string(), None);
+            for tag in tags {
+                method.add_tag(tag);
+            }
+            document.add_object_method(method);
+        }
+        let names = |document: &OpenrpcDocument| {
+            document
+                .methods
+                .iter()
+                .map(|m| m.name.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(&document.filter_tags(&["public"])), ["getBalance"]);
+        assert_eq!(
+            names(&document.filter_tags(&["public", "internal"])),
+            ["getBalance", "getPeers"]
+        );
+    }
+
+    #[test]
+    fn role_view_prunes_unused_components() {
+        use crate::document::{Components, ContentDescriptorOrReference, ReferenceObject};
+        use serde_json::json;
+
+        let mut document = OpenrpcDocument::default();
+        for (name, roles, schema) in [
+            ("getBalance", json!([]), "Balance"),
+            ("setLimits", json!(["admin"]), "Limits"),
+        ] {
+            let mut method = MethodObject::new(name.to_string(), None);
+            method.extensions.insert(ROLES_EXTENSION.to_string(), roles);
+            method.params = vec![ContentDescriptorOrReference::ReferenceObject(
+                ReferenceObject {
+                    reference: format!("#/components/contentDescriptors/{}", schema),
+                },
+            )];
+            document.add_object_method(method);
+        }
+        let content_descriptors = Some(
+            [
+                (
+                    "Balance",
+                    json!({ "schema": { "$ref": "#/components/schemas/Amount" } }),
+                ),
+                (
+                    "Limits",
+                    json!({ "schema": { "$ref": "#/components/schemas/Limits" } }),
+                ),
+            ]
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), Some(value)))
+            .collect(),
+        );
+        let schemas = Some(
+            ["Amount", "Limits"]
+                .into_iter()
+                .map(|name| (name.to_string(), Some(json!({}))))
+                .collect(),
+        );
+        document.components = Some(Components {
+            content_descriptors,
+            schemas,
+            ..Components::default()
+        });
+
+        let admin = DocumentView::for_role("admin").apply(&document);
+        assert_eq!(admin.methods.len(), 2);
+        assert_eq!(
+            admin
+                .components
+                .as_ref()
+                .unwrap()
+                .schemas
+                .as_ref()
+                .unwrap()
+                .len(),
+            2
+        );
+
+        let user = DocumentView::for_role("user").apply(&document);
+        assert_eq!(user.methods.len(), 1);
+        let components = user.components.unwrap();
+        let mut schemas: Vec<_> = components.schemas.unwrap().into_keys().collect();
+        schemas.sort();
+        assert_eq!(schemas, ["Amount"]);
+        assert!(components
+            .content_descriptors
+            .unwrap()
+            .contains_key("Balance"));
+    }
+}